@@ -1,3 +1,12 @@
+use crate::api::{ApiConfig, HttpRequestSpec};
+use crate::build_system::BuildDetector;
+use crate::compare::Comparator;
+use crate::interactive::ExpectStep;
+use crate::parameters::TestParameters;
+use crate::web::PageStep;
+use crate::entry_point::EntryPoint;
+use crate::language::LanguageDetector;
+use crate::toolchain::ToolchainConfig;
 use reqwest::Client;
 use serde_json::{json, Value};
 
@@ -54,14 +63,18 @@ impl GroqService {
 
         println!("🤖 Chamando Groq AI para gerar testes...");
 
-        let prompt = self.build_comparison_prompt(legacy_code, new_code, project_info);
+        let (prompt, kind) = match project_info.project_type.as_str() {
+            "API" => (self.build_api_prompt(legacy_code, new_code, project_info), "HTTP APIs"),
+            "Web" => (self.build_web_prompt(legacy_code, new_code, project_info), "web applications"),
+            _ => (self.build_comparison_prompt(legacy_code, new_code, project_info), "terminal applications"),
+        };
 
         let payload = json!({
             "model": self.model,
             "messages": [
                 {
                     "role": "system",
-                    "content": "You are an expert software testing assistant. Generate comprehensive tests to validate behavioral compatibility between terminal applications. Always return valid JSON."
+                    "content": format!("You are an expert software testing assistant. Generate comprehensive tests to validate behavioral compatibility between {}. Always return valid JSON.", kind)
                 },
                 {
                     "role": "user",
@@ -125,6 +138,19 @@ PROJECT DETAILS:
 - Legacy Path: {}
 - New Path: {}
 
+DETECTED LANGUAGES (files, lines):
+- Legacy: {}
+- New: {}
+
+DETECTED BUILD SYSTEMS (prefer these build/run commands over single-file compilation):
+- Legacy: {}
+- New: {}
+
+PINNED ENTRY POINTS (name: path relative to the project root):
+- Legacy: {}
+- New: {}
+When the project has several entry points, set "entryPoint" on a test to one of these names; ENTRY_POINT then holds the ready-to-run program (compiled into BUILD_DIR when needed) and ENTRY_SOURCE its source file, e.g. "executeCommand": "\"${{ENTRY_POINT}}\"" or "python3 \"${{ENTRY_SOURCE}}\""
+
 TASK: Generate comprehensive tests to validate BEHAVIORAL COMPATIBILITY between the legacy and new versions.
 
 LEGACY APPLICATION CODE:
@@ -166,19 +192,20 @@ Return a JSON object with this structure:
       "fullCode": "Test inputs as command-line arguments (e.g., '2 + 3', '--input file.txt', 'username password')",
       "expectedExitCode": 0,
       "timeout": 30000,
+      "entryPoint": null,
       "legacyExec": {{
-        "type": "c_compiled|python|node|java|rust|go",
+        "type": "c_compiled|python|node|java|rust|go|cobol|fortran|pascal|perl",
         "sourceFile": "calculadora.c",
-        "compileCommand": "gcc -o calculadora calculadora.c -lm",
-        "executeCommand": "./calculadora",
-        "workingDirectory": "/path/to/legacy/project"
+        "compileCommand": "cd \"${{LEGACY_ROOT}}\" && gcc -o \"${{BUILD_DIR}}\"/calculadora calculadora.c -lm",
+        "executeCommand": "\"${{BUILD_DIR}}\"/calculadora",
+        "workingDirectory": "${{LEGACY_ROOT}}"
       }},
       "newExec": {{
-        "type": "c_compiled|python|node|java|rust|go",
+        "type": "c_compiled|python|node|java|rust|go|cobol|fortran|pascal|perl",
         "sourceFile": "calculadora.py",
         "compileCommand": null,
         "executeCommand": "python3 calculadora.py",
-        "workingDirectory": "/path/to/new/project"
+        "workingDirectory": "${{NEW_ROOT}}"
       }}
     }}
   ]
@@ -191,6 +218,10 @@ EXECUTION TYPES:
 - java: Java needs compilation (javac) then execution (java)
 - rust: Rust needs compilation (cargo build or rustc)
 - go: Go needs compilation (go build)
+- cobol: COBOL needs compilation (e.g., "cobc -x -o \"${{BUILD_DIR}}\"/prog prog.cbl")
+- fortran: Fortran needs compilation (gfortran)
+- pascal: Pascal needs compilation (fpc)
+- perl: Perl scripts run directly
 
 IMPORTANT: Analyze the code to determine:
 1. What language each project uses
@@ -198,16 +229,47 @@ IMPORTANT: Analyze the code to determine:
 3. What dependencies/libraries are used (like math.h → add -lm)
 4. The exact commands to compile and execute each program
 
+PATHS:
+- Never write the absolute project paths; use ${{LEGACY_ROOT}} for the legacy path and ${{NEW_ROOT}} for the new path
+- workingDirectory is ${{LEGACY_ROOT}} / ${{NEW_ROOT}} or a subdirectory (e.g., "${{LEGACY_ROOT}}/src")
+- sourceFile is relative to workingDirectory
+
 COMPILATION COMMANDS:
-- Always use FULL PATHS for source files
-- Use 'cd' command to set working directory first, then compile
-- Example: "cd /full/path/to/project && gcc -o calculadora calculadora.c -lm"
-- Or: "gcc -o /full/path/to/project/calculadora /full/path/to/project/calculadora.c -lm"
+- Never write build outputs inside the project directories; the environment variable BUILD_DIR points to a scratch directory for each version (CARGO_TARGET_DIR is also set)
+- Use 'cd' command to set working directory first, then compile into ${{BUILD_DIR}}
+- Paths may contain spaces (e.g., "Application Support" on macOS): always wrap ${{LEGACY_ROOT}}, ${{NEW_ROOT}}, ${{BUILD_DIR}}, ${{ENTRY_POINT}} and ${{ENTRY_SOURCE}} in double quotes
+- Example: "cd \"${{LEGACY_ROOT}}\" && gcc -o \"${{BUILD_DIR}}\"/calculadora calculadora.c -lm"
+- Java: "cd \"${{LEGACY_ROOT}}\" && javac -d \"${{BUILD_DIR}}\" Main.java", then execute "java -cp \"${{BUILD_DIR}}\" Main"
 
 EXECUTION COMMANDS:
-- Use relative paths when working directory is set
-- Example: "./calculadora" (when cd was used)
-- Or use full paths: "/full/path/to/project/calculadora"
+- Commands run inside workingDirectory; compiled programs are run from ${{BUILD_DIR}}
+- Example: "\"${{BUILD_DIR}}\"/calculadora"
+- Interpreted programs use relative paths: "python3 calculadora.py"
+
+PARAMETERIZED TESTS:
+- When the same scenario should run for many inputs (every operator, every edge value), write ONE test with "parameters" instead of copying it
+- Use {{{{name}}}} placeholders in args, stdin, fullCode or name; the test runs once per row
+- Example: "args": ["{{{{a}}}}", "{{{{op}}}}", "{{{{b}}}}"], "parameters": {{"rows": [{{"a": "2", "op": "+", "b": "3"}}, {{"a": "2", "op": "-", "b": "3"}}]}}
+- Rows may also come from "dataFile" (CSV with header or JSON array, relative to the legacy root) or "filesIn" (a fixtures folder; one row per file with {{{{file}}}} and {{{{fileName}}}})
+
+STRUCTURED OUTPUT:
+- When the program prints JSON, CSV or XML, add a "comparator" so formatting differences (key order, quoting, whitespace) are not reported as failures
+- {{"type": "json", "ignoreArrayOrder": false, "tolerance": 0.0}}
+- {{"type": "csv", "delimiter": ",", "header": true, "ignoreColumnOrder": true, "ignoreRowOrder": false, "tolerance": 0.0}}
+- {{"type": "xml", "preserveWhitespace": false}}
+- Only ignore array/row order when the order is not part of the expected behavior; use "tolerance" only for floating point results
+- Omit "comparator" for plain text output
+- Never generate {{"type": "external", ...}} comparators; they are configured by the user
+
+INTERACTIVE PROGRAMS:
+- If the program shows menus or prompts and reads answers interactively, add an "interactive" script instead of relying on arguments; it runs in a pseudo-terminal and the full transcript is compared
+- Steps: {{"action": "expect", "pattern": "Escolha uma opção"}}, {{"action": "sendLine", "text": "1"}}, {{"action": "send", "text": "y"}}, {{"action": "sendControl", "key": "c"}}
+- "pattern" is a regular expression; always expect the prompt before answering it, and end the script by choosing the exit option
+- Example: "interactive": [{{"action": "expect", "pattern": "Opção:"}}, {{"action": "sendLine", "text": "2"}}, {{"action": "expect", "pattern": "Opção:"}}, {{"action": "sendLine", "text": "0"}}]
+
+OUTPUT FILES:
+- If the program writes files as part of its behavior (reports, exports, databases), list them in "trackedFiles" relative to workingDirectory, e.g. "trackedFiles": ["out/relatorio.txt"]; created, modified and deleted files there are compared
+- Omit "trackedFiles" when only the printed output matters
 
 IMPORTANT: Provide accurate execution commands so the Rust system can compile, run and compare both versions automatically.
 
@@ -221,11 +283,197 @@ INPUT FORMAT REQUIREMENTS:
             project_info.name,
             project_info.legacy_path,
             project_info.new_path,
+            Self::describe_languages(&project_info.legacy_path),
+            Self::describe_languages(&project_info.new_path),
+            Self::describe_build_plans(&project_info.legacy_path),
+            Self::describe_build_plans(&project_info.new_path),
+            Self::describe_entry_points(&project_info.legacy_entry_points),
+            Self::describe_entry_points(&project_info.new_entry_points),
+            legacy_code,
+            new_code
+        )
+    }
+
+    /// Prompt para projetos do tipo API: os testes são requisições HTTP enviadas
+    /// aos dois servidores, sem comandos de execução
+    fn build_api_prompt(
+        &self,
+        legacy_code: &str,
+        new_code: &str,
+        project_info: &ProjectInfo
+    ) -> String {
+        format!(
+            r#"You are a software testing expert specializing in HTTP API compatibility testing.
+
+PROJECT DETAILS:
+- Name: {}
+- Type: HTTP API
+- Legacy Path: {}
+- New Path: {}
+
+DETECTED LANGUAGES (files, lines):
+- Legacy: {}
+- New: {}
+
+TASK: Generate HTTP requests that validate BEHAVIORAL COMPATIBILITY between the legacy and new services. Both services are started by the Rust system; every request is sent to both and the status codes, headers and bodies are compared (JSON bodies are compared structurally, ignoring key order).
+
+LEGACY SERVICE CODE:
+{}
+
+NEW SERVICE CODE:
+{}
+
+REQUIREMENTS:
+1. Generate 9 requests covering the routes found in the code
+2. Include success cases, validation errors, not-found routes and unsupported methods
+3. Use request bodies and headers (e.g., Content-Type: application/json) when the route expects them
+4. Requests must be independent of each other and safe to repeat
+
+RETURN FORMAT:
+Return a JSON object with this structure:
+{{
+  "tests": [
+    {{
+      "id": "unique-test-id",
+      "name": "Descriptive test name",
+      "description": "What this request validates",
+      "fullCode": "POST /users",
+      "expectedExitCode": 0,
+      "timeout": 10000,
+      "request": {{
+        "method": "POST",
+        "path": "/users?notify=false",
+        "headers": {{ "Content-Type": "application/json" }},
+        "body": "{{"name": "Ana"}}"
+      }}
+    }}
+  ]
+}}
+
+IMPORTANT:
+- "path" starts with '/' and may include a query string; never include host or port
+- "body" is a string (JSON bodies must be serialized) or null
+- Do not include legacyExec/newExec"#,
+            project_info.name,
+            project_info.legacy_path,
+            project_info.new_path,
+            Self::describe_languages(&project_info.legacy_path),
+            Self::describe_languages(&project_info.new_path),
+            legacy_code,
+            new_code
+        )
+    }
+
+    /// Prompt para projetos do tipo Web: cada teste é uma sequência de páginas
+    /// visitadas (e formulários enviados) nos dois apps
+    fn build_web_prompt(
+        &self,
+        legacy_code: &str,
+        new_code: &str,
+        project_info: &ProjectInfo
+    ) -> String {
+        format!(
+            r#"You are a software testing expert specializing in web application compatibility testing.
+
+PROJECT DETAILS:
+- Name: {}
+- Type: Web Application
+- Legacy Path: {}
+- New Path: {}
+
+DETECTED LANGUAGES (files, lines):
+- Legacy: {}
+- New: {}
+
+TASK: Find the routes, pages and forms in the code and generate page-visit scenarios that validate BEHAVIORAL COMPATIBILITY between the legacy and new apps. Both apps are started by the Rust system; every step is executed on both and the rendered pages are compared by normalized DOM structure and visible text (CSRF tokens, nonces and asset hashes are ignored). Cookies persist between the steps of a test, so login flows work.
+
+LEGACY APPLICATION CODE:
+{}
+
+NEW APPLICATION CODE:
+{}
+
+REQUIREMENTS:
+1. Generate 9 scenarios covering the main pages, navigation and forms
+2. Include form submissions with valid and invalid data, and a missing page (404)
+3. Hidden form fields from the previous step (e.g., CSRF tokens) are sent automatically; only list the visible fields
+4. Scenarios must be independent of each other
+
+RETURN FORMAT:
+Return a JSON object with this structure:
+{{
+  "tests": [
+    {{
+      "id": "unique-test-id",
+      "name": "Descriptive test name",
+      "description": "What this scenario validates",
+      "fullCode": "GET /login, POST /login",
+      "expectedExitCode": 0,
+      "timeout": 15000,
+      "pages": [
+        {{ "method": "GET", "path": "/login" }},
+        {{ "method": "POST", "path": "/login", "form": {{ "username": "ana", "password": "secret" }} }}
+      ]
+    }}
+  ]
+}}
+
+IMPORTANT:
+- "path" starts with '/' and may include a query string; never include host or port
+- "form" values are strings; omit "form" for plain page visits
+- Do not include legacyExec/newExec"#,
+            project_info.name,
+            project_info.legacy_path,
+            project_info.new_path,
+            Self::describe_languages(&project_info.legacy_path),
+            Self::describe_languages(&project_info.new_path),
             legacy_code,
             new_code
         )
     }
 
+    /// Linguagens detectadas em um lado, para orientar a IA
+    fn describe_languages(project_path: &str) -> String {
+        match LanguageDetector::summarize(std::path::Path::new(project_path)) {
+            Ok(stats) if !stats.is_empty() => stats.iter()
+                .map(|s| format!("{} ({} files, {} lines)", s.language, s.files, s.lines))
+                .collect::<Vec<_>>()
+                .join(", "),
+            _ => "unknown".to_string(),
+        }
+    }
+
+    /// Resumo dos planos de build detectados, para orientar a IA
+    fn describe_build_plans(project_path: &str) -> String {
+        let plans = BuildDetector::detect(std::path::Path::new(project_path));
+
+        if plans.is_empty() {
+            return "none".to_string();
+        }
+
+        plans.iter()
+            .map(|plan| format!(
+                "{} ({}): build `{}`, run `{}`",
+                plan.kind,
+                plan.manifest,
+                plan.build_command.as_deref().unwrap_or("-"),
+                plan.run_command
+            ))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    fn describe_entry_points(entry_points: &[EntryPoint]) -> String {
+        if entry_points.is_empty() {
+            return "none".to_string();
+        }
+
+        entry_points.iter()
+            .map(|entry| format!("{}: {}", entry.name, entry.path))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
     /// Extrai FullTestFromAI da resposta da API (com todos os metadados)
     async fn extract_full_tests_from_response(&self, response: Value) -> Result<Vec<FullTestFromAI>, String> {
         let content = response
@@ -276,6 +524,7 @@ INPUT FORMAT REQUIREMENTS:
 
 use serde::{Deserialize, Serialize};
 
+/// Projeto salvo em `projects.json` (mesmo formato de `Project` no frontend)
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ProjectInfo {
     pub id: String,
@@ -286,6 +535,32 @@ pub struct ProjectInfo {
     pub new_path: String,
     #[serde(rename = "type")]
     pub project_type: String,
+    #[serde(rename = "lastOpened", default, skip_serializing_if = "Option::is_none")]
+    pub last_opened: Option<String>,
+    /// Compiladores/interpretadores fixados para o legado
+    #[serde(rename = "legacyToolchain", default, skip_serializing_if = "Option::is_none")]
+    pub legacy_toolchain: Option<ToolchainConfig>,
+    /// Compiladores/interpretadores fixados para o novo
+    #[serde(rename = "newToolchain", default, skip_serializing_if = "Option::is_none")]
+    pub new_toolchain: Option<ToolchainConfig>,
+    /// Pontos de entrada fixados no legado (o primeiro é o padrão)
+    #[serde(rename = "legacyEntryPoints", default, skip_serializing_if = "Vec::is_empty")]
+    pub legacy_entry_points: Vec<EntryPoint>,
+    /// Pontos de entrada fixados no novo (o primeiro é o padrão)
+    #[serde(rename = "newEntryPoints", default, skip_serializing_if = "Vec::is_empty")]
+    pub new_entry_points: Vec<EntryPoint>,
+    /// Servidores de legado e novo (projetos do tipo "API")
+    #[serde(rename = "apiConfig", default, skip_serializing_if = "Option::is_none")]
+    pub api_config: Option<ApiConfig>,
+    /// Codificação das saídas do legado ("auto", "ISO-8859-1", "CP850"...)
+    #[serde(rename = "legacyEncoding", default, skip_serializing_if = "Option::is_none")]
+    pub legacy_encoding: Option<String>,
+    /// Codificação das saídas do novo
+    #[serde(rename = "newEncoding", default, skip_serializing_if = "Option::is_none")]
+    pub new_encoding: Option<String>,
+    /// Comparador usado pelos testes que não definem o próprio
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comparator: Option<Comparator>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -329,15 +604,51 @@ pub struct FullTestFromAI {
     #[serde(rename = "expectedExitCode")]
     pub expected_exit_code: i32,
     pub timeout: Option<u64>,
-
-    #[serde(rename = "legacyExec")]
+    /// Argumentos explícitos, passados literalmente (sem expansão do shell);
+    /// quando ausente, são extraídos de `fullCode`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub args: Option<Vec<String>>,
+    /// Entrada enviada ao stdin dos dois programas
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stdin: Option<String>,
+    /// Roteiro expect (esperar texto, enviar linha/tecla) para programas
+    /// interativos, executados em pseudo-terminal no lugar do `stdin`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interactive: Option<Vec<ExpectStep>>,
+    /// Tabela ou arquivo de dados: o teste roda uma vez por linha, com `{{nome}}`
+    /// trocado pelos valores
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<TestParameters>,
+    /// Como as saídas são comparadas (texto, JSON, CSV ou XML); padrão: texto
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comparator: Option<Comparator>,
+    /// Nome do ponto de entrada fixado que o teste exercita (exposto como `ENTRY_POINT`)
+    #[serde(rename = "entryPoint", default, skip_serializing_if = "Option::is_none")]
+    pub entry_point: Option<String>,
+    /// Requisição enviada aos dois servidores (projetos do tipo "API")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request: Option<HttpRequestSpec>,
+    /// Páginas visitadas em sequência nos dois apps (projetos do tipo "Web")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pages: Option<Vec<PageStep>>,
+    /// Arquivos ou diretórios (relativos ao diretório de trabalho) cujos efeitos
+    /// entram no veredito; sem isso só as saídas e o exit code são comparados
+    #[serde(rename = "trackedFiles", default, skip_serializing_if = "Option::is_none")]
+    pub tracked_files: Option<Vec<String>>,
+    /// "ai" nos testes gerados pela IA, que a próxima geração substitui; testes
+    /// escritos, duplicados ou derivados (fuzz, minimização) não têm origem e ficam
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+
+    /// Ausentes em testes de API e Web, que não executam comandos
+    #[serde(rename = "legacyExec", default)]
     pub legacy_exec: ExecutionInfo,
-    #[serde(rename = "newExec")]
+    #[serde(rename = "newExec", default)]
     pub new_exec: ExecutionInfo,
 }
 
 /// Informações de como executar cada projeto
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct ExecutionInfo {
     #[serde(rename = "type")]
     pub exec_type: String,
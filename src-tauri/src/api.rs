@@ -0,0 +1,481 @@
+use crate::ai::{FullTestFromAI, ProjectInfo, TestCase};
+use crate::build_cache::BuildCache;
+use crate::compare::{Comparator, OutputComparator};
+use crate::history::TestRunRecord;
+use crate::runner::TestRunner;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::net::TcpStream;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Cabeçalhos que mudam a cada resposta e não indicam diferença de comportamento
+const DEFAULT_IGNORED_HEADERS: [&str; 10] = [
+    "date", "server", "connection", "keep-alive", "transfer-encoding",
+    "content-length", "etag", "last-modified", "x-request-id", "x-powered-by",
+];
+
+/// Linhas do log do servidor incluídas nas mensagens de erro
+const LOG_TAIL_LINES: usize = 20;
+
+fn default_probe_path() -> String {
+    "/".to_string()
+}
+
+fn default_probe_timeout() -> u64 {
+    30000
+}
+
+/// Como saber que o servidor está pronto para receber requisições
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReadinessProbe {
+    #[serde(default = "default_probe_path")]
+    pub path: String,
+    #[serde(rename = "timeoutMs", default = "default_probe_timeout")]
+    pub timeout_ms: u64,
+    /// Status esperado; sem ele, qualquer resposta HTTP conta como pronto
+    #[serde(rename = "expectedStatus", default, skip_serializing_if = "Option::is_none")]
+    pub expected_status: Option<u16>,
+}
+
+impl Default for ReadinessProbe {
+    fn default() -> Self {
+        Self { path: default_probe_path(), timeout_ms: default_probe_timeout(), expected_status: None }
+    }
+}
+
+/// Como subir o servidor de um dos lados
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ServerConfig {
+    /// Comando executado com `sh -c` (recebe `PORT`, `BUILD_DIR` e o toolchain do lado)
+    pub command: String,
+    /// Diretório relativo à raiz do lado (padrão: a raiz)
+    #[serde(rename = "workingDirectory", default, skip_serializing_if = "Option::is_none")]
+    pub working_directory: Option<String>,
+    pub port: u16,
+    #[serde(default)]
+    pub readiness: ReadinessProbe,
+}
+
+/// Servidores de projetos do tipo "API" e "Web" (`apiConfig` em `projects.json`)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ApiConfig {
+    pub legacy: ServerConfig,
+    pub new: ServerConfig,
+    /// Cabeçalhos ignorados na comparação, além dos padrão (`date`, `server`...)
+    #[serde(rename = "ignoreHeaders", default, skip_serializing_if = "Vec::is_empty")]
+    pub ignore_headers: Vec<String>,
+    /// Atributos HTML ignorados na comparação de páginas (projetos Web)
+    #[serde(rename = "ignoreAttributes", default, skip_serializing_if = "Vec::is_empty")]
+    pub ignore_attributes: Vec<String>,
+}
+
+impl ApiConfig {
+    pub fn validate(&self, legacy_root: &str, new_root: &str) -> Result<(), String> {
+        for (server, root, label) in [(&self.legacy, legacy_root, "legado"), (&self.new, new_root, "novo")] {
+            if server.command.trim().is_empty() {
+                return Err(format!("Comando do servidor {} não pode ser vazio", label));
+            }
+
+            if server.port == 0 {
+                return Err(format!("Porta do servidor {} inválida", label));
+            }
+
+            if !server.readiness.path.starts_with('/') {
+                return Err(format!("Caminho de prontidão do {} deve começar com '/': {}", label, server.readiness.path));
+            }
+
+            let dir = ApiRunner::server_dir(server, root);
+            if !dir.is_dir() {
+                return Err(format!("Diretório do servidor {} não existe: {}", label, dir.display()));
+            }
+        }
+
+        if self.legacy.port == self.new.port {
+            return Err(format!("Legado e novo precisam de portas diferentes (ambos usam {})", self.legacy.port));
+        }
+
+        Ok(())
+    }
+}
+
+/// Requisição HTTP de um teste de API (`request` no teste)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HttpRequestSpec {
+    pub method: String,
+    /// Caminho com query string (ex.: "/users?page=2")
+    pub path: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+}
+
+/// Resposta capturada de um dos servidores
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HttpCapture {
+    pub status: u16,
+    /// Nomes em minúsculas; valores repetidos juntados com ", "
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+    #[serde(rename = "elapsedMs")]
+    pub elapsed_ms: u64,
+}
+
+impl HttpCapture {
+    /// Texto exibido na UI (status e corpo)
+    pub fn display_output(&self) -> String {
+        format!("HTTP {}\n\n{}", self.status, self.body)
+    }
+}
+
+/// Servidor em execução; é encerrado (com os processos filhos) ao sair de escopo
+pub struct ServerHandle {
+    child: Child,
+    base_url: String,
+    log_path: std::path::PathBuf,
+    label: String,
+}
+
+impl ServerHandle {
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Últimas linhas do log do servidor, para diagnosticar falhas
+    pub fn log_tail(&self) -> String {
+        let log = std::fs::read(&self.log_path).unwrap_or_default();
+        let log = String::from_utf8_lossy(&log);
+        let lines: Vec<&str> = log.lines().collect();
+        lines[lines.len().saturating_sub(LOG_TAIL_LINES)..].join("\n")
+    }
+}
+
+impl Drop for ServerHandle {
+    fn drop(&mut self) {
+        println!("🛑 Encerrando servidor {}", self.label);
+
+        // `sh -c` pode ter filhos (npm → node...); o grupo de processos inteiro é encerrado
+        #[cfg(unix)]
+        {
+            let _ = Command::new("kill")
+                .arg("-TERM")
+                .arg(format!("-{}", self.child.id()))
+                .stderr(Stdio::null())
+                .status();
+        }
+
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Sobe os servidores, envia as requisições dos testes e compara as respostas
+pub struct ApiRunner;
+
+impl ApiRunner {
+    /// Sobe os dois servidores, envia a requisição de cada teste aos dois e
+    /// compara as respostas. Os servidores são encerrados ao final.
+    pub async fn run_tests(
+        project: &ProjectInfo,
+        tests: &[FullTestFromAI],
+        legacy_cache: &BuildCache,
+        new_cache: &BuildCache
+    ) -> Result<Vec<TestRunRecord>, String> {
+        let config = project.api_config.as_ref()
+            .ok_or("Projeto do tipo API sem servidores configurados (apiConfig)")?;
+        let client = reqwest::Client::new();
+
+        let legacy_server = Self::start(&config.legacy, &project.legacy_path, legacy_cache, &client, "legado").await?;
+        let new_server = Self::start(&config.new, &project.new_path, new_cache, &client, "novo").await?;
+
+        let mut executed_tests = Vec::new();
+
+        for (i, test) in tests.iter().enumerate() {
+            println!("⚡ [{}/{}] Requisição: {}", i + 1, tests.len(), test.name);
+            let start_time = Instant::now();
+
+            let request = match &test.request {
+                Some(request) => request,
+                None => {
+                    executed_tests.push(TestRunRecord::not_run(test, "Teste sem requisição HTTP (request)"));
+                    continue;
+                }
+            };
+
+            let timeout = test.timeout.unwrap_or(30000);
+            let legacy_result = Self::send(&client, &legacy_server, request, timeout).await;
+            let new_result = Self::send(&client, &new_server, request, timeout).await;
+
+            let record = match (legacy_result, new_result) {
+                (Ok(legacy), Ok(new)) => {
+                    let differences = Self::compare(&legacy, &new, &config.ignore_headers, test.comparator.as_ref().or(project.comparator.as_ref()));
+                    for difference in &differences {
+                        println!("   ↳ {}", difference);
+                    }
+
+                    let success = differences.is_empty();
+                    println!("{} Teste {} - Status: {}", if success { "✅" } else { "❌" }, test.name, if success { "PASS" } else { "FAIL" });
+
+                    TestRunRecord {
+                        result: TestCase {
+                            id: test.id.clone(),
+                            name: test.name.clone(),
+                            description: test.description.clone(),
+                            full_code: test.full_code.clone(),
+                            status: if success { "passed" } else { "failed" }.to_string(),
+                            execution_time: Some(start_time.elapsed().as_millis() as u64),
+                            legacy_output: Some(legacy.display_output()),
+                            new_output: Some(new.display_output()),
+                        },
+                        legacy_http: Some(legacy),
+                        new_http: Some(new),
+                        differences,
+                        ..Default::default()
+                    }
+                },
+                (Err(legacy_error), Err(new_error)) => TestRunRecord::not_run(
+                    test,
+                    &format!("Erro no legado: {}\nErro no novo: {}", legacy_error, new_error)
+                ),
+                // Só um lado falhou na conexão: é uma divergência de comportamento
+                (Ok(legacy), Err(e)) => Self::transport_failure(test, start_time, Some(legacy), None, format!("servidor novo não respondeu: {}", e)),
+                (Err(e), Ok(new)) => Self::transport_failure(test, start_time, None, Some(new), format!("servidor legado não respondeu: {}", e)),
+            };
+
+            executed_tests.push(record);
+        }
+
+        Ok(executed_tests)
+    }
+
+    /// Registro de um teste em que só um dos servidores respondeu; o erro de
+    /// transporte é a diferença e aparece no lugar da saída do lado que falhou
+    fn transport_failure(
+        test: &FullTestFromAI,
+        start_time: Instant,
+        legacy: Option<HttpCapture>,
+        new: Option<HttpCapture>,
+        difference: String
+    ) -> TestRunRecord {
+        println!("   ↳ {}", difference);
+        println!("❌ Teste {} - Status: FAIL", test.name);
+
+        TestRunRecord {
+            result: TestCase {
+                id: test.id.clone(),
+                name: test.name.clone(),
+                description: test.description.clone(),
+                full_code: test.full_code.clone(),
+                status: "failed".to_string(),
+                execution_time: Some(start_time.elapsed().as_millis() as u64),
+                legacy_output: Some(legacy.as_ref().map(|c| c.display_output()).unwrap_or_else(|| difference.clone())),
+                new_output: Some(new.as_ref().map(|c| c.display_output()).unwrap_or_else(|| difference.clone())),
+            },
+            legacy_http: legacy,
+            new_http: new,
+            differences: vec![difference],
+            ..Default::default()
+        }
+    }
+
+    /// Inicia o servidor de um lado e espera a sonda de prontidão responder
+    pub async fn start(
+        server: &ServerConfig,
+        root: &str,
+        cache: &BuildCache,
+        client: &reqwest::Client,
+        label: &str
+    ) -> Result<ServerHandle, String> {
+        if TcpStream::connect(("127.0.0.1", server.port)).is_ok() {
+            return Err(format!("Porta {} do servidor {} já está em uso", server.port, label));
+        }
+
+        let dir = Self::server_dir(server, root);
+        let log_path = cache.dir().join("server.log");
+        let log = File::create(&log_path)
+            .map_err(|e| format!("Failed to create server log {:?}: {}", log_path, e))?;
+        let log_err = log.try_clone()
+            .map_err(|e| format!("Failed to create server log {:?}: {}", log_path, e))?;
+
+        println!("🌐 Iniciando servidor {}: {} (porta {})", label, server.command, server.port);
+
+        let mut command = Command::new("sh");
+        command.arg("-c")
+            .arg(&server.command)
+            .current_dir(&dir)
+            .envs(cache.env())
+            .env("PORT", server.port.to_string())
+            .stdin(Stdio::null())
+            .stdout(log)
+            .stderr(log_err);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            command.process_group(0);
+        }
+
+        let child = command.spawn()
+            .map_err(|e| format!("Erro ao iniciar servidor {}: {}", label, e))?;
+
+        let mut handle = ServerHandle {
+            child,
+            base_url: format!("http://127.0.0.1:{}", server.port),
+            log_path,
+            label: label.to_string(),
+        };
+
+        let probe_url = format!("{}{}", handle.base_url, server.readiness.path);
+        let deadline = Instant::now() + Duration::from_millis(server.readiness.timeout_ms);
+
+        loop {
+            if let Ok(Some(status)) = handle.child.try_wait() {
+                return Err(format!(
+                    "Servidor {} terminou antes de ficar pronto ({})\n{}",
+                    label, status, handle.log_tail()
+                ));
+            }
+
+            if let Ok(response) = client.get(&probe_url).timeout(Duration::from_secs(2)).send().await {
+                let status = response.status().as_u16();
+                if server.readiness.expected_status.is_none_or(|expected| expected == status) {
+                    println!("✅ Servidor {} pronto em {}", label, handle.base_url);
+                    return Ok(handle);
+                }
+            }
+
+            if Instant::now() >= deadline {
+                return Err(format!(
+                    "Servidor {} não ficou pronto em {}ms ({})\n{}",
+                    label, server.readiness.timeout_ms, probe_url, handle.log_tail()
+                ));
+            }
+
+            tokio::time::sleep(Duration::from_millis(250)).await;
+        }
+    }
+
+    /// Envia a requisição do teste a um servidor
+    pub async fn send(
+        client: &reqwest::Client,
+        server: &ServerHandle,
+        request: &HttpRequestSpec,
+        timeout_ms: u64
+    ) -> Result<HttpCapture, String> {
+        let method = reqwest::Method::from_bytes(request.method.to_uppercase().as_bytes())
+            .map_err(|_| format!("Método HTTP inválido: {}", request.method))?;
+        let url = format!("{}{}", server.base_url(), request.path);
+
+        let mut builder = client.request(method, &url).timeout(Duration::from_millis(timeout_ms));
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(body) = &request.body {
+            builder = builder.body(body.clone());
+        }
+
+        let start = Instant::now();
+        let response = builder.send().await
+            .map_err(|e| format!("Erro na requisição {} {}: {}", request.method, url, e))?;
+
+        let status = response.status().as_u16();
+        let mut headers: BTreeMap<String, String> = BTreeMap::new();
+        for (name, value) in response.headers() {
+            let value = String::from_utf8_lossy(value.as_bytes()).to_string();
+            headers.entry(name.as_str().to_lowercase())
+                .and_modify(|existing| {
+                    existing.push_str(", ");
+                    existing.push_str(&value);
+                })
+                .or_insert(value);
+        }
+
+        let body = response.bytes().await
+            .map_err(|e| format!("Erro lendo resposta de {}: {}", url, e))?;
+
+        Ok(HttpCapture {
+            status,
+            headers,
+            body: String::from_utf8_lossy(&body).to_string(),
+            elapsed_ms: start.elapsed().as_millis() as u64,
+        })
+    }
+
+    /// Diferenças entre as respostas: status, cabeçalhos (menos os ignorados) e
+    /// corpo, comparado com o comparador do teste ou como JSON quando os dois lados são JSON
+    pub fn compare(
+        legacy: &HttpCapture,
+        new: &HttpCapture,
+        ignore_headers: &[String],
+        comparator: Option<&Comparator>
+    ) -> Vec<String> {
+        let mut differences = Vec::new();
+
+        if legacy.status != new.status {
+            differences.push(format!("status: {} != {}", legacy.status, new.status));
+        }
+
+        let ignored: BTreeSet<String> = DEFAULT_IGNORED_HEADERS.iter()
+            .map(|h| h.to_string())
+            .chain(ignore_headers.iter().map(|h| h.to_lowercase()))
+            .collect();
+        let names: BTreeSet<&String> = legacy.headers.keys().chain(new.headers.keys())
+            .filter(|name| !ignored.contains(*name))
+            .collect();
+
+        for name in names {
+            let legacy_value = legacy.headers.get(name).map(|v| Self::normalize_header(name, v));
+            let new_value = new.headers.get(name).map(|v| Self::normalize_header(name, v));
+
+            if legacy_value != new_value {
+                differences.push(format!(
+                    "header {}: {} != {}",
+                    name,
+                    legacy_value.as_deref().unwrap_or("(ausente)"),
+                    new_value.as_deref().unwrap_or("(ausente)")
+                ));
+            }
+        }
+
+        if let Some(comparator) = comparator {
+            differences.extend(OutputComparator::compare_text(comparator, &legacy.body, &new.body)
+                .iter()
+                .map(|d| d.describe()));
+            return differences;
+        }
+
+        match (serde_json::from_str::<Value>(&legacy.body), serde_json::from_str::<Value>(&new.body)) {
+            (Ok(legacy_json), Ok(new_json)) => {
+                let mut body_differences = Vec::new();
+                OutputComparator::json_differences("$", &legacy_json, &new_json, false, 0.0, &mut body_differences);
+                differences.extend(body_differences.iter().map(|d| d.describe()));
+            },
+            _ if !TestRunner::outputs_match(&legacy.body, &new.body) => {
+                differences.push("corpo: conteúdo diferente".to_string());
+            },
+            _ => {}
+        }
+
+        differences
+    }
+
+    pub fn server_dir(server: &ServerConfig, root: &str) -> std::path::PathBuf {
+        match &server.working_directory {
+            Some(dir) => Path::new(root).join(dir),
+            None => Path::new(root).to_path_buf(),
+        }
+    }
+
+    /// `content-type` varia em espaços e caixa do charset entre frameworks
+    fn normalize_header(name: &str, value: &str) -> String {
+        if name == "content-type" {
+            value.to_lowercase().replace(' ', "")
+        } else {
+            value.to_string()
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use crate::ai::ProjectInfo;
+use crate::capture::OutputCapture;
+use crate::project::ProjectService;
+use crate::settings::SettingsService;
+use crate::storage::{Storage, CURRENT_SCHEMA_VERSION};
+use crate::toolchain::ToolchainConfig;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Artefato de compilação gerado a partir de um arquivo-fonte do projeto
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BuildArtifact {
+    /// "legacy" ou "new"
+    pub side: String,
+    pub source: String,
+    /// Binário gerado (ou diretório de classes, no caso de Java)
+    pub artifact: String,
+    /// sha256 do fonte (e do toolchain) usado na compilação; `None` quando a ferramenta de build
+    /// cuida da recompilação incremental (ex.: cargo)
+    #[serde(rename = "sourceHash")]
+    pub source_hash: Option<String>,
+    #[serde(rename = "builtAt")]
+    pub built_at: String,
+}
+
+/// Conteúdo de `build/<lado>/artifacts.json`
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct ArtifactIndex {
+    #[serde(rename = "schemaVersion", default)]
+    schema_version: u32,
+    artifacts: Vec<BuildArtifact>,
+}
+
+/// Diretório de build de um lado do projeto em app data (`projects/<id>/build/<lado>`),
+/// para que a compilação não deixe binários nos repositórios sendo migrados.
+/// Também aplica o toolchain fixado do lado (ver `ToolchainConfig`).
+#[derive(Clone)]
+pub struct BuildCache {
+    side: String,
+    dir: PathBuf,
+    toolchain: ToolchainConfig,
+    /// PATH com os shims do toolchain, quando o lado tem toolchain configurado
+    path: Option<OsString>,
+    /// Fonte e programa pronto do ponto de entrada escolhido pelo teste
+    entry_point: Option<(String, String)>,
+    /// Codificação das saídas do lado (`None` detecta)
+    encoding: Option<String>,
+    /// Limites e destino das saídas grandes
+    capture: OutputCapture,
+    /// Fixtures do projeto (`projects/<id>/fixtures`), que sobrevivem à limpeza do build
+    fixtures_dir: PathBuf,
+}
+
+impl BuildCache {
+    pub fn for_side(project: &ProjectInfo, side: &str, app: &tauri::AppHandle) -> Result<Self, String> {
+        let dir = Self::build_root(&project.id, app)?.join(side);
+
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create build directory {:?}: {}", dir, e))?;
+
+        let toolchain = ToolchainConfig::for_side(project, side);
+        let path = if toolchain.is_empty() {
+            None
+        } else {
+            Some(toolchain.install_shims(&dir.join("toolchain-bin"))?)
+        };
+
+        let encoding = if side == "legacy" { project.legacy_encoding.clone() } else { project.new_encoding.clone() };
+
+        // Fora de uma execução (ex.: snapshots), saídas grandes ficam no diretório de build
+        let capture = OutputCapture::new(&dir.join("outputs"), &SettingsService::load(app)?);
+
+        let fixtures_dir = Self::fixtures_root(&project.id, app)?;
+
+        Ok(Self { side: side.to_string(), dir, toolchain, path, entry_point: None, encoding, capture, fixtures_dir })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn fixtures_dir(&self) -> &Path {
+        &self.fixtures_dir
+    }
+
+    /// Diretório das fixtures de um projeto (ex.: as reduzidas pela minimização)
+    pub fn fixtures_root(project_id: &str, app: &tauri::AppHandle) -> Result<PathBuf, String> {
+        Ok(ProjectService::project_data_dir(project_id, app)?.join("fixtures"))
+    }
+
+    pub fn encoding(&self) -> Option<&str> {
+        self.encoding.as_deref()
+    }
+
+    pub fn capture(&self) -> &OutputCapture {
+        &self.capture
+    }
+
+    /// Mesmo ambiente, gravando as saídas grandes em outro diretório (o da execução)
+    pub fn with_capture_dir(&self, dir: &Path) -> Self {
+        Self { capture: self.capture.with_dir(dir), ..self.clone() }
+    }
+
+    /// Mesmo ambiente, expondo também um ponto de entrada (`ENTRY_SOURCE`/`ENTRY_POINT`)
+    pub fn with_entry_point(&self, source: &str, executable: &str) -> Self {
+        Self { entry_point: Some((source.to_string(), executable.to_string())), ..self.clone() }
+    }
+
+    /// Variáveis de ambiente passadas aos comandos de compilação e execução dos
+    /// testes: `BUILD_DIR` para saídas explícitas (`-o ${BUILD_DIR}/prog`),
+    /// `CARGO_TARGET_DIR`, que o cargo já respeita sozinho, `FIXTURES_DIR` e o PATH do toolchain
+    pub fn env(&self) -> Vec<(&'static str, OsString)> {
+        let mut env = vec![
+            ("BUILD_DIR", self.dir.clone().into_os_string()),
+            ("CARGO_TARGET_DIR", self.dir.join("cargo-target").into_os_string()),
+            ("FIXTURES_DIR", self.fixtures_dir.clone().into_os_string()),
+        ];
+
+        if let Some(path) = &self.path {
+            env.push(("PATH", path.clone()));
+        }
+
+        if let Some((source, executable)) = &self.entry_point {
+            env.push(("ENTRY_SOURCE", OsString::from(source)));
+            env.push(("ENTRY_POINT", OsString::from(executable)));
+        }
+
+        env
+    }
+
+    /// Comando para chamar uma ferramenta com o toolchain do lado (path fixado,
+    /// flags configuradas e ambiente de build)
+    pub fn command(&self, tool: &str) -> Command {
+        let program = self.toolchain.resolve(tool)
+            .map(|p| p.into_os_string())
+            .unwrap_or_else(|| OsString::from(tool));
+
+        let mut command = Command::new(program);
+        command.args(self.toolchain.flags(tool)).envs(self.env());
+        command
+    }
+
+    /// Caminho de saída para o artefato de um fonte. Inclui um hash do caminho do
+    /// fonte para que `a/main.c` e `b/main.c` não se sobrescrevam.
+    pub fn artifact_path(&self, source_path: &str) -> PathBuf {
+        let stem = Path::new(source_path).file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "program".to_string());
+        let path_hash = format!("{:x}", Sha256::digest(source_path.as_bytes()));
+
+        self.dir.join(format!("{}-{}", stem, &path_hash[..8]))
+    }
+
+    /// Artefato já compilado a partir do mesmo conteúdo do fonte, se ainda existir
+    pub fn reusable(&self, source_path: &str) -> Option<String> {
+        let hash = self.source_hash(source_path)?;
+        let entry = self.load_index().artifacts.into_iter()
+            .find(|a| a.source == source_path && a.source_hash.as_deref() == Some(hash.as_str()))?;
+
+        if !Path::new(&entry.artifact).exists() {
+            return None;
+        }
+
+        println!("♻️ Reutilizando artefato de {}: {}", source_path, entry.artifact);
+        Some(entry.artifact)
+    }
+
+    /// Registra o artefato gerado; `hash_source` indica se vale reaproveitá-lo
+    /// enquanto o fonte não mudar
+    pub fn record(&self, source_path: &str, artifact: &str, hash_source: bool) -> Result<(), String> {
+        let mut index = self.load_index();
+        index.artifacts.retain(|a| a.source != source_path);
+        index.artifacts.push(BuildArtifact {
+            side: self.side.clone(),
+            source: source_path.to_string(),
+            artifact: artifact.to_string(),
+            source_hash: if hash_source { self.source_hash(source_path) } else { None },
+            built_at: chrono::Utc::now().to_rfc3339(),
+        });
+        index.schema_version = CURRENT_SCHEMA_VERSION;
+
+        Storage::write_json_atomic(&self.dir.join("artifacts.json"), &index)
+    }
+
+    /// Lista os artefatos registrados dos dois lados do projeto
+    pub fn list(project_id: &str, app: &tauri::AppHandle) -> Result<Vec<BuildArtifact>, String> {
+        let build_root = Self::build_root(project_id, app)?;
+        let mut artifacts = Vec::new();
+
+        for side in ["legacy", "new"] {
+            let index: ArtifactIndex = Storage::read_json(&build_root.join(side).join("artifacts.json"))?
+                .unwrap_or_default();
+            artifacts.extend(index.artifacts);
+        }
+
+        Ok(artifacts)
+    }
+
+    /// Remove todo o diretório de build do projeto; retorna os bytes liberados
+    pub fn clean(project_id: &str, app: &tauri::AppHandle) -> Result<u64, String> {
+        let build_root = Self::build_root(project_id, app)?;
+
+        if !build_root.exists() {
+            return Ok(0);
+        }
+
+        let size: u64 = walkdir::WalkDir::new(&build_root)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter_map(|e| e.metadata().ok())
+            .filter(|m| m.is_file())
+            .map(|m| m.len())
+            .sum();
+
+        fs::remove_dir_all(&build_root)
+            .map_err(|e| format!("Failed to remove build directory: {}", e))?;
+
+        println!("🗑️ Artefatos de build removidos do projeto {} ({} bytes)", project_id, size);
+        Ok(size)
+    }
+
+    fn build_root(project_id: &str, app: &tauri::AppHandle) -> Result<PathBuf, String> {
+        Ok(ProjectService::project_data_dir(project_id, app)?.join("build"))
+    }
+
+    fn load_index(&self) -> ArtifactIndex {
+        Storage::read_json(&self.dir.join("artifacts.json"))
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+    }
+
+    /// sha256 do fonte; inclui o toolchain do lado para que trocar o compilador
+    /// ou as flags invalide o artefato
+    fn source_hash(&self, path: &str) -> Option<String> {
+        let data = fs::read(path).ok()?;
+        let mut hasher = Sha256::new();
+        hasher.update(&data);
+        if !self.toolchain.is_empty() {
+            hasher.update(serde_json::to_vec(&self.toolchain).ok()?);
+        }
+        Some(format!("{:x}", hasher.finalize()))
+    }
+}
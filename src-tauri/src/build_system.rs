@@ -0,0 +1,483 @@
+use crate::ai::ExecutionInfo;
+use crate::paths::shell_quote;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Como compilar e executar um programa a partir do sistema de build do projeto
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BuildPlan {
+    /// "make", "cmake", "cargo", "maven", "gradle", "npm", "python" ou "go"
+    pub kind: String,
+    /// Arquivo de onde o plano foi extraído, relativo à raiz do projeto
+    pub manifest: String,
+    /// Nome do binário/script/target
+    pub name: String,
+    #[serde(rename = "buildCommand")]
+    pub build_command: Option<String>,
+    /// Comando de execução, rodado na raiz do projeto; os argumentos do teste vão no final.
+    /// `${BUILD_DIR}`/`${CARGO_TARGET_DIR}` são definidos pelo runner (ver `BuildCache::env`).
+    /// Sistemas que não aceitam redirecionar a saída (make, maven, gradle, npm) compilam
+    /// uma cópia do projeto em `${BUILD_DIR}/<kind>`, e o programa é executado de lá.
+    #[serde(rename = "runCommand")]
+    pub run_command: String,
+}
+
+impl BuildPlan {
+    fn new(kind: &str, manifest: &str, name: &str, build_command: Option<String>, run_command: String) -> Self {
+        Self {
+            kind: kind.to_string(),
+            manifest: manifest.to_string(),
+            name: name.to_string(),
+            build_command,
+            run_command,
+        }
+    }
+
+    /// Bloco de execução equivalente, para substituir o sugerido pela IA.
+    /// `root` é o diretório raiz do projeto.
+    pub fn to_exec_info(&self, root: &str) -> ExecutionInfo {
+        ExecutionInfo {
+            exec_type: self.kind.clone(),
+            source_file: self.manifest.clone(),
+            compile_command: self.build_command.as_ref().map(|build| format!("cd {} && {}", shell_quote(root), build)),
+            execute_command: self.run_command.clone(),
+            working_directory: root.to_string(),
+        }
+    }
+}
+
+/// Detecção de sistemas de build na raiz de um projeto
+pub struct BuildDetector;
+
+impl BuildDetector {
+    /// Lista os planos de build/execução encontrados na raiz do projeto, na ordem
+    /// em que são preferidos
+    pub fn detect(project_root: &Path) -> Vec<BuildPlan> {
+        let mut plans = Vec::new();
+
+        plans.extend(Self::detect_cargo(project_root));
+        plans.extend(Self::detect_go(project_root));
+        plans.extend(Self::detect_cmake(project_root));
+        plans.extend(Self::detect_make(project_root));
+        plans.extend(Self::detect_maven(project_root));
+        plans.extend(Self::detect_gradle(project_root));
+        plans.extend(Self::detect_npm(project_root));
+        plans.extend(Self::detect_python(project_root));
+
+        plans
+    }
+
+    /// Compila numa cópia do projeto em `${BUILD_DIR}/<kind>`, para que `target/`,
+    /// `build/`, `node_modules/` e os objetos do make não sujem a árvore de fontes
+    fn isolated(kind: &str, build: &str) -> String {
+        format!(
+            "mkdir -p \"${{BUILD_DIR}}/{0}\" && cp -Rp ./. \"${{BUILD_DIR}}/{0}/\" && cd \"${{BUILD_DIR}}/{0}\" && {1}",
+            kind, build
+        )
+    }
+
+    /// Avisa quando um bloco de execução ignora o sistema de build detectado
+    /// (ex.: compila com `gcc` um projeto que tem Makefile)
+    pub fn check_exec(exec: &ExecutionInfo, plans: &[BuildPlan]) -> Option<String> {
+        if plans.is_empty() {
+            return None;
+        }
+
+        let commands = format!("{} {}", exec.compile_command.as_deref().unwrap_or(""), exec.execute_command);
+        let uses_plan = plans.iter().any(|plan| {
+            // Nos builds isolados o comando da ferramenta é o último da cadeia
+            let tool = plan.build_command.as_deref()
+                .and_then(|build| build.rsplit("&&").next())
+                .unwrap_or(&plan.run_command)
+                .split_whitespace()
+                .next()
+                .unwrap_or("");
+
+            commands.contains(&plan.run_command) || (!tool.is_empty() && commands.split_whitespace().any(|t| t == tool))
+        });
+
+        if uses_plan {
+            return None;
+        }
+
+        let suggested = &plans[0];
+        Some(format!(
+            "o projeto usa {} ({}), mas o teste executa '{}'; sugerido: {}{}",
+            suggested.kind,
+            suggested.manifest,
+            exec.execute_command,
+            suggested.build_command.as_ref().map(|b| format!("{} && ", b)).unwrap_or_default(),
+            suggested.run_command
+        ))
+    }
+
+    /// Nomes dos binários de um pacote Cargo: os `[[bin]]` declarados, ou o pacote
+    /// (com `src/main.rs`) e os de `src/bin`
+    pub fn cargo_binaries(crate_dir: &Path) -> Vec<String> {
+        let manifest: toml::Value = match Self::read_toml(&crate_dir.join("Cargo.toml")) {
+            Some(manifest) => manifest,
+            None => return Vec::new(),
+        };
+
+        let bins: Vec<String> = manifest.get("bin")
+            .and_then(|b| b.as_array())
+            .map(|bins| bins.iter()
+                .filter_map(|b| b.get("name").and_then(|n| n.as_str()))
+                .map(|n| n.to_string())
+                .collect())
+            .unwrap_or_default();
+
+        if !bins.is_empty() {
+            return bins;
+        }
+
+        let package_name = manifest.get("package")
+            .and_then(|p| p.get("name"))
+            .and_then(|n| n.as_str());
+
+        let mut binaries: Vec<String> = match package_name {
+            Some(name) if crate_dir.join("src").join("main.rs").exists() => vec![name.to_string()],
+            _ => Vec::new(),
+        };
+
+        // Binários descobertos automaticamente em `src/bin/<nome>.rs` e `src/bin/<nome>/main.rs`
+        let mut discovered: Vec<String> = fs::read_dir(crate_dir.join("src").join("bin"))
+            .map(|entries| entries.filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter_map(|p| match p.extension() {
+                    Some(ext) if ext == "rs" => p.file_stem().map(|s| s.to_string_lossy().to_string()),
+                    None if p.join("main.rs").is_file() => p.file_name().map(|s| s.to_string_lossy().to_string()),
+                    _ => None,
+                })
+                .collect())
+            .unwrap_or_default();
+        discovered.sort();
+        binaries.extend(discovered);
+
+        binaries
+    }
+
+    /// Binário do pacote Cargo que compila um fonte: `[[bin]]` com esse `path`,
+    /// `src/main.rs` → nome do pacote, `src/bin/<nome>.rs` (ou `<nome>/main.rs`) → `<nome>`.
+    /// Outros arquivos só valem quando o pacote tem um único binário.
+    pub fn cargo_binary_for(crate_dir: &Path, source: &Path) -> Result<String, String> {
+        let relative = source.strip_prefix(crate_dir).unwrap_or(source)
+            .to_string_lossy()
+            .replace('\\', "/");
+        let manifest = Self::read_toml(&crate_dir.join("Cargo.toml"));
+
+        let declared = manifest.as_ref()
+            .and_then(|m| m.get("bin"))
+            .and_then(|b| b.as_array())
+            .and_then(|bins| bins.iter().find(|b| {
+                b.get("path").and_then(|p| p.as_str())
+                    .is_some_and(|p| p.trim_start_matches("./") == relative)
+            }))
+            .and_then(|b| b.get("name").and_then(|n| n.as_str()));
+        if let Some(name) = declared {
+            return Ok(name.to_string());
+        }
+
+        if relative == "src/main.rs" {
+            if let Some(name) = manifest.as_ref().and_then(|m| m.get("package")).and_then(|p| p.get("name")).and_then(|n| n.as_str()) {
+                return Ok(name.to_string());
+            }
+        }
+
+        if let Some(rest) = relative.strip_prefix("src/bin/") {
+            let name = rest.strip_suffix("/main.rs").or_else(|| rest.strip_suffix(".rs"));
+            if let Some(name) = name.filter(|n| !n.contains('/')) {
+                return Ok(name.to_string());
+            }
+        }
+
+        let binaries = Self::cargo_binaries(crate_dir);
+        match binaries.as_slice() {
+            [only] => Ok(only.clone()),
+            [] => Err(format!("Cargo.toml em {} não declara nenhum binário", crate_dir.display())),
+            _ => Err(format!(
+                "Não é possível saber qual binário de {} compila {} (binários: {}); aponte o teste para src/main.rs ou src/bin/<nome>.rs",
+                crate_dir.display(), relative, binaries.join(", ")
+            )),
+        }
+    }
+
+    fn detect_cargo(root: &Path) -> Vec<BuildPlan> {
+        Self::cargo_binaries(root).into_iter()
+            .map(|name| BuildPlan::new(
+                "cargo",
+                "Cargo.toml",
+                &name,
+                Some(format!("cargo build --release --bin {}", name)),
+                format!("\"${{CARGO_TARGET_DIR}}\"/release/{}", name),
+            ))
+            .collect()
+    }
+
+    fn detect_go(root: &Path) -> Vec<BuildPlan> {
+        let go_mod = match fs::read_to_string(root.join("go.mod")) {
+            Ok(content) => content,
+            Err(_) => return Vec::new(),
+        };
+
+        // Vários comandos em `cmd/<nome>`; senão o pacote main fica na raiz
+        let mut commands: Vec<String> = fs::read_dir(root.join("cmd"))
+            .map(|entries| entries.filter_map(|e| e.ok())
+                .filter(|e| e.path().is_dir())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect())
+            .unwrap_or_default();
+        commands.sort();
+
+        if !commands.is_empty() {
+            return commands.iter()
+                .map(|name| BuildPlan::new(
+                    "go",
+                    "go.mod",
+                    name,
+                    Some(format!("go build -o \"${{BUILD_DIR}}\"/{} ./cmd/{}", name, name)),
+                    format!("\"${{BUILD_DIR}}\"/{}", name),
+                ))
+                .collect();
+        }
+
+        let module_name = go_mod.lines()
+            .find_map(|line| line.trim().strip_prefix("module "))
+            .and_then(|module| module.trim().rsplit('/').next())
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| Self::dir_name(root));
+
+        vec![BuildPlan::new(
+            "go",
+            "go.mod",
+            &module_name,
+            Some(format!("go build -o \"${{BUILD_DIR}}\"/{} .", module_name)),
+            format!("\"${{BUILD_DIR}}\"/{}", module_name),
+        )]
+    }
+
+    fn detect_cmake(root: &Path) -> Vec<BuildPlan> {
+        let content = match fs::read_to_string(root.join("CMakeLists.txt")) {
+            Ok(content) => content,
+            Err(_) => return Vec::new(),
+        };
+
+        let re = Regex::new(r"(?i)add_executable\s*\(\s*([A-Za-z0-9_.+-]+)").unwrap();
+
+        re.captures_iter(&content)
+            .map(|caps| caps[1].to_string())
+            .filter(|name| !name.starts_with("${"))
+            .map(|name| BuildPlan::new(
+                "cmake",
+                "CMakeLists.txt",
+                &name,
+                Some(format!("cmake -S . -B \"${{BUILD_DIR}}/cmake\" && cmake --build \"${{BUILD_DIR}}/cmake\" --target {}", name)),
+                format!("\"${{BUILD_DIR}}\"/cmake/{}", name),
+            ))
+            .collect()
+    }
+
+    fn detect_make(root: &Path) -> Vec<BuildPlan> {
+        let makefile = ["GNUmakefile", "makefile", "Makefile"].iter()
+            .find(|name| root.join(name).is_file());
+        let makefile = match makefile {
+            Some(makefile) => *makefile,
+            None => return Vec::new(),
+        };
+
+        let content = fs::read_to_string(root.join(makefile)).unwrap_or_default();
+        let target_re = Regex::new(r"^([A-Za-z0-9_./-]+)\s*:([^=]|$)").unwrap();
+        let targets: Vec<String> = content.lines()
+            .filter_map(|line| target_re.captures(line).map(|caps| caps[1].to_string()))
+            .collect();
+
+        // Primeiro target que parece um binário; `make run` só quando não há nenhum
+        // (os argumentos do teste não chegam ao programa nesse caso)
+        const NON_BINARY: [&str; 9] = ["all", "clean", "install", "test", "check", "dist", "distclean", "uninstall", "run"];
+        let binary = targets.iter()
+            .find(|t| !t.starts_with('.') && !t.contains('%') && !t.ends_with(".o") && !NON_BINARY.contains(&t.as_str()));
+
+        match binary {
+            Some(binary) => vec![BuildPlan::new(
+                "make",
+                makefile,
+                binary,
+                Some(Self::isolated("make", "make")),
+                format!("\"${{BUILD_DIR}}\"/make/{}", binary.trim_start_matches("./")),
+            )],
+            None if targets.iter().any(|t| t == "run") => {
+                vec![BuildPlan::new(
+                    "make",
+                    makefile,
+                    "run",
+                    Some(Self::isolated("make", "make")),
+                    "make -s -C \"${BUILD_DIR}/make\" run".to_string(),
+                )]
+            },
+            None => Vec::new(),
+        }
+    }
+
+    fn detect_maven(root: &Path) -> Vec<BuildPlan> {
+        let content = match fs::read_to_string(root.join("pom.xml")) {
+            Ok(content) => content,
+            Err(_) => return Vec::new(),
+        };
+
+        // Ignora os blocos que também declaram artifactId/version
+        let nested = Regex::new(r"(?s)<(parent|dependencies|dependencyManagement|build|plugins|profiles)>.*?</(parent|dependencies|dependencyManagement|build|plugins|profiles)>").unwrap();
+        let own = nested.replace_all(&content, "");
+
+        let tag = |name: &str| Regex::new(&format!(r"<{0}>\s*([^<\s]+)\s*</{0}>", name)).unwrap()
+            .captures(&own)
+            .map(|caps| caps[1].to_string());
+
+        let artifact_id = tag("artifactId").unwrap_or_else(|| Self::dir_name(root));
+        let version = tag("version");
+        let jar = match version {
+            Some(version) => format!("target/{}-{}.jar", artifact_id, version),
+            None => format!("target/{}.jar", artifact_id),
+        };
+
+        vec![BuildPlan::new(
+            "maven",
+            "pom.xml",
+            &artifact_id,
+            Some(Self::isolated("maven", "mvn -q -DskipTests package")),
+            format!("java -jar \"${{BUILD_DIR}}\"/maven/{}", jar),
+        )]
+    }
+
+    fn detect_gradle(root: &Path) -> Vec<BuildPlan> {
+        let manifest = ["build.gradle.kts", "build.gradle"].iter()
+            .find(|name| root.join(name).is_file());
+        let manifest = match manifest {
+            Some(manifest) => *manifest,
+            None => return Vec::new(),
+        };
+
+        let settings = ["settings.gradle.kts", "settings.gradle"].iter()
+            .filter_map(|name| fs::read_to_string(root.join(name)).ok())
+            .next()
+            .unwrap_or_default();
+        let name = Regex::new(r#"rootProject\.name\s*=\s*["']([^"']+)["']"#).unwrap()
+            .captures(&settings)
+            .map(|caps| caps[1].to_string())
+            .unwrap_or_else(|| Self::dir_name(root));
+
+        let gradle = if root.join("gradlew").is_file() { "./gradlew" } else { "gradle" };
+
+        vec![BuildPlan::new(
+            "gradle",
+            manifest,
+            &name,
+            Some(Self::isolated("gradle", &format!("{} -q installDist", gradle))),
+            format!("\"${{BUILD_DIR}}\"/gradle/build/install/{0}/bin/{0}", name),
+        )]
+    }
+
+    fn detect_npm(root: &Path) -> Vec<BuildPlan> {
+        let package: serde_json::Value = match fs::read_to_string(root.join("package.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok()) {
+            Some(package) => package,
+            None => return Vec::new(),
+        };
+
+        let package_name = package.get("name").and_then(|n| n.as_str())
+            .map(|n| n.rsplit('/').next().unwrap_or(n).to_string())
+            .unwrap_or_else(|| Self::dir_name(root));
+        let scripts = package.get("scripts").and_then(|s| s.as_object());
+        let has_script = |name: &str| scripts.is_some_and(|s| s.contains_key(name));
+
+        let build_command = Some(Self::isolated("npm", if has_script("build") {
+            "npm install && npm run build"
+        } else {
+            "npm install"
+        }));
+
+        let mut plans = Vec::new();
+
+        match package.get("bin") {
+            Some(serde_json::Value::String(path)) => {
+                plans.push(BuildPlan::new("npm", "package.json", &package_name, build_command.clone(), Self::npm_node(path)));
+            },
+            Some(serde_json::Value::Object(bins)) => {
+                for (name, path) in bins {
+                    if let Some(path) = path.as_str() {
+                        plans.push(BuildPlan::new("npm", "package.json", name, build_command.clone(), Self::npm_node(path)));
+                    }
+                }
+            },
+            _ => {}
+        }
+
+        if plans.is_empty() && has_script("start") {
+            plans.push(BuildPlan::new("npm", "package.json", "start", build_command.clone(), "npm --prefix \"${BUILD_DIR}/npm\" run --silent start --".to_string()));
+        }
+
+        if plans.is_empty() {
+            if let Some(main) = package.get("main").and_then(|m| m.as_str()) {
+                plans.push(BuildPlan::new("npm", "package.json", &package_name, build_command, Self::npm_node(main)));
+            }
+        }
+
+        plans
+    }
+
+    /// Executa um script da cópia compilada em `${BUILD_DIR}/npm`
+    fn npm_node(path: &str) -> String {
+        format!("node \"${{BUILD_DIR}}\"/npm/{}", path.trim_start_matches("./"))
+    }
+
+    fn detect_python(root: &Path) -> Vec<BuildPlan> {
+        let manifest: toml::Value = match Self::read_toml(&root.join("pyproject.toml")) {
+            Some(manifest) => manifest,
+            None => return Vec::new(),
+        };
+
+        let scripts = manifest.get("project").and_then(|p| p.get("scripts"))
+            .or_else(|| manifest.get("tool")
+                .and_then(|t| t.get("poetry"))
+                .and_then(|p| p.get("scripts")))
+            .and_then(|s| s.as_table());
+
+        let scripts = match scripts {
+            Some(scripts) => scripts,
+            None => return Vec::new(),
+        };
+
+        // Chama a função de entrada direto, sem instalar o pacote no ambiente
+        scripts.iter()
+            .filter_map(|(name, target)| {
+                let (module, function) = target.as_str()?.split_once(':')?;
+                let function = function.split_whitespace().next()?;
+                let code = format!(
+                    "import sys; sys.argv[0] = '{}'; from {} import {}; sys.exit({}())",
+                    name, module.trim(), function.split('.').next()?, function
+                );
+
+                Some(BuildPlan::new("python", "pyproject.toml", name, None, format!("python3 -c \"{}\"", code)))
+            })
+            .collect()
+    }
+
+    fn read_toml(path: &Path) -> Option<toml::Value> {
+        let content = fs::read_to_string(path).ok()?;
+        match toml::from_str(&content) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                println!("⚠️ Não foi possível ler {:?}: {}", path, e);
+                None
+            }
+        }
+    }
+
+    fn dir_name(root: &Path) -> String {
+        root.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "program".to_string())
+    }
+}
@@ -0,0 +1,396 @@
+use crate::ai::ProjectInfo;
+use crate::build_cache::BuildCache;
+use crate::paths::PathRemap;
+use crate::project::ProjectService;
+use crate::settings::{AppSettings, SettingsService};
+use crate::snapshot::LegacySnapshot;
+use crate::storage::{Storage, CURRENT_SCHEMA_VERSION};
+use crate::test_store::{StoredTest, TestStore, TestSuite};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Component, Path};
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+/// Identificador gravado no manifesto para reconhecer o arquivo exportado
+const BUNDLE_FORMAT: &str = "legacyevo-project";
+const MANIFEST_FILE: &str = "manifest.json";
+const TESTS_FILE: &str = "tests.json";
+const SNAPSHOTS_DIR: &str = "snapshots/";
+/// Fixtures usadas pelos testes (`${FIXTURES_DIR}/...`), sempre exportadas
+const FIXTURES_DIR: &str = "fixtures/";
+
+/// Manifesto de um pacote de projeto exportado
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BundleManifest {
+    #[serde(rename = "schemaVersion", default)]
+    pub schema_version: u32,
+    pub format: String,
+    #[serde(rename = "exportedAt")]
+    pub exported_at: String,
+    /// Projeto como estava na máquina de origem (paths absolutos de lá)
+    pub project: ProjectInfo,
+    pub settings: AppSettings,
+    #[serde(rename = "testCount")]
+    pub test_count: usize,
+    #[serde(rename = "includesSnapshots")]
+    pub includes_snapshots: bool,
+}
+
+/// Manifesto mostrado antes da importação, com o que mudaria nas configurações
+/// globais desta máquina se as do pacote forem aplicadas
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BundlePreview {
+    #[serde(flatten)]
+    pub manifest: BundleManifest,
+    #[serde(rename = "settingsChanges")]
+    pub settings_changes: Vec<String>,
+}
+
+/// Exportação e importação de projetos em um arquivo `.zip` portátil
+pub struct BundleService;
+
+impl BundleService {
+    /// Gera o pacote com projeto, definições dos testes, configurações, fixtures e,
+    /// opcionalmente, os snapshots do legado
+    pub async fn export(
+        project_id: &str,
+        destination: &str,
+        include_snapshots: bool,
+        app: &tauri::AppHandle
+    ) -> Result<BundleManifest, String> {
+        let project = ProjectService::load_project(project_id, app).await?;
+
+        let _lock = Storage::lock_project(project_id, app)?;
+        let suite = TestStore::load_suite(project_id, app).await?;
+
+        // Resultados de execução são da máquina de origem; só as definições viajam
+        let suite = TestSuite {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            tests: suite.tests.into_iter()
+                .map(|t| StoredTest { definition: t.definition, last_result: None })
+                .collect(),
+        };
+
+        let manifest = BundleManifest {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            format: BUNDLE_FORMAT.to_string(),
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            project,
+            settings: SettingsService::load(app)?,
+            test_count: suite.tests.len(),
+            includes_snapshots: include_snapshots,
+        };
+
+        let file = File::create(destination)
+            .map_err(|e| format!("Failed to create bundle {}: {}", destination, e))?;
+        let mut zip = ZipWriter::new(file);
+
+        Self::write_entry(&mut zip, MANIFEST_FILE, &serde_json::to_vec_pretty(&manifest)
+            .map_err(|e| format!("Failed to serialize manifest: {}", e))?)?;
+        Self::write_entry(&mut zip, TESTS_FILE, &serde_json::to_vec_pretty(&suite)
+            .map_err(|e| format!("Failed to serialize tests: {}", e))?)?;
+
+        if include_snapshots {
+            let snapshots_dir = ProjectService::project_data_dir(project_id, app)?.join("snapshots");
+            Self::write_dir(&mut zip, &snapshots_dir, SNAPSHOTS_DIR)?;
+        }
+
+        Self::write_dir(&mut zip, &BuildCache::fixtures_root(project_id, app)?, FIXTURES_DIR)?;
+
+        zip.finish()
+            .map_err(|e| format!("Failed to finish bundle: {}", e))?;
+
+        println!("📦 Projeto {} exportado para {} ({} testes)", manifest.project.name, destination, manifest.test_count);
+        Ok(manifest)
+    }
+
+    /// Lê apenas o manifesto, para a UI mostrar o conteúdo, pedir os novos paths e
+    /// perguntar se as configurações do pacote devem substituir as atuais
+    pub fn preview(bundle_path: &str, app: &tauri::AppHandle) -> Result<BundlePreview, String> {
+        let mut archive = Self::open(bundle_path)?;
+        let manifest = Self::read_manifest(&mut archive)?;
+        let settings_changes = Self::settings_changes(&SettingsService::load(app)?, &manifest.settings);
+
+        Ok(BundlePreview { manifest, settings_changes })
+    }
+
+    /// Importa um pacote como um novo projeto. Os paths absolutos da máquina de
+    /// origem são trocados pelos informados (ou mantidos, se omitidos). Toolchains
+    /// que não existirem nesta máquina ficam de fora e precisam ser configurados de novo.
+    /// Com `apply_settings`, as configurações globais do pacote substituem as atuais.
+    pub async fn import(
+        bundle_path: &str,
+        name: Option<String>,
+        legacy_path: Option<String>,
+        new_path: Option<String>,
+        apply_settings: bool,
+        app: &tauri::AppHandle
+    ) -> Result<ProjectInfo, String> {
+        let mut archive = Self::open(bundle_path)?;
+        let manifest = Self::read_manifest(&mut archive)?;
+        let original = &manifest.project;
+
+        let legacy_path = legacy_path.unwrap_or_else(|| original.legacy_path.clone());
+        let new_path = new_path.unwrap_or_else(|| original.new_path.clone());
+        let name = name.unwrap_or_else(|| original.name.clone());
+
+        let remap = PathRemap::new(vec![
+            (original.legacy_path.clone(), legacy_path.clone()),
+            (original.new_path.clone(), new_path.clone()),
+        ]);
+
+        let mut suite: TestSuite = serde_json::from_slice(&Self::read_entry(&mut archive, TESTS_FILE)?)
+            .map_err(|e| format!("Failed to deserialize bundle tests: {}", e))?;
+        for stored in &mut suite.tests {
+            remap.apply_to_test(&mut stored.definition);
+        }
+
+        let mut snapshots = Vec::new();
+        let mut capture_files = Vec::new();
+        let mut fixtures = Vec::new();
+        for index in 0..archive.len() {
+            let mut entry = archive.by_index(index)
+                .map_err(|e| format!("Failed to read bundle entry: {}", e))?;
+            if !entry.is_file() {
+                continue;
+            }
+            let (relative, is_fixture) = match (entry.name().strip_prefix(SNAPSHOTS_DIR), entry.name().strip_prefix(FIXTURES_DIR)) {
+                (Some(relative), _) => (relative.to_string(), false),
+                (_, Some(relative)) => (relative.to_string(), true),
+                _ => continue,
+            };
+
+            if !Self::is_safe_relative(Path::new(&relative)) {
+                return Err(format!("Entrada inválida no pacote: {}", entry.name()));
+            }
+
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data)
+                .map_err(|e| format!("Failed to read {}: {}", entry.name(), e))?;
+
+            if is_fixture {
+                fixtures.push((relative, data));
+                continue;
+            }
+
+            // Saídas grandes gravadas em arquivo ao lado das versões vão como estão
+            if !Self::is_snapshot_version(Path::new(&relative)) {
+                capture_files.push((relative, data));
+                continue;
+            }
+
+            let mut snapshot: LegacySnapshot = serde_json::from_slice(&data)
+                .map_err(|e| format!("Failed to deserialize snapshot {}: {}", relative, e))?;
+            snapshot.command = remap.apply(&snapshot.command);
+
+            snapshots.push((relative, snapshot));
+        }
+
+        let mut project = ProjectService::create_project(&name, &legacy_path, &new_path, &original.project_type, app)?;
+
+        // Pontos de entrada são relativos à raiz, então continuam valendo nos novos paths
+        for (side, entry_points) in [("legacy", &original.legacy_entry_points), ("new", &original.new_entry_points)] {
+            if entry_points.is_empty() {
+                continue;
+            }
+            match ProjectService::set_entry_points(&project.id, side, entry_points.clone(), app) {
+                Ok(updated) => project = updated,
+                Err(e) => println!("⚠️ Pontos de entrada do lado {} não importados: {}", side, e),
+            }
+        }
+
+        for (side, encoding) in [("legacy", &original.legacy_encoding), ("new", &original.new_encoding)] {
+            if encoding.is_none() {
+                continue;
+            }
+            match ProjectService::set_encoding(&project.id, side, encoding.clone(), app) {
+                Ok(updated) => project = updated,
+                Err(e) => println!("⚠️ Codificação do lado {} não importada: {}", side, e),
+            }
+        }
+
+        if original.comparator.is_some() {
+            match ProjectService::set_comparator(&project.id, original.comparator.clone(), app) {
+                Ok(updated) => project = updated,
+                Err(e) => println!("⚠️ Comparador padrão não importado: {}", e),
+            }
+        }
+
+        // Os diretórios de trabalho dos servidores são relativos à raiz de cada lado
+        if original.api_config.is_some() {
+            match ProjectService::set_api_config(&project.id, original.api_config.clone(), app) {
+                Ok(updated) => project = updated,
+                Err(e) => println!("⚠️ Configuração dos servidores não importada: {}", e),
+            }
+        }
+
+        // Toolchains apontam para executáveis da máquina de origem: os que ficam dentro
+        // das raízes acompanham os novos paths, e o lado só é importado se tudo existir aqui
+        for (side, toolchain) in [("legacy", &original.legacy_toolchain), ("new", &original.new_toolchain)] {
+            let mut toolchain = match toolchain {
+                Some(toolchain) => toolchain.clone(),
+                None => continue,
+            };
+            for path in toolchain.tools.values_mut().chain(toolchain.extra_path.iter_mut()) {
+                *path = remap.apply(path);
+            }
+
+            match ProjectService::set_toolchain(&project.id, side, Some(toolchain), app) {
+                Ok(updated) => project = updated,
+                Err(e) => println!("⚠️ Toolchain do lado {} não importado (configure-o nesta máquina): {}", side, e),
+            }
+        }
+
+        let result = Self::write_project_data(&project.id, &suite, &snapshots, &capture_files, &fixtures, app).await;
+        if let Err(e) = result {
+            let _ = ProjectService::delete_project(&project.id, app);
+            return Err(e);
+        }
+
+        if apply_settings {
+            let current = SettingsService::load(app)?;
+            for change in Self::settings_changes(&current, &manifest.settings) {
+                println!("⚙️ Configuração importada: {}", change);
+            }
+            if let Err(e) = SettingsService::save(&manifest.settings, app) {
+                println!("⚠️ Configurações do pacote não aplicadas: {}", e);
+            }
+        }
+
+        println!("📦 Projeto {} importado de {} ({} testes, {} snapshots)",
+            project.name, bundle_path, suite.tests.len(), snapshots.len());
+        Ok(project)
+    }
+
+    async fn write_project_data(
+        project_id: &str,
+        suite: &TestSuite,
+        snapshots: &[(String, LegacySnapshot)],
+        capture_files: &[(String, Vec<u8>)],
+        fixtures: &[(String, Vec<u8>)],
+        app: &tauri::AppHandle
+    ) -> Result<(), String> {
+        let _lock = Storage::lock_project(project_id, app)?;
+        TestStore::save_suite(project_id, suite, app).await?;
+
+        let fixtures_dir = BuildCache::fixtures_root(project_id, app)?;
+        for (relative, data) in fixtures {
+            Storage::write_atomic(&fixtures_dir.join(relative), data)?;
+        }
+
+        let snapshots_dir = ProjectService::project_data_dir(project_id, app)?.join("snapshots");
+        for (relative, data) in capture_files {
+            Storage::write_atomic(&snapshots_dir.join(relative), data)?;
+        }
+
+        for (relative, snapshot) in snapshots {
+            let path = snapshots_dir.join(relative);
+            let test_dir = path.parent().unwrap_or(&snapshots_dir);
+
+            // As capturas apontavam para o app data da máquina de origem
+            let mut snapshot = snapshot.clone();
+            for capture in [&mut snapshot.output.stdout_capture, &mut snapshot.output.stderr_capture].into_iter().flatten() {
+                let file_name = capture.file.rsplit(['/', '\\']).next().unwrap_or_default().to_string();
+                capture.file = test_dir.join(file_name).to_string_lossy().to_string();
+            }
+
+            Storage::write_json_atomic(&path, &snapshot)?;
+        }
+
+        Ok(())
+    }
+
+    /// Diferenças entre as configurações atuais e as do pacote, uma por linha
+    fn settings_changes(current: &AppSettings, incoming: &AppSettings) -> Vec<String> {
+        let fields = [
+            ("executionRetention", current.execution_retention as u64, incoming.execution_retention as u64),
+            ("captureInlineBytes", current.capture_inline_bytes, incoming.capture_inline_bytes),
+            ("maxOutputBytes", current.max_output_bytes, incoming.max_output_bytes),
+            ("previewBytes", current.preview_bytes as u64, incoming.preview_bytes as u64),
+        ];
+
+        fields.iter()
+            .filter(|(_, current, incoming)| current != incoming)
+            .map(|(name, current, incoming)| format!("{}: {} → {}", name, current, incoming))
+            .collect()
+    }
+
+    fn open(bundle_path: &str) -> Result<ZipArchive<File>, String> {
+        let file = File::open(bundle_path)
+            .map_err(|e| format!("Failed to open bundle {}: {}", bundle_path, e))?;
+
+        ZipArchive::new(file)
+            .map_err(|e| format!("Arquivo {} não é um pacote de projeto válido: {}", bundle_path, e))
+    }
+
+    fn read_manifest(archive: &mut ZipArchive<File>) -> Result<BundleManifest, String> {
+        let manifest: BundleManifest = serde_json::from_slice(&Self::read_entry(archive, MANIFEST_FILE)?)
+            .map_err(|e| format!("Failed to deserialize bundle manifest: {}", e))?;
+
+        if manifest.format != BUNDLE_FORMAT {
+            return Err(format!("Formato de pacote desconhecido: {}", manifest.format));
+        }
+
+        if manifest.schema_version > CURRENT_SCHEMA_VERSION {
+            return Err(format!(
+                "Pacote gerado por uma versão mais nova do app (schemaVersion {})",
+                manifest.schema_version
+            ));
+        }
+
+        Ok(manifest)
+    }
+
+    fn read_entry(archive: &mut ZipArchive<File>, name: &str) -> Result<Vec<u8>, String> {
+        let mut entry = archive.by_name(name)
+            .map_err(|e| format!("Pacote sem {}: {}", name, e))?;
+
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data)
+            .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+
+        Ok(data)
+    }
+
+    /// Adiciona todos os arquivos de um diretório (se existir) sob `prefix`
+    fn write_dir(zip: &mut ZipWriter<File>, dir: &Path, prefix: &str) -> Result<(), String> {
+        for entry in walkdir::WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+
+            let relative = entry.path().strip_prefix(dir)
+                .map_err(|e| format!("Invalid path in {:?}: {}", dir, e))?;
+            let data = fs::read(entry.path())
+                .map_err(|e| format!("Failed to read {:?}: {}", entry.path(), e))?;
+            let name = format!("{}{}", prefix, relative.to_string_lossy().replace('\\', "/"));
+
+            Self::write_entry(zip, &name, &data)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_entry(zip: &mut ZipWriter<File>, name: &str, data: &[u8]) -> Result<(), String> {
+        zip.start_file(name, SimpleFileOptions::default())
+            .map_err(|e| format!("Failed to add {} to bundle: {}", name, e))?;
+        zip.write_all(data)
+            .map_err(|e| format!("Failed to write {} to bundle: {}", name, e))
+    }
+
+    /// Versão de snapshot (`<teste>/v<N>.json`), e não um arquivo de saída capturada
+    fn is_snapshot_version(path: &Path) -> bool {
+        path.file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_prefix('v'))
+            .and_then(|n| n.strip_suffix(".json"))
+            .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+    }
+
+    /// Só aceita caminhos relativos sem `..` (evita escrever fora do diretório do projeto)
+    fn is_safe_relative(path: &Path) -> bool {
+        path.components().all(|c| matches!(c, Component::Normal(_)))
+    }
+}
@@ -0,0 +1,148 @@
+use crate::settings::AppSettings;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Tamanho das leituras dos pipes dos programas
+const READ_CHUNK: usize = 64 * 1024;
+
+/// Saída grande gravada em arquivo em vez de ficar em memória
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CaptureInfo {
+    /// Bytes produzidos pelo programa, inclusive os descartados
+    pub size: u64,
+    /// sha256 de todos os bytes produzidos
+    pub sha256: String,
+    pub file: String,
+    /// A saída passou de `maxOutputBytes` e o arquivo só tem o início
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+/// Stream lido de um programa
+pub struct CapturedStream {
+    /// Saída completa, ou só a prévia quando ela foi para arquivo
+    pub bytes: Vec<u8>,
+    pub spill: Option<CaptureInfo>,
+}
+
+/// Captura das saídas com limites (`settings.json`): até `captureInlineBytes`
+/// fica em memória; acima disso vai para um arquivo do diretório da execução
+#[derive(Clone, Debug)]
+pub struct OutputCapture {
+    dir: PathBuf,
+    inline_limit: u64,
+    max_bytes: u64,
+    preview_bytes: usize,
+}
+
+impl OutputCapture {
+    pub fn new(dir: &Path, settings: &AppSettings) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+            inline_limit: settings.capture_inline_bytes,
+            max_bytes: settings.max_output_bytes,
+            preview_bytes: settings.preview_bytes,
+        }
+    }
+
+    /// Mesmos limites, gravando em outro diretório
+    pub fn with_dir(&self, dir: &Path) -> Self {
+        Self { dir: dir.to_path_buf(), ..self.clone() }
+    }
+
+    /// Lê o stream até o fim. Depois do limite em memória o conteúdo vai para
+    /// `<dir>/<uuid>.<name>`; depois de `max_bytes` continua sendo lido (para o
+    /// programa não travar no pipe cheio) e entra no hash, mas não é gravado.
+    pub fn read_stream(&self, mut reader: impl Read, name: &str) -> Result<CapturedStream, String> {
+        let mut memory = Vec::new();
+        let mut spill: Option<(File, PathBuf)> = None;
+        let mut hasher = Sha256::new();
+        let mut total: u64 = 0;
+        let mut written: u64 = 0;
+        let mut buffer = vec![0u8; READ_CHUNK];
+
+        loop {
+            let n = match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(format!("Erro lendo {}: {}", name, e)),
+            };
+            let chunk = &buffer[..n];
+            hasher.update(chunk);
+            total += n as u64;
+
+            if spill.is_none() && total > self.inline_limit {
+                fs::create_dir_all(&self.dir)
+                    .map_err(|e| format!("Erro ao criar {}: {}", self.dir.display(), e))?;
+                let path = self.dir.join(format!("{}.{}", uuid::Uuid::new_v4(), name));
+                let mut file = File::create(&path)
+                    .map_err(|e| format!("Erro ao criar {}: {}", path.display(), e))?;
+
+                self.write_capped(&mut file, &memory, &mut written)?;
+                memory.truncate(Self::preview_end(&memory, self.preview_bytes));
+                spill = Some((file, path));
+            }
+
+            match &mut spill {
+                Some((file, _)) => self.write_capped(file, chunk, &mut written)?,
+                None => memory.extend_from_slice(chunk),
+            }
+        }
+
+        let spill = spill.map(|(_, path)| {
+            println!("💾 Saída {} com {} bytes gravada em {}", name, total, path.display());
+            CaptureInfo {
+                size: total,
+                sha256: format!("{:x}", hasher.finalize()),
+                file: path.to_string_lossy().to_string(),
+                truncated: total > self.max_bytes,
+            }
+        });
+
+        Ok(CapturedStream { bytes: memory, spill })
+    }
+
+    /// Prévia de um texto para a UI: corta em `previewBytes` (sem partir caracteres)
+    pub fn preview(text: &str, limit: usize) -> String {
+        if text.len() <= limit {
+            return text.to_string();
+        }
+
+        let mut end = limit;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        format!("{}\n… (prévia de {} de {} bytes)", &text[..end], end, text.len())
+    }
+
+    /// Fim da prévia em memória sem partir um caractere UTF-8: a codificação do
+    /// lado é detectada pela prévia, e um caractere cortado a faria cair para
+    /// Windows-1252 ao decodificar o arquivo inteiro
+    fn preview_end(bytes: &[u8], limit: usize) -> usize {
+        let end = limit.min(bytes.len());
+        match std::str::from_utf8(&bytes[..end]) {
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            _ => end,
+        }
+    }
+
+    pub fn sha256(bytes: &[u8]) -> String {
+        format!("{:x}", Sha256::digest(bytes))
+    }
+
+    fn write_capped(&self, file: &mut File, data: &[u8], written: &mut u64) -> Result<(), String> {
+        let room = self.max_bytes.saturating_sub(*written).min(data.len() as u64) as usize;
+        if room == 0 {
+            return Ok(());
+        }
+
+        file.write_all(&data[..room])
+            .map_err(|e| format!("Erro gravando saída: {}", e))?;
+        *written += room as u64;
+        Ok(())
+    }
+}
@@ -0,0 +1,949 @@
+use crate::ai::{FullTestFromAI, ProjectInfo};
+use crate::encoding::OutputEncoding;
+use crate::runner::{SideOutput, TestRunner};
+use crate::snapshot::FileEffect;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Tempo padrão para um comparador externo dar o veredito
+const DEFAULT_EXTERNAL_TIMEOUT_MS: u64 = 60000;
+
+/// Como as saídas de um teste são comparadas (`comparator` no teste)
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Comparator {
+    /// Texto exato (sem espaços nas pontas), o comportamento original
+    #[default]
+    Text,
+    /// Objetos sem ordem de chaves; arrays em ordem, a não ser com `ignoreArrayOrder`
+    Json {
+        #[serde(rename = "ignoreArrayOrder", default)]
+        ignore_array_order: bool,
+        /// Diferença máxima aceita entre números
+        #[serde(default)]
+        tolerance: f64,
+    },
+    /// Tabelas: colunas identificadas pelo cabeçalho, linhas como lista ou conjunto
+    Csv {
+        #[serde(default)]
+        delimiter: Option<char>,
+        /// Se a primeira linha é cabeçalho (padrão: sim)
+        #[serde(default = "default_true")]
+        header: bool,
+        #[serde(rename = "ignoreColumnOrder", default)]
+        ignore_column_order: bool,
+        #[serde(rename = "ignoreRowOrder", default)]
+        ignore_row_order: bool,
+        #[serde(default)]
+        tolerance: f64,
+    },
+    /// Documentos canonizados: ordem de atributos, espaços entre elementos,
+    /// comentários e declaração não contam
+    Xml {
+        /// Compara também espaços dentro dos textos
+        #[serde(rename = "preserveWhitespace", default)]
+        preserve_whitespace: bool,
+    },
+    /// Programa do usuário que recebe os diretórios com as saídas de cada lado
+    /// (`$1` legado, `$2` novo) e responde pelo exit code (0 iguais, 1 diferentes)
+    /// ou por JSON `{"equal": bool, "diff": "...", "differences": [...]}` no stdout
+    External {
+        command: String,
+        #[serde(rename = "timeoutMs", default, skip_serializing_if = "Option::is_none")]
+        timeout_ms: Option<u64>,
+    },
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Comparator {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Comparator::Text => "text",
+            Comparator::Json { .. } => "json",
+            Comparator::Csv { .. } => "csv",
+            Comparator::Xml { .. } => "xml",
+            Comparator::External { .. } => "external",
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Comparator::Csv { delimiter: Some(delimiter), .. } if !delimiter.is_ascii() => {
+                Err(format!("Delimitador CSV deve ser um caractere ASCII: {}", delimiter))
+            },
+            Comparator::External { command, .. } if command.trim().is_empty() => {
+                Err("Comparador externo sem comando".to_string())
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Saída de um lado e os arquivos que ele criou ou modificou, para comparação
+pub struct ComparedSide<'a> {
+    pub output: &'a SideOutput,
+    pub effects: &'a [FileEffect],
+    /// Diretório de trabalho onde os arquivos estão; sem ele (snapshots gravados)
+    /// só há o conteúdo guardado no efeito
+    pub dir: Option<&'a Path>,
+    /// Raiz do lado, exposta ao comparador externo como `LEGACY_ROOT`/`NEW_ROOT`
+    pub root: Option<&'a str>,
+}
+
+impl<'a> ComparedSide<'a> {
+    /// Lado sem arquivos, só com a saída
+    pub fn output(output: &'a SideOutput) -> Self {
+        Self { output, effects: &[], dir: None, root: None }
+    }
+}
+
+/// Resposta em JSON de um comparador externo
+#[derive(Deserialize)]
+struct ExternalVerdict {
+    equal: bool,
+    #[serde(default)]
+    diff: Option<String>,
+    #[serde(default)]
+    differences: Vec<Difference>,
+}
+
+/// Diferença em um ponto da saída (`$.itens[2].preco`, `[3].total`, `/pedido/item[2]/@id`)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Difference {
+    pub path: String,
+    /// Valor no legado (`None` quando só existe no novo)
+    pub legacy: Option<String>,
+    /// Valor no novo (`None` quando só existe no legado)
+    pub new: Option<String>,
+}
+
+impl Difference {
+    fn new(path: &str, legacy: Option<String>, new: Option<String>) -> Self {
+        Self { path: path.to_string(), legacy, new }
+    }
+
+    /// Descrição em uma linha, no formato das demais diferenças do histórico
+    pub fn describe(&self) -> String {
+        match (&self.legacy, &self.new) {
+            (Some(legacy), Some(new)) => format!("{}: {} != {}", self.path, legacy, new),
+            (Some(legacy), None) => format!("{}: ausente no novo ({})", self.path, legacy),
+            (None, Some(new)) => format!("{}: ausente no legado ({})", self.path, new),
+            (None, None) => self.path.clone(),
+        }
+    }
+}
+
+/// Resultado da comparação entre legado e novo
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ComparisonResult {
+    pub comparator: String,
+    #[serde(rename = "outputsMatch")]
+    pub outputs_match: bool,
+    #[serde(rename = "exitCodesMatch")]
+    pub exit_codes_match: bool,
+    pub differences: Vec<Difference>,
+    /// Texto de diff devolvido pelo comparador externo
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>,
+    /// Dump hexadecimal em volta da primeira diferença, quando a saída é binária
+    #[serde(rename = "hexDiff", default, skip_serializing_if = "Option::is_none")]
+    pub hex_diff: Option<String>,
+}
+
+impl ComparisonResult {
+    /// Regra única de aprovação de um teste, nas execuções ao vivo e por snapshot:
+    /// saídas iguais, exit codes iguais e nenhuma diferença nos arquivos afetados
+    pub fn passed(&self, effect_differences: &[String]) -> bool {
+        self.outputs_match && self.exit_codes_match && effect_differences.is_empty()
+    }
+}
+
+/// Comparadores de saída: texto, JSON, CSV, XML e programas externos
+pub struct OutputComparator;
+
+impl OutputComparator {
+    /// Comparador de um teste: o do próprio teste, o padrão do projeto ou texto
+    pub fn for_test(project: &ProjectInfo, test: &FullTestFromAI) -> Comparator {
+        test.comparator.clone()
+            .or_else(|| project.comparator.clone())
+            .unwrap_or_default()
+    }
+
+    /// Compara as saídas dos dois lados com o comparador do teste. O texto usa a
+    /// saída exibida; os comparadores estruturados leem apenas o stdout e o
+    /// externo recebe stdout, stderr e arquivos produzidos.
+    pub fn compare(comparator: &Comparator, legacy: &ComparedSide, new: &ComparedSide) -> ComparisonResult {
+        let (legacy_output, new_output) = (legacy.output, new.output);
+        let mut diff = None;
+        let mut hex_diff = None;
+
+        let mut differences = match comparator {
+            // Saídas grandes ficaram em arquivo: compara pelo hash dos bytes
+            Comparator::Text if [legacy_output, new_output].iter().any(|o| o.stdout_capture.is_some() || o.stderr_capture.is_some()) => {
+                Self::compare_large(legacy_output, new_output)
+            },
+            // Saída binária não tem texto a comparar: compara os bytes
+            Comparator::Text if legacy_output.binary || new_output.binary => {
+                let (legacy_bytes, new_bytes) = (legacy_output.stdout_raw(), new_output.stdout_raw());
+                hex_diff = OutputEncoding::hex_diff(&legacy_bytes, &new_bytes);
+
+                match OutputEncoding::first_difference(&legacy_bytes, &new_bytes) {
+                    Some(offset) => vec![Difference::new(
+                        &format!("byte {}", offset),
+                        Some(Self::describe_byte(&legacy_bytes, offset)),
+                        Some(Self::describe_byte(&new_bytes, offset))
+                    )],
+                    None => Vec::new(),
+                }
+            },
+            Comparator::Text => {
+                let (legacy_final, new_final) = (legacy_output.display_output(), new_output.display_output());
+                if TestRunner::outputs_match(&legacy_final, &new_final) {
+                    Vec::new()
+                } else {
+                    vec![Self::first_line_difference(&legacy_final, &new_final)]
+                }
+            },
+            Comparator::External { command, timeout_ms } => {
+                let (differences, text) = Self::run_external(command, *timeout_ms, legacy, new);
+                diff = text;
+                differences
+            },
+            _ => match (Self::full_text(legacy_output), Self::full_text(new_output)) {
+                (Ok(legacy_text), Ok(new_text)) => Self::compare_text(comparator, &legacy_text, &new_text),
+                (a, b) => Self::parse_errors("$", a.err(), b.err()),
+            },
+        };
+
+        // Roteiro interativo que falhou em qualquer lado reprova o teste, mesmo com
+        // transcrições iguais (ex.: os dois travaram no mesmo `expect`)
+        if legacy_output.script_failure.is_some() || new_output.script_failure.is_some() {
+            let describe = |output: &SideOutput| output.script_failure.clone()
+                .unwrap_or_else(|| "roteiro concluído".to_string());
+            differences.push(Difference::new("roteiro", Some(describe(legacy_output)), Some(describe(new_output))));
+        }
+
+        ComparisonResult {
+            comparator: comparator.name().to_string(),
+            outputs_match: differences.is_empty(),
+            exit_codes_match: legacy_output.exit_code == new_output.exit_code,
+            differences,
+            diff,
+            hex_diff,
+        }
+    }
+
+    /// Saídas grandes: iguais se o hash bate; senão, tamanho/hash e a primeira
+    /// linha diferente entre as prévias
+    fn compare_large(legacy: &SideOutput, new: &SideOutput) -> Vec<Difference> {
+        let (legacy_stream, legacy_hash, legacy_size, legacy_preview) = Self::displayed_stream(legacy);
+        let (new_stream, new_hash, new_size, new_preview) = Self::displayed_stream(new);
+        if legacy_stream == new_stream && legacy_hash == new_hash {
+            return Vec::new();
+        }
+
+        let describe = |stream: &str, size: u64, hash: &str| format!("{}: {} bytes, sha256 {}", stream, size, &hash[..12]);
+        let path = if legacy_stream == new_stream { legacy_stream.to_string() } else { format!("{}/{}", legacy_stream, new_stream) };
+        let mut differences = vec![Difference::new(
+            &path,
+            Some(describe(legacy_stream, legacy_size, &legacy_hash)),
+            Some(describe(new_stream, new_size, &new_hash))
+        )];
+        if !TestRunner::outputs_match(legacy_preview, new_preview) {
+            differences.push(Self::first_line_difference(legacy_preview, new_preview));
+        }
+
+        differences
+    }
+
+    /// Stream comparado no modo texto, como em `display_output`: stdout, ou stderr
+    /// quando stdout está vazio. Devolve nome, sha256, tamanho e prévia.
+    fn displayed_stream(output: &SideOutput) -> (&'static str, String, u64, &str) {
+        if output.stdout_capture.is_none() && output.stdout.trim().is_empty() {
+            ("stderr", output.stderr_sha256(), output.stderr_size(), &output.stderr)
+        } else {
+            ("stdout", output.stdout_sha256(), output.stdout_size(), &output.stdout)
+        }
+    }
+
+    /// stdout completo como texto, relendo o arquivo quando a saída foi para disco
+    fn full_text(output: &SideOutput) -> Result<String, String> {
+        if output.stdout_capture.is_none() {
+            return Ok(output.stdout.clone());
+        }
+
+        let (text, _) = OutputEncoding::decode(&output.full_stdout_raw()?, output.encoding.as_deref());
+        Ok(text)
+    }
+
+    fn describe_byte(bytes: &[u8], offset: usize) -> String {
+        match bytes.get(offset) {
+            Some(byte) => format!("0x{:02x}", byte),
+            None => format!("fim ({} bytes)", bytes.len()),
+        }
+    }
+
+    /// Diferenças entre dois textos segundo um comparador estruturado; um lado
+    /// que não pode ser lido no formato vira uma diferença na raiz
+    pub fn compare_text(comparator: &Comparator, legacy: &str, new: &str) -> Vec<Difference> {
+        let mut differences = Vec::new();
+
+        match comparator {
+            Comparator::Text => {
+                if !TestRunner::outputs_match(legacy, new) {
+                    differences.push(Self::first_line_difference(legacy, new));
+                }
+            },
+            Comparator::Json { ignore_array_order, tolerance } => {
+                match (Self::parse_json(legacy, "legado"), Self::parse_json(new, "novo")) {
+                    (Ok(a), Ok(b)) => Self::json_differences("$", &a, &b, *ignore_array_order, *tolerance, &mut differences),
+                    (a, b) => differences.extend(Self::parse_errors("$", a.err(), b.err())),
+                }
+            },
+            Comparator::Csv { delimiter, header, ignore_column_order, ignore_row_order, tolerance } => {
+                let delimiter = delimiter.unwrap_or(',');
+                match (Self::parse_csv(legacy, delimiter, "legado"), Self::parse_csv(new, delimiter, "novo")) {
+                    (Ok(a), Ok(b)) => Self::csv_differences(&a, &b, *header, *ignore_column_order, *ignore_row_order, *tolerance, &mut differences),
+                    (a, b) => differences.extend(Self::parse_errors("[]", a.err(), b.err())),
+                }
+            },
+            Comparator::Xml { preserve_whitespace } => {
+                match (roxmltree::Document::parse(legacy.trim()), roxmltree::Document::parse(new.trim())) {
+                    (Ok(a), Ok(b)) => Self::xml_differences(
+                        &format!("/{}", Self::xml_name(a.root_element())),
+                        a.root_element(),
+                        b.root_element(),
+                        *preserve_whitespace,
+                        &mut differences
+                    ),
+                    (a, b) => differences.extend(Self::parse_errors(
+                        "/",
+                        a.err().map(|e| format!("XML inválido no legado: {}", e)),
+                        b.err().map(|e| format!("XML inválido no novo: {}", e))
+                    )),
+                }
+            },
+            Comparator::External { command, timeout_ms } => {
+                let as_output = |text: &str| SideOutput { stdout: text.to_string(), ..Default::default() };
+                let (legacy, new) = (as_output(legacy), as_output(new));
+                let (external, _) = Self::run_external(command, *timeout_ms, &ComparedSide::output(&legacy), &ComparedSide::output(&new));
+                differences.extend(external);
+            },
+        }
+
+        differences
+    }
+
+    /// Compara dois documentos JSON ignorando a ordem das chaves; números iguais
+    /// em representações diferentes (1 e 1.0) ou dentro da tolerância são iguais
+    pub fn json_differences(
+        path: &str,
+        legacy: &Value,
+        new: &Value,
+        ignore_array_order: bool,
+        tolerance: f64,
+        differences: &mut Vec<Difference>
+    ) {
+        match (legacy, new) {
+            (Value::Object(a), Value::Object(b)) => {
+                let keys: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+                for key in keys {
+                    let child = format!("{}.{}", path, key);
+                    match (a.get(key), b.get(key)) {
+                        (Some(x), Some(y)) => Self::json_differences(&child, x, y, ignore_array_order, tolerance, differences),
+                        (x, y) => differences.push(Difference::new(&child, x.map(Value::to_string), y.map(Value::to_string))),
+                    }
+                }
+            },
+            (Value::Array(a), Value::Array(b)) if ignore_array_order => {
+                // Cada item do legado consome um item igual do novo; sobram os diferentes
+                let mut unmatched: Vec<(usize, &Value)> = b.iter().enumerate().collect();
+                for (i, x) in a.iter().enumerate() {
+                    let found = unmatched.iter().position(|(_, y)| {
+                        let mut inner = Vec::new();
+                        Self::json_differences("", x, y, true, tolerance, &mut inner);
+                        inner.is_empty()
+                    });
+
+                    match found {
+                        Some(position) => { unmatched.remove(position); },
+                        None => differences.push(Difference::new(&format!("{}[{}]", path, i), Some(x.to_string()), None)),
+                    }
+                }
+
+                for (j, y) in unmatched {
+                    differences.push(Difference::new(&format!("{}[{}]", path, j), None, Some(y.to_string())));
+                }
+            },
+            (Value::Array(a), Value::Array(b)) => {
+                for i in 0..a.len().max(b.len()) {
+                    let child = format!("{}[{}]", path, i);
+                    match (a.get(i), b.get(i)) {
+                        (Some(x), Some(y)) => Self::json_differences(&child, x, y, false, tolerance, differences),
+                        (x, y) => differences.push(Difference::new(&child, x.map(Value::to_string), y.map(Value::to_string))),
+                    }
+                }
+            },
+            (Value::Number(a), Value::Number(b)) if Self::numbers_match(a.as_f64(), b.as_f64(), tolerance) => {},
+            (a, b) if a != b => differences.push(Difference::new(path, Some(a.to_string()), Some(b.to_string()))),
+            _ => {}
+        }
+    }
+
+    /// Compara tabelas CSV. Com cabeçalho, as colunas são casadas pelo nome
+    /// (`ignoreColumnOrder`) e as linhas identificadas como `[n].coluna`.
+    fn csv_differences(
+        legacy: &[Vec<String>],
+        new: &[Vec<String>],
+        header: bool,
+        ignore_column_order: bool,
+        ignore_row_order: bool,
+        tolerance: f64,
+        differences: &mut Vec<Difference>
+    ) {
+        let (legacy_columns, legacy_rows, new_columns, new_rows) = if header {
+            (
+                legacy.first().cloned().unwrap_or_default(),
+                legacy.get(1..).unwrap_or_default(),
+                new.first().cloned().unwrap_or_default(),
+                new.get(1..).unwrap_or_default(),
+            )
+        } else {
+            let width = legacy.iter().chain(new.iter()).map(|r| r.len()).max().unwrap_or(0);
+            let columns: Vec<String> = (0..width).map(|i| i.to_string()).collect();
+            (columns.clone(), legacy, columns, new)
+        };
+
+        if header && !ignore_column_order && legacy_columns != new_columns {
+            differences.push(Difference::new("cabeçalho", Some(legacy_columns.join(",")), Some(new_columns.join(","))));
+        }
+
+        // Colunas comparadas: as do legado, seguidas das que só existem no novo
+        let mut columns: Vec<&String> = legacy_columns.iter().collect();
+        columns.extend(new_columns.iter().filter(|c| !legacy_columns.contains(c)));
+        for column in &columns {
+            match (legacy_columns.contains(column), new_columns.contains(column)) {
+                (true, false) => differences.push(Difference::new(&format!("coluna {}", column), Some("presente".to_string()), None)),
+                (false, true) => differences.push(Difference::new(&format!("coluna {}", column), None, Some("presente".to_string()))),
+                _ => {}
+            }
+        }
+        let shared: Vec<&String> = columns.into_iter()
+            .filter(|c| legacy_columns.contains(c) && new_columns.contains(c))
+            .collect();
+
+        let project = |row: &Vec<String>, names: &[String]| -> BTreeMap<String, String> {
+            shared.iter()
+                .map(|c| {
+                    let index = names.iter().position(|n| n == *c).unwrap_or(usize::MAX);
+                    (c.to_string(), row.get(index).cloned().unwrap_or_default())
+                })
+                .collect()
+        };
+        let legacy_rows: Vec<BTreeMap<String, String>> = legacy_rows.iter().map(|r| project(r, &legacy_columns)).collect();
+        let new_rows: Vec<BTreeMap<String, String>> = new_rows.iter().map(|r| project(r, &new_columns)).collect();
+        let describe = |row: &BTreeMap<String, String>| shared.iter()
+            .map(|c| row.get(*c).cloned().unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join(",");
+
+        if ignore_row_order {
+            let mut unmatched: Vec<(usize, &BTreeMap<String, String>)> = new_rows.iter().enumerate().collect();
+            for (i, row) in legacy_rows.iter().enumerate() {
+                match unmatched.iter().position(|(_, other)| Self::rows_match(row, other, tolerance)) {
+                    Some(position) => { unmatched.remove(position); },
+                    None => differences.push(Difference::new(&format!("[{}]", i), Some(describe(row)), None)),
+                }
+            }
+
+            for (j, row) in unmatched {
+                differences.push(Difference::new(&format!("[{}]", j), None, Some(describe(row))));
+            }
+            return;
+        }
+
+        for i in 0..legacy_rows.len().max(new_rows.len()) {
+            match (legacy_rows.get(i), new_rows.get(i)) {
+                (Some(a), Some(b)) => {
+                    for column in &shared {
+                        let (x, y) = (&a[*column], &b[*column]);
+                        if !Self::cells_match(x, y, tolerance) {
+                            differences.push(Difference::new(&format!("[{}].{}", i, column), Some(x.clone()), Some(y.clone())));
+                        }
+                    }
+                },
+                (a, b) => differences.push(Difference::new(&format!("[{}]", i), a.map(describe), b.map(describe))),
+            }
+        }
+    }
+
+    /// Compara elementos XML canonizados: nome (com namespace), atributos sem
+    /// ordem, texto e filhos na ordem do documento
+    fn xml_differences(
+        path: &str,
+        legacy: roxmltree::Node,
+        new: roxmltree::Node,
+        preserve_whitespace: bool,
+        differences: &mut Vec<Difference>
+    ) {
+        let (legacy_name, new_name) = (Self::xml_name(legacy), Self::xml_name(new));
+        if legacy_name != new_name {
+            differences.push(Difference::new(path, Some(legacy_name), Some(new_name)));
+            return;
+        }
+
+        let legacy_attributes: BTreeMap<String, &str> = legacy.attributes().map(|a| (Self::xml_attribute_name(&a), a.value())).collect();
+        let new_attributes: BTreeMap<String, &str> = new.attributes().map(|a| (Self::xml_attribute_name(&a), a.value())).collect();
+        let names: BTreeSet<&String> = legacy_attributes.keys().chain(new_attributes.keys()).collect();
+        for name in names {
+            let (a, b) = (legacy_attributes.get(name), new_attributes.get(name));
+            if a != b {
+                differences.push(Difference::new(
+                    &format!("{}/@{}", path, name),
+                    a.map(|v| v.to_string()),
+                    b.map(|v| v.to_string())
+                ));
+            }
+        }
+
+        let (legacy_text, new_text) = (Self::xml_text(legacy, preserve_whitespace), Self::xml_text(new, preserve_whitespace));
+        if legacy_text != new_text {
+            differences.push(Difference::new(&format!("{}/text()", path), Some(legacy_text), Some(new_text)));
+        }
+
+        let legacy_children: Vec<roxmltree::Node> = legacy.children().filter(|n| n.is_element()).collect();
+        let new_children: Vec<roxmltree::Node> = new.children().filter(|n| n.is_element()).collect();
+
+        // Posição entre irmãos com o mesmo nome, no estilo XPath (1-based)
+        let mut seen: BTreeMap<String, usize> = BTreeMap::new();
+        for i in 0..legacy_children.len().max(new_children.len()) {
+            let (a, b) = (legacy_children.get(i), new_children.get(i));
+            let name = Self::xml_name(*a.or(b).unwrap());
+            let position = seen.entry(name.clone()).or_insert(0);
+            *position += 1;
+            let child = format!("{}/{}[{}]", path, name, position);
+
+            match (a, b) {
+                (Some(a), Some(b)) => Self::xml_differences(&child, *a, *b, preserve_whitespace, differences),
+                (a, b) => differences.push(Difference::new(
+                    &child,
+                    a.map(|n| Self::xml_text(*n, preserve_whitespace)),
+                    b.map(|n| Self::xml_text(*n, preserve_whitespace))
+                )),
+            }
+        }
+    }
+
+    /// Roda o comparador externo; uma falha do próprio comparador (erro, tempo
+    /// esgotado, código diferente de 0/1) conta como diferença
+    fn run_external(
+        command: &str,
+        timeout_ms: Option<u64>,
+        legacy: &ComparedSide,
+        new: &ComparedSide
+    ) -> (Vec<Difference>, Option<String>) {
+        let dir = std::env::temp_dir().join(format!("comparator-{}", uuid::Uuid::new_v4()));
+        println!("⚖️ Executando comparador externo: {}", command);
+
+        let result = Self::write_external_side(&dir.join("legacy"), legacy)
+            .and_then(|_| Self::write_external_side(&dir.join("new"), new))
+            .and_then(|_| Self::invoke_external(command, timeout_ms.unwrap_or(DEFAULT_EXTERNAL_TIMEOUT_MS), &dir, legacy, new));
+        let _ = fs::remove_dir_all(&dir);
+
+        match result {
+            Ok(verdict) if verdict.equal => (Vec::new(), verdict.diff),
+            Ok(verdict) if verdict.differences.is_empty() => {
+                (vec![Difference::new("comparador externo: saídas diferentes", None, None)], verdict.diff)
+            },
+            Ok(verdict) => (verdict.differences, verdict.diff),
+            Err(e) => {
+                println!("❌ Comparador externo falhou: {}", e);
+                (vec![Difference::new(&format!("comparador externo: {}", e), None, None)], None)
+            },
+        }
+    }
+
+    /// Diretório de um lado para o comparador: `stdout`, `stderr`, `exit_code`,
+    /// `effects.json` e os arquivos criados/modificados em `files/`
+    fn write_external_side(dir: &Path, side: &ComparedSide) -> Result<(), String> {
+        let files = dir.join("files");
+        fs::create_dir_all(&files)
+            .map_err(|e| format!("Erro ao criar {}: {}", files.display(), e))?;
+
+        let exit_code = side.output.exit_code.map(|c| c.to_string()).unwrap_or_default();
+        let effects: Vec<FileEffect> = side.effects.iter()
+            .map(|effect| FileEffect { content: None, ..effect.clone() })
+            .collect();
+        let effects = serde_json::to_string_pretty(&effects)
+            .map_err(|e| format!("Erro ao serializar efeitos: {}", e))?;
+
+        // Bytes originais, sem a transcodificação feita para comparar texto
+        for (name, content) in [
+            ("stdout", side.output.stdout_raw()),
+            ("stderr", side.output.stderr_raw()),
+            ("exit_code", exit_code.into_bytes()),
+            ("effects.json", effects.into_bytes()),
+        ] {
+            fs::write(dir.join(name), content)
+                .map_err(|e| format!("Erro ao gravar {}: {}", name, e))?;
+        }
+
+        // Saídas grandes: o arquivo completo no lugar da prévia
+        for (name, capture) in [("stdout", &side.output.stdout_capture), ("stderr", &side.output.stderr_capture)] {
+            if let Some(capture) = capture {
+                fs::copy(&capture.file, dir.join(name))
+                    .map_err(|e| format!("Erro ao copiar {}: {}", capture.file, e))?;
+            }
+        }
+
+        for effect in side.effects.iter().filter(|e| e.change != "deleted") {
+            let target = files.join(&effect.path);
+            if let Some(parent) = target.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+
+            let copied = match (side.dir, &effect.content) {
+                (Some(source), _) => fs::copy(source.join(&effect.path), &target).map(|_| ()),
+                (None, Some(content)) => fs::write(&target, content),
+                // Snapshot sem o conteúdo (arquivo grande ou binário): só consta em effects.json
+                (None, None) => continue,
+            };
+            copied.map_err(|e| format!("Erro ao copiar {}: {}", effect.path, e))?;
+        }
+
+        Ok(())
+    }
+
+    fn invoke_external(
+        command: &str,
+        timeout_ms: u64,
+        dir: &Path,
+        legacy: &ComparedSide,
+        new: &ComparedSide
+    ) -> Result<ExternalVerdict, String> {
+        let (stdout_path, stderr_path) = (dir.join("verdict.out"), dir.join("verdict.err"));
+        let stdout = File::create(&stdout_path).map_err(|e| format!("Erro ao criar saída: {}", e))?;
+        let stderr = File::create(&stderr_path).map_err(|e| format!("Erro ao criar saída: {}", e))?;
+
+        let mut process = Command::new("sh");
+        process.arg("-c")
+            .arg(command)
+            .arg("comparator")
+            .arg(dir.join("legacy"))
+            .arg(dir.join("new"))
+            .current_dir(dir)
+            .env("LEGACY_OUTPUT", dir.join("legacy"))
+            .env("NEW_OUTPUT", dir.join("new"))
+            .stdin(Stdio::null())
+            .stdout(stdout)
+            .stderr(stderr);
+        if let Some(root) = legacy.root {
+            process.env("LEGACY_ROOT", root);
+        }
+        if let Some(root) = new.root {
+            process.env("NEW_ROOT", root);
+        }
+        // Grupo próprio para o timeout encerrar também os filhos de `sh -c`
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            process.process_group(0);
+        }
+
+        let mut child = process.spawn()
+            .map_err(|e| format!("erro ao executar: {}", e))?;
+
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+        let status = loop {
+            match child.try_wait().map_err(|e| format!("erro aguardando: {}", e))? {
+                Some(status) => break status,
+                None if Instant::now() >= deadline => {
+                    TestRunner::kill_group(child.id());
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(format!("tempo esgotado ({}ms)", timeout_ms));
+                },
+                None => thread::sleep(Duration::from_millis(20)),
+            }
+        };
+
+        let output = fs::read_to_string(&stdout_path).unwrap_or_default();
+        if let Ok(verdict) = serde_json::from_str::<ExternalVerdict>(output.trim()) {
+            return Ok(verdict);
+        }
+
+        let diff = Some(output.clone()).filter(|o| !o.trim().is_empty());
+        match status.code() {
+            Some(0) => Ok(ExternalVerdict { equal: true, diff, differences: Vec::new() }),
+            Some(1) => Ok(ExternalVerdict { equal: false, diff, differences: Vec::new() }),
+            code => {
+                let errors = fs::read_to_string(&stderr_path).unwrap_or_default();
+                Err(format!(
+                    "código {} - {}",
+                    code.map(|c| c.to_string()).unwrap_or("(sinal)".to_string()),
+                    errors.lines().last().unwrap_or("").trim()
+                ))
+            },
+        }
+    }
+
+    fn xml_name(node: roxmltree::Node) -> String {
+        match node.tag_name().namespace() {
+            Some(namespace) => format!("{{{}}}{}", namespace, node.tag_name().name()),
+            None => node.tag_name().name().to_string(),
+        }
+    }
+
+    fn xml_attribute_name(attribute: &roxmltree::Attribute) -> String {
+        match attribute.namespace() {
+            Some(namespace) => format!("{{{}}}{}", namespace, attribute.name()),
+            None => attribute.name().to_string(),
+        }
+    }
+
+    /// Texto direto do elemento (sem o dos filhos), com espaços normalizados;
+    /// a indentação entre elementos filhos nunca conta
+    fn xml_text(node: roxmltree::Node, preserve_whitespace: bool) -> String {
+        let has_elements = node.children().any(|n| n.is_element());
+        let text: String = node.children()
+            .filter(|n| n.is_text())
+            .filter_map(|n| n.text())
+            .filter(|t| !(has_elements && t.trim().is_empty()))
+            .collect();
+
+        if preserve_whitespace {
+            text
+        } else {
+            text.split_whitespace().collect::<Vec<_>>().join(" ")
+        }
+    }
+
+    fn parse_json(text: &str, label: &str) -> Result<Value, String> {
+        serde_json::from_str(text.trim()).map_err(|e| format!("JSON inválido no {}: {}", label, e))
+    }
+
+    /// Linhas da tabela; espaços em volta das células e linhas vazias são ignorados
+    fn parse_csv(text: &str, delimiter: char, label: &str) -> Result<Vec<Vec<String>>, String> {
+        let delimiter = u8::try_from(delimiter)
+            .map_err(|_| format!("Delimitador CSV deve ser um caractere ASCII: {}", delimiter))?;
+
+        csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .has_headers(false)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(text.trim().as_bytes())
+            .records()
+            .map(|record| record
+                .map(|r| r.iter().map(|cell| cell.to_string()).collect())
+                .map_err(|e| format!("CSV inválido no {}: {}", label, e)))
+            .filter(|row: &Result<Vec<String>, String>| !matches!(row, Ok(cells) if cells.iter().all(|c| c.is_empty())))
+            .collect()
+    }
+
+    /// Erros de leitura na raiz; o lado que pôde ser lido aparece como "válido"
+    fn parse_errors(path: &str, legacy: Option<String>, new: Option<String>) -> Vec<Difference> {
+        let valid = || Some("válido".to_string());
+        vec![Difference::new(path, legacy.or_else(valid), new.or_else(valid))]
+    }
+
+    fn rows_match(a: &BTreeMap<String, String>, b: &BTreeMap<String, String>, tolerance: f64) -> bool {
+        a.iter().all(|(column, x)| b.get(column).is_some_and(|y| Self::cells_match(x, y, tolerance)))
+    }
+
+    /// Células iguais, ou números iguais dentro da tolerância ("1.50" e "1.5")
+    fn cells_match(a: &str, b: &str, tolerance: f64) -> bool {
+        a == b || Self::numbers_match(a.parse().ok(), b.parse().ok(), tolerance)
+    }
+
+    fn numbers_match(a: Option<f64>, b: Option<f64>, tolerance: f64) -> bool {
+        match (a, b) {
+            (Some(a), Some(b)) => (a - b).abs() <= tolerance,
+            _ => false,
+        }
+    }
+
+    /// Primeira linha em que os textos divergem
+    fn first_line_difference(legacy: &str, new: &str) -> Difference {
+        let legacy_lines: Vec<&str> = legacy.trim().lines().collect();
+        let new_lines: Vec<&str> = new.trim().lines().collect();
+
+        for i in 0..legacy_lines.len().max(new_lines.len()) {
+            let (a, b) = (legacy_lines.get(i), new_lines.get(i));
+            if a != b {
+                return Difference::new(
+                    &format!("linha {}", i + 1),
+                    a.map(|l| l.to_string()),
+                    b.map(|l| l.to_string())
+                );
+            }
+        }
+
+        Difference::new("saída", Some(legacy.to_string()), Some(new.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn json_paths(legacy: Value, new: Value, ignore_array_order: bool, tolerance: f64) -> Vec<String> {
+        let mut differences = Vec::new();
+        OutputComparator::json_differences("$", &legacy, &new, ignore_array_order, tolerance, &mut differences);
+        differences.into_iter().map(|d| d.path).collect()
+    }
+
+    fn table(rows: &[&[&str]]) -> Vec<Vec<String>> {
+        rows.iter().map(|r| r.iter().map(|c| c.to_string()).collect()).collect()
+    }
+
+    fn csv_paths(legacy: &[&[&str]], new: &[&[&str]], ignore_column_order: bool, ignore_row_order: bool, tolerance: f64) -> Vec<String> {
+        let mut differences = Vec::new();
+        OutputComparator::csv_differences(&table(legacy), &table(new), true, ignore_column_order, ignore_row_order, tolerance, &mut differences);
+        differences.into_iter().map(|d| d.path).collect()
+    }
+
+    #[test]
+    fn json_ignores_key_order() {
+        let legacy = json!({"nome": "Ana", "itens": [{"id": 1, "qtd": 2}]});
+        let new = json!({"itens": [{"qtd": 2, "id": 1}], "nome": "Ana"});
+
+        assert!(json_paths(legacy, new, false, 0.0).is_empty());
+    }
+
+    #[test]
+    fn json_reports_missing_and_changed_keys_by_path() {
+        let legacy = json!({"a": {"b": 1, "c": 2}});
+        let new = json!({"a": {"b": 3, "d": 2}});
+
+        assert_eq!(json_paths(legacy, new, false, 0.0), ["$.a.b", "$.a.c", "$.a.d"]);
+    }
+
+    #[test]
+    fn json_numbers_use_value_and_tolerance() {
+        assert!(json_paths(json!({"x": 1}), json!({"x": 1.0}), false, 0.0).is_empty());
+        assert!(json_paths(json!({"x": 0.1}), json!({"x": 0.1004}), false, 0.001).is_empty());
+        assert_eq!(json_paths(json!({"x": 0.1}), json!({"x": 0.1004}), false, 0.0), ["$.x"]);
+        assert_eq!(json_paths(json!({"x": "1"}), json!({"x": 1}), false, 1.0), ["$.x"]);
+    }
+
+    #[test]
+    fn json_array_order_only_ignored_when_asked() {
+        let legacy = json!([1, 2, 3]);
+        let new = json!([3, 1, 2]);
+
+        assert_eq!(json_paths(legacy.clone(), new.clone(), false, 0.0), ["$[0]", "$[1]", "$[2]"]);
+        assert!(json_paths(legacy, new, true, 0.0).is_empty());
+        assert_eq!(json_paths(json!([1, 1, 2]), json!([1, 2, 2]), true, 0.0), ["$[1]", "$[2]"]);
+    }
+
+    #[test]
+    fn csv_matches_columns_by_header() {
+        let legacy: &[&[&str]] = &[&["id", "nome"], &["1", "Ana"], &["2", "Bia"]];
+        let new: &[&[&str]] = &[&["nome", "id"], &["Ana", "1"], &["Bia", "2"]];
+
+        assert!(csv_paths(legacy, new, true, false, 0.0).is_empty());
+        assert_eq!(csv_paths(legacy, new, false, false, 0.0), ["cabeçalho"]);
+    }
+
+    #[test]
+    fn csv_row_order_only_ignored_when_asked() {
+        let legacy: &[&[&str]] = &[&["id", "nome"], &["1", "Ana"], &["2", "Bia"]];
+        let new: &[&[&str]] = &[&["id", "nome"], &["2", "Bia"], &["1", "Ana"]];
+
+        assert!(csv_paths(legacy, new, false, true, 0.0).is_empty());
+        assert_eq!(csv_paths(legacy, new, false, false, 0.0), ["[0].id", "[0].nome", "[1].id", "[1].nome"]);
+    }
+
+    #[test]
+    fn csv_reports_missing_columns_and_rows() {
+        let legacy: &[&[&str]] = &[&["id", "nome"], &["1", "Ana"], &["2", "Bia"]];
+        let new: &[&[&str]] = &[&["id", "email"], &["1", "ana@x"]];
+
+        assert_eq!(csv_paths(legacy, new, true, false, 0.0), ["coluna nome", "coluna email", "[1]"]);
+        assert_eq!(csv_paths(legacy, new, false, false, 0.0), ["cabeçalho", "coluna nome", "coluna email", "[1]"]);
+    }
+
+    #[test]
+    fn csv_cells_compare_numbers_with_tolerance() {
+        let legacy: &[&[&str]] = &[&["item", "total"], &["a", "1.50"], &["b", "2.001"]];
+        let new: &[&[&str]] = &[&["item", "total"], &["a", "1.5"], &["b", "2.0"]];
+
+        assert!(csv_paths(legacy, new, false, false, 0.01).is_empty());
+        assert_eq!(csv_paths(legacy, new, false, false, 0.0), ["[1].total"]);
+    }
+
+    fn xml_paths(legacy: &str, new: &str, preserve_whitespace: bool) -> Vec<String> {
+        OutputComparator::compare_text(&Comparator::Xml { preserve_whitespace }, legacy, new)
+            .into_iter()
+            .map(|d| d.path)
+            .collect()
+    }
+
+    fn external_paths(command: &str, timeout_ms: Option<u64>, legacy: &str, new: &str) -> Vec<String> {
+        let comparator = Comparator::External { command: command.to_string(), timeout_ms };
+        OutputComparator::compare_text(&comparator, legacy, new)
+            .into_iter()
+            .map(|d| d.path)
+            .collect()
+    }
+
+    #[test]
+    fn xml_ignores_attribute_order_and_declaration() {
+        let legacy = r#"<?xml version="1.0"?><pedido id="1" status="ok"><!-- gerado --><item qtd="2"/></pedido>"#;
+        let new = r#"<pedido status="ok" id="1"><item qtd="2"/></pedido>"#;
+
+        assert!(xml_paths(legacy, new, false).is_empty());
+        assert_eq!(xml_paths(legacy, r#"<pedido id="2" status="ok" extra="x"><item qtd="2"/></pedido>"#, false),
+                   ["/pedido/@extra", "/pedido/@id"]);
+    }
+
+    #[test]
+    fn xml_whitespace_only_counts_inside_text_when_preserved() {
+        let legacy = "<lista>\n  <nome>Ana  Maria</nome>\n  <nome>Bia</nome>\n</lista>";
+        let new = "<lista><nome> Ana Maria </nome><nome>Bia</nome></lista>";
+
+        assert!(xml_paths(legacy, new, false).is_empty());
+        assert_eq!(xml_paths(legacy, new, true), ["/lista/nome[1]/text()"]);
+    }
+
+    #[test]
+    fn xml_text_excludes_children_text() {
+        let legacy = "<a>total: <b>1</b><b>2</b></a>";
+        let new = "<a>total: <b>1</b><b>3</b><c/></a>";
+
+        assert_eq!(xml_paths(legacy, new, false), ["/a/b[2]/text()", "/a/c[1]"]);
+        assert_eq!(xml_paths("<a>x<b/></a>", "<a>y<b/></a>", false), ["/a/text()"]);
+    }
+
+    #[test]
+    fn external_comparator_answers_by_exit_code() {
+        let command = r#"cmp -s "$1/stdout" "$2/stdout""#;
+
+        assert!(external_paths(command, None, "abc", "abc").is_empty());
+        assert_eq!(external_paths(command, None, "abc", "abd"), ["comparador externo: saídas diferentes"]);
+    }
+
+    #[test]
+    fn external_comparator_failure_is_a_difference() {
+        assert_eq!(external_paths("echo quebrou >&2; exit 3", None, "a", "a"), ["comparador externo: código 3 - quebrou"]);
+    }
+
+    #[test]
+    fn external_comparator_times_out() {
+        let started = Instant::now();
+
+        assert_eq!(external_paths("sleep 5; exit 0", Some(200), "a", "a"), ["comparador externo: tempo esgotado (200ms)"]);
+        assert!(started.elapsed() < Duration::from_secs(4));
+    }
+}
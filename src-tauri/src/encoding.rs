@@ -0,0 +1,199 @@
+use encoding_rs::Encoding;
+
+/// Nome usado quando a codificação é detectada a partir dos bytes
+pub const AUTO_ENCODING: &str = "auto";
+
+/// Bytes por linha no dump hexadecimal
+const HEX_ROW: usize = 16;
+
+/// Linhas do dump exibidas antes e depois da primeira diferença
+const HEX_CONTEXT_ROWS: usize = 4;
+
+/// Code page 850 (DOS Latin-1), comum em sistemas legados e ausente do
+/// encoding_rs: caracteres dos bytes 0x80-0xFF
+const CP850_HIGH: [char; 128] = [
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å',
+    'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', 'ø', '£', 'Ø', '×', 'ƒ',
+    'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '®', '¬', '½', '¼', '¡', '«', '»',
+    '░', '▒', '▓', '│', '┤', 'Á', 'Â', 'À', '©', '╣', '║', '╗', '╝', '¢', '¥', '┐',
+    '└', '┴', '┬', '├', '─', '┼', 'ã', 'Ã', '╚', '╔', '╩', '╦', '╠', '═', '╬', '¤',
+    'ð', 'Ð', 'Ê', 'Ë', 'È', 'ı', 'Í', 'Î', 'Ï', '┘', '┌', '█', '▄', '¦', 'Ì', '▀',
+    'Ó', 'ß', 'Ô', 'Ò', 'õ', 'Õ', 'µ', 'þ', 'Þ', 'Ú', 'Û', 'Ù', 'ý', 'Ý', '¯', '´',
+    '\u{AD}', '±', '‗', '¾', '¶', '§', '÷', '¸', '°', '¨', '·', '¹', '³', '²', '■', '\u{A0}',
+];
+
+/// Decodificação das saídas dos programas conforme a codificação de cada lado
+/// (`legacyEncoding`/`newEncoding`) e comparação byte a byte de saídas binárias
+pub struct OutputEncoding;
+
+impl OutputEncoding {
+    /// Aceita "auto", "cp850"/"ibm850" e os rótulos do WHATWG ("iso-8859-1",
+    /// "windows-1252", "shift_jis", "utf-16le"...)
+    pub fn validate(label: &str) -> Result<(), String> {
+        if Self::is_auto(label) || Self::is_cp850(label) || Encoding::for_label(label.trim().as_bytes()).is_some() {
+            Ok(())
+        } else {
+            Err(format!("Codificação desconhecida: {}", label))
+        }
+    }
+
+    /// Texto em UTF-8 e o nome da codificação usada. Sem configuração (ou "auto"):
+    /// BOM, depois UTF-8 válido, senão Windows-1252 (superconjunto do ISO-8859-1)
+    pub fn decode(bytes: &[u8], setting: Option<&str>) -> (String, &'static str) {
+        let label = setting.map(str::trim).filter(|l| !Self::is_auto(l));
+
+        if let Some(label) = label {
+            if Self::is_cp850(label) {
+                return (Self::decode_cp850(bytes), "IBM850");
+            }
+
+            if let Some(encoding) = Encoding::for_label(label.as_bytes()) {
+                let (text, _, _) = encoding.decode(bytes);
+                return (text.into_owned(), encoding.name());
+            }
+        }
+
+        if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+            let (text, _, _) = encoding.decode(bytes);
+            return (text.into_owned(), encoding.name());
+        }
+
+        match std::str::from_utf8(bytes) {
+            Ok(text) => (text.to_string(), encoding_rs::UTF_8.name()),
+            Err(_) => {
+                let (text, _, _) = encoding_rs::WINDOWS_1252.decode(bytes);
+                (text.into_owned(), encoding_rs::WINDOWS_1252.name())
+            },
+        }
+    }
+
+    /// Saída binária: tem byte nulo, ou mais de 10% de caracteres de controle
+    /// fora de tab/quebras de linha/ESC
+    pub fn is_binary(bytes: &[u8]) -> bool {
+        if bytes.contains(&0) {
+            return true;
+        }
+
+        let control = bytes.iter()
+            .filter(|b| **b < 0x20 && !matches!(**b, b'\t' | b'\n' | b'\r' | 0x0c | 0x1b))
+            .count();
+        !bytes.is_empty() && control * 10 > bytes.len()
+    }
+
+    /// Posição do primeiro byte diferente (ou do fim da menor saída)
+    pub fn first_difference(legacy: &[u8], new: &[u8]) -> Option<usize> {
+        if legacy == new {
+            return None;
+        }
+
+        Some(legacy.iter().zip(new.iter())
+            .position(|(a, b)| a != b)
+            .unwrap_or(legacy.len().min(new.len())))
+    }
+
+    /// Dump hexadecimal dos dois lados em volta da primeira diferença, com as
+    /// linhas que diferem marcadas com `*`
+    pub fn hex_diff(legacy: &[u8], new: &[u8]) -> Option<String> {
+        let offset = Self::first_difference(legacy, new)?;
+        let first_row = (offset / HEX_ROW).saturating_sub(HEX_CONTEXT_ROWS);
+        let last_row = offset / HEX_ROW + HEX_CONTEXT_ROWS;
+
+        let mut dump = format!("Primeira diferença no byte {} (0x{:08x})\n", offset, offset);
+        for (label, bytes, other) in [("legado", legacy, new), ("novo", new, legacy)] {
+            dump.push_str(&format!("\n{} ({} bytes):\n", label, bytes.len()));
+
+            for row in first_row..=last_row {
+                let start = row * HEX_ROW;
+                if start >= bytes.len() && start >= other.len() {
+                    break;
+                }
+
+                let chunk = bytes.get(start..bytes.len().min(start + HEX_ROW)).unwrap_or(&[]);
+                let other_chunk = other.get(start..other.len().min(start + HEX_ROW)).unwrap_or(&[]);
+                let hex: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+                let ascii: String = chunk.iter()
+                    .map(|b| if b.is_ascii_graphic() || *b == b' ' { *b as char } else { '.' })
+                    .collect();
+
+                dump.push_str(&format!(
+                    "{} {:08x}  {:<47}  |{}|\n",
+                    if chunk != other_chunk { '*' } else { ' ' },
+                    start,
+                    hex.join(" "),
+                    ascii
+                ));
+            }
+        }
+
+        Some(dump)
+    }
+
+    fn decode_cp850(bytes: &[u8]) -> String {
+        bytes.iter()
+            .map(|b| if *b < 0x80 { *b as char } else { CP850_HIGH[(*b - 0x80) as usize] })
+            .collect()
+    }
+
+    fn is_auto(label: &str) -> bool {
+        label.trim().is_empty() || label.trim().eq_ignore_ascii_case(AUTO_ENCODING)
+    }
+
+    fn is_cp850(label: &str) -> bool {
+        matches!(label.trim().to_lowercase().replace(['-', '_'], "").as_str(), "cp850" | "ibm850" | "850" | "dos850")
+    }
+}
+
+/// Decodificação de uma saída que chega aos pedaços (pseudo-terminal): a
+/// codificação é fixada no primeiro pedaço e cada byte é decodificado uma só vez
+pub struct StreamDecoder {
+    /// `None` para CP850, que é de um byte por caractere
+    decoder: Option<encoding_rs::Decoder>,
+}
+
+impl StreamDecoder {
+    /// Mesma escolha de `OutputEncoding::decode`, feita pelo primeiro pedaço; uma
+    /// sequência UTF-8 cortada no fim do pedaço ainda conta como UTF-8 válido
+    pub fn new(first: &[u8], setting: Option<&str>) -> Self {
+        let label = setting.map(str::trim).filter(|l| !OutputEncoding::is_auto(l));
+
+        if let Some(label) = label {
+            if OutputEncoding::is_cp850(label) {
+                return Self { decoder: None };
+            }
+
+            if let Some(encoding) = Encoding::for_label(label.as_bytes()) {
+                return Self::with(encoding);
+            }
+        }
+
+        if let Some((encoding, _)) = Encoding::for_bom(first) {
+            return Self::with(encoding);
+        }
+
+        match std::str::from_utf8(first) {
+            Err(e) if e.error_len().is_some() => Self::with(encoding_rs::WINDOWS_1252),
+            _ => Self::with(encoding_rs::UTF_8),
+        }
+    }
+
+    fn with(encoding: &'static Encoding) -> Self {
+        Self { decoder: Some(encoding.new_decoder()) }
+    }
+
+    /// Texto do pedaço; bytes de um caractere incompleto ficam para o próximo
+    pub fn decode(&mut self, chunk: &[u8], last: bool) -> String {
+        let decoder = match &mut self.decoder {
+            Some(decoder) => decoder,
+            None => return OutputEncoding::decode_cp850(chunk),
+        };
+
+        let mut text = String::with_capacity(decoder.max_utf8_buffer_length(chunk.len()).unwrap_or(chunk.len() * 3));
+        let _ = decoder.decode_to_string(chunk, &mut text, last);
+        text
+    }
+
+    /// Nome da codificação usada (depois do BOM, se houver)
+    pub fn name(&self) -> &'static str {
+        self.decoder.as_ref().map(|d| d.encoding().name()).unwrap_or("IBM850")
+    }
+}
@@ -0,0 +1,306 @@
+use crate::ai::{FullTestFromAI, ProjectInfo};
+use crate::build_cache::BuildCache;
+use crate::build_system::BuildDetector;
+use crate::language::LanguageDetector;
+use crate::project::ProjectService;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+/// Nomes de arquivo comuns para pontos de entrada (ordem de prioridade)
+const PRIORITY_NAMES: [&str; 13] = [
+    "main", "app", "index", "start", "run", "cli", "server", "client",
+    "entry", "init", "bootstrap", "program", "application",
+];
+
+/// Quanto do arquivo é lido para procurar `main`/shebang
+const CONTENT_SCAN_LIMIT: u64 = 64 * 1024;
+
+/// Ponto de entrada fixado em um lado do projeto (`legacyEntryPoints`/`newEntryPoints`)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EntryPoint {
+    /// Nome usado pelos testes (`entryPoint`) para escolher o programa
+    pub name: String,
+    /// Caminho relativo à raiz do lado
+    pub path: String,
+}
+
+/// Arquivo candidato a ponto de entrada, com os motivos da pontuação
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EntryPointCandidate {
+    pub path: String,
+    #[serde(rename = "relativePath")]
+    pub relative_path: String,
+    pub score: i32,
+    pub reasons: Vec<String>,
+}
+
+/// Descoberta, fixação e resolução dos pontos de entrada de cada lado
+pub struct EntryPointService;
+
+impl EntryPointService {
+    /// Lista os arquivos de código do diretório ordenados por pontuação (maior primeiro)
+    pub fn discover(project_path: &str) -> Result<Vec<EntryPointCandidate>, String> {
+        let root = Path::new(project_path);
+        if !root.is_dir() {
+            return Err(format!("Diretório não encontrado: {}", project_path));
+        }
+
+        let manifest_entries = Self::manifest_entries(root);
+        let mut candidates = Vec::new();
+
+        for (path, _) in LanguageDetector::source_files(root) {
+            if !LanguageDetector::is_program(&path) {
+                continue;
+            }
+
+            let path = path.as_path();
+            let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+
+            let relative = path.strip_prefix(root).unwrap_or(path);
+            let (name_score, mut reasons) = Self::calculate_executable_score(relative);
+            let mut score = name_score;
+
+            for (bonus, reason) in Self::content_signals(path, extension) {
+                score += bonus;
+                reasons.push(reason);
+            }
+
+            if let Some(manifest) = manifest_entries.iter().find(|(p, _)| p == relative) {
+                score += 60;
+                reasons.push(format!("declarado em {}", manifest.1));
+            }
+
+            candidates.push(EntryPointCandidate {
+                path: path.to_string_lossy().to_string(),
+                relative_path: relative.to_string_lossy().replace('\\', "/"),
+                score: score.max(0),
+                reasons,
+            });
+        }
+
+        candidates.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.relative_path.cmp(&b.relative_path)));
+        Ok(candidates)
+    }
+
+    /// Pontos de entrada fixados de um lado ("legacy" ou "new")
+    pub fn pinned<'a>(project: &'a ProjectInfo, side: &str) -> &'a [EntryPoint] {
+        if side == "legacy" { &project.legacy_entry_points } else { &project.new_entry_points }
+    }
+
+    /// Valida os pontos de entrada de um lado: nomes únicos e arquivos dentro da raiz
+    pub fn validate(root: &str, entry_points: &[EntryPoint], label: &str) -> Result<(), String> {
+        for (i, entry) in entry_points.iter().enumerate() {
+            if entry.name.trim().is_empty() {
+                return Err(format!("Ponto de entrada do {} sem nome: {}", label, entry.path));
+            }
+
+            if entry_points[..i].iter().any(|e| e.name == entry.name) {
+                return Err(format!("Ponto de entrada duplicado no {}: {}", label, entry.name));
+            }
+
+            let relative = Path::new(&entry.path);
+            if !relative.components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir)) {
+                return Err(format!("Ponto de entrada do {} deve ser relativo à raiz: {}", label, entry.path));
+            }
+
+            if !Path::new(root).join(relative).is_file() {
+                return Err(format!("Ponto de entrada do {} não encontrado: {}", label, entry.path));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Caminho absoluto do ponto de entrada de um lado: o fixado com o nome pedido,
+    /// o primeiro fixado, ou o melhor candidato encontrado
+    pub fn resolve(project: &ProjectInfo, side: &str, name: Option<&str>) -> Result<PathBuf, String> {
+        let root = if side == "legacy" { &project.legacy_path } else { &project.new_path };
+        let pinned = Self::pinned(project, side);
+
+        let entry = match name {
+            Some(name) => Some(pinned.iter().find(|e| e.name == name).ok_or(format!(
+                "Ponto de entrada '{}' não fixado no lado {}",
+                name, side
+            ))?),
+            None => pinned.first(),
+        };
+
+        if let Some(entry) = entry {
+            return Ok(Path::new(root).join(&entry.path));
+        }
+
+        Self::discover(root)?
+            .into_iter()
+            .next()
+            .map(|c| PathBuf::from(c.path))
+            .ok_or(format!("Nenhum arquivo de código encontrado em: {}", root))
+    }
+
+    /// Ambiente de build para um teste: quando o teste aponta para um ponto de
+    /// entrada, compila-o e expõe `ENTRY_POINT` (programa pronto) e `ENTRY_SOURCE`
+    pub fn prepare(
+        cache: &BuildCache,
+        project: &ProjectInfo,
+        side: &str,
+        test: &FullTestFromAI
+    ) -> Result<BuildCache, String> {
+        let name = match &test.entry_point {
+            Some(name) => name,
+            None => return Ok(cache.clone()),
+        };
+
+        let source = Self::resolve(project, side, Some(name))?;
+        let source = source.to_string_lossy().to_string();
+        let executable = ProjectService::ensure_executable(&source, cache)?;
+
+        Ok(cache.with_entry_point(&source, &executable))
+    }
+
+    /// Pontuação pelo nome e pela posição do arquivo, com os motivos
+    fn calculate_executable_score(relative: &Path) -> (i32, Vec<String>) {
+        let mut score = 0;
+        let mut reasons = Vec::new();
+
+        let name_lower = relative.file_name()
+            .map(|n| n.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let name_without_ext = match name_lower.rfind('.') {
+            Some(dot_pos) => &name_lower[..dot_pos],
+            None => &name_lower[..],
+        };
+
+        // Nome exatamente igual a um dos prioritários (maior score no início da lista)
+        if let Some(i) = PRIORITY_NAMES.iter().position(|p| name_without_ext == *p) {
+            score += 100 - (i as i32 * 5);
+            reasons.push(format!("nome '{}'", name_without_ext));
+        }
+
+        // Palavras-chave de executáveis no nome
+        if name_lower.contains("main") || name_lower.contains("start") || name_lower.contains("run") {
+            score += 50;
+        }
+        if name_lower.contains("app") || name_lower.contains("cli") || name_lower.contains("server") {
+            score += 40;
+        }
+        if name_lower.contains("index") || name_lower.contains("entry") || name_lower.contains("init") {
+            score += 30;
+        }
+
+        // Arquivo na raiz do projeto (provavelmente o ponto de entrada)
+        if relative.components().count() == 1 {
+            score += 20;
+            reasons.push("na raiz do projeto".to_string());
+        }
+
+        // Penalidade por ser arquivo de configuração ou testes
+        let path_lower = relative.to_string_lossy().to_lowercase();
+        if name_lower.contains("config") || path_lower.contains("test") ||
+           name_lower.contains("spec") || name_lower.starts_with('.') {
+            score -= 30;
+            reasons.push("parece configuração ou teste".to_string());
+        }
+
+        // Linguagens comuns para CLI
+        score += match relative.extension().and_then(|e| e.to_str()).unwrap_or("") {
+            "sh" | "bash" => 15,
+            "rs" | "go" => 12,
+            "py" => 10,
+            "js" | "mjs" | "ts" => 8,
+            _ => 0,
+        };
+
+        (score, reasons)
+    }
+
+    /// Sinais no conteúdo do arquivo: shebang, programa principal (`main`, `PROCEDURE
+    /// DIVISION`, `PROGRAM`...), bloco `__main__` e bit de execução
+    fn content_signals(path: &Path, extension: &str) -> Vec<(i32, String)> {
+        let mut signals = Vec::new();
+
+        if ProjectService::has_shebang(path) {
+            signals.push((25, "shebang".to_string()));
+        }
+
+        if ProjectService::is_executable(&path.to_string_lossy()) {
+            signals.push((15, "bit de execução".to_string()));
+        }
+
+        let mut data = Vec::new();
+        if let Ok(file) = fs::File::open(path) {
+            let _ = file.take(CONTENT_SCAN_LIMIT).read_to_end(&mut data);
+        }
+        let content = String::from_utf8_lossy(&data);
+
+        let main_pattern = match extension.to_lowercase().as_str() {
+            "c" | "cpp" | "cc" | "cxx" => Some((r"(?m)^\s*(int|void)\s+main\s*\(", "função main")),
+            "rs" => Some((r"(?m)^\s*(pub\s+)?(async\s+)?fn\s+main\s*\(", "função main")),
+            "go" => Some((r"(?m)^\s*func\s+main\s*\(\s*\)", "função main")),
+            "java" | "cs" => Some((r"(public\s+)?static\s+(void|int)\s+[Mm]ain\s*\(", "função main")),
+            "cbl" | "cob" | "cobol" => Some((r"(?i)PROCEDURE\s+DIVISION", "PROCEDURE DIVISION")),
+            "f" | "for" | "f77" | "f90" | "f95" | "f03" | "ftn" => Some((r"(?im)^\s*program\s+\w+", "bloco PROGRAM")),
+            "pas" | "pp" | "dpr" | "lpr" => Some((r"(?im)^\s*program\s+\w+\s*;", "bloco program")),
+            "bas" | "vb" => Some((r"(?im)^\s*(public\s+)?sub\s+main\s*\(", "Sub Main")),
+            _ => None,
+        };
+
+        if let Some((pattern, reason)) = main_pattern {
+            if Regex::new(pattern).map(|re| re.is_match(&content)).unwrap_or(false) {
+                signals.push((60, reason.to_string()));
+            }
+        }
+
+        if extension == "py" {
+            let guard = Regex::new(r#"if\s+__name__\s*==\s*['"]__main__['"]"#).unwrap();
+            if guard.is_match(&content) {
+                signals.push((60, "bloco if __name__ == \"__main__\"".to_string()));
+            } else if path.file_name().is_some_and(|n| n == "__main__.py") {
+                signals.push((60, "__main__.py do pacote".to_string()));
+            }
+        }
+
+        signals
+    }
+
+    /// Arquivos apontados por manifestos na raiz (`package.json` main/bin,
+    /// `src/main.rs` de pacotes Cargo), com o nome do manifesto
+    fn manifest_entries(root: &Path) -> Vec<(PathBuf, &'static str)> {
+        let mut entries = Vec::new();
+
+        if let Some(package) = fs::read_to_string(root.join("package.json")).ok()
+            .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok()) {
+            if let Some(main) = package.get("main").and_then(|m| m.as_str()) {
+                entries.push((Self::normalize(main), "package.json"));
+            }
+
+            match package.get("bin") {
+                Some(serde_json::Value::String(bin)) => entries.push((Self::normalize(bin), "package.json")),
+                Some(serde_json::Value::Object(bins)) => entries.extend(bins.values()
+                    .filter_map(|b| b.as_str())
+                    .map(|b| (Self::normalize(b), "package.json"))),
+                _ => {}
+            }
+        }
+
+        if !BuildDetector::cargo_binaries(root).is_empty() {
+            entries.push((PathBuf::from("src").join("main.rs"), "Cargo.toml"));
+        }
+
+        if let Ok(bins) = fs::read_dir(root.join("src").join("bin")) {
+            entries.extend(bins.filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|e| e == "rs"))
+                .filter_map(|p| p.strip_prefix(root).ok().map(|p| (p.to_path_buf(), "Cargo.toml"))));
+        }
+
+        entries
+    }
+
+    fn normalize(path: &str) -> PathBuf {
+        Path::new(path).components()
+            .filter(|c| !matches!(c, Component::CurDir))
+            .collect()
+    }
+}
@@ -0,0 +1,479 @@
+use crate::ai::{FullTestFromAI, ProjectInfo, TestCase};
+use crate::build_cache::BuildCache;
+use crate::capture::OutputCapture;
+use crate::compare::{ComparedSide, Comparator, ComparisonResult, OutputComparator};
+use crate::entry_point::EntryPointService;
+use crate::parameters::ParameterExpander;
+use crate::project::ProjectService;
+use crate::runner::{SideOutput, TestRunner};
+use crate::settings::SettingsService;
+use crate::snapshot::SnapshotService;
+use crate::test_store::TestStore;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Entradas geradas por padrão em uma sessão de fuzzing
+const DEFAULT_ITERATIONS: usize = 100;
+
+/// Divergências salvas por padrão antes de parar
+const DEFAULT_MAX_DIVERGENCES: usize = 10;
+
+/// Tempo máximo de cada execução quando nem as opções nem o teste definem um
+const DEFAULT_RUN_TIMEOUT_MS: u64 = 10000;
+
+/// Tamanho das strings "enormes"
+const HUGE_STRING_LEN: usize = 10000;
+
+/// Números nos limites dos tipos comuns (inteiros de 32/64 bits, double)
+const BOUNDARY_NUMBERS: &[&str] = &[
+    "0", "-0", "1", "-1", "00", "0.0", "0.1", "-0.5",
+    "127", "128", "255", "256", "32767", "-32768", "65535", "65536",
+    "2147483647", "-2147483648", "2147483648", "4294967295", "4294967296",
+    "9223372036854775807", "-9223372036854775808", "18446744073709551616",
+    "1e308", "-1e308", "1e309", "4.9e-324", "NaN", "inf", "-inf", "0x10", "1,5",
+];
+
+/// Strings vazias, com espaços, unicode e caracteres especiais
+const SPECIAL_STRINGS: &[&str] = &[
+    "", " ", "  a  ", "\t", "a b", "a\nb", "\r\n",
+    "ção", "ÁÉÍÓÚ ñ ü", "日本語", "Ω≈ç√∫", "🦀🚀", "e\u{301}", "\u{202e}abc", "\u{feff}x",
+    "'", "\"", "\\", "`", "$HOME", "%s%n%x%d", "{}", "[]", "<>&", ";", "|", "*", "?",
+    "../../../etc/passwd", "/", ".", "-", "--", "-h", "--help", "--version",
+    "null", "NULL", "true", "false", "undefined",
+];
+
+/// Números dentro de um valor, trocados por números de limite
+static NUMBER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"-?\d+(\.\d+)?").unwrap());
+
+/// Opções de uma sessão de fuzzing
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct FuzzOptions {
+    /// Entradas geradas (padrão 100)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iterations: Option<usize>,
+    /// Semente do gerador, para repetir uma sessão (padrão: relógio)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Para depois de salvar esta quantidade de divergências (padrão 10)
+    #[serde(rename = "maxDivergences", default, skip_serializing_if = "Option::is_none")]
+    pub max_divergences: Option<usize>,
+    /// Tempo máximo de cada execução (padrão: timeout do teste ou 10s)
+    #[serde(rename = "timeoutMs", default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+/// Entrada gerada que fez legado e novo divergirem
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FuzzDivergence {
+    pub args: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stdin: Option<String>,
+    pub differences: Vec<String>,
+    #[serde(rename = "legacyOutput")]
+    pub legacy_output: String,
+    #[serde(rename = "newOutput")]
+    pub new_output: String,
+    /// ID do teste criado com a entrada
+    #[serde(rename = "savedAs", default, skip_serializing_if = "Option::is_none")]
+    pub saved_as: Option<String>,
+}
+
+/// Resultado de uma sessão de fuzzing
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FuzzReport {
+    #[serde(rename = "testId")]
+    pub test_id: String,
+    /// Semente usada (repete a sessão com `seed`)
+    pub seed: u64,
+    /// Entradas executadas (sem contar a original)
+    pub iterations: usize,
+    pub divergences: Vec<FuzzDivergence>,
+}
+
+/// Resultado de uma entrada executada nos dois lados
+pub struct DifferentialOutcome {
+    pub legacy: SideOutput,
+    pub new: SideOutput,
+    pub comparison: ComparisonResult,
+    /// Diferenças nos arquivos declarados em `trackedFiles`
+    pub effect_differences: Vec<String>,
+}
+
+impl DifferentialOutcome {
+    /// Mesma regra das execuções de testes: o que diverge aqui falha lá
+    pub fn diverges(&self) -> bool {
+        !self.comparison.passed(&self.effect_differences)
+    }
+
+    /// Diferenças de saída, de arquivos afetados e de exit code
+    pub fn differences(&self) -> Vec<String> {
+        let mut differences: Vec<String> = self.comparison.differences.iter()
+            .map(|d| d.describe())
+            .chain(self.effect_differences.iter().cloned())
+            .collect();
+        if !self.comparison.exit_codes_match {
+            differences.push(format!(
+                "exit code: legado {}, novo {}",
+                Self::exit_code(&self.legacy),
+                Self::exit_code(&self.new)
+            ));
+        }
+        differences
+    }
+
+    /// Identifica o tipo da divergência (exit codes, onde as saídas diferem e se
+    /// algum arquivo diverge), para não guardar várias entradas que quebram do mesmo jeito
+    pub fn signature(&self) -> String {
+        format!(
+            "{}|{}|{}|{}",
+            Self::exit_code(&self.legacy),
+            Self::exit_code(&self.new),
+            self.comparison.differences.first().map(|d| d.path.as_str()).unwrap_or_default(),
+            !self.effect_differences.is_empty()
+        )
+    }
+
+    fn exit_code(output: &SideOutput) -> String {
+        output.exit_code.map(|c| c.to_string()).unwrap_or_else(|| "nenhum".to_string())
+    }
+}
+
+/// Os dois lados de um teste de linha de comando compilados e prontos para
+/// rodar várias entradas seguidas, com timeout em cada execução
+pub struct DifferentialRun {
+    pub project: ProjectInfo,
+    pub test: FullTestFromAI,
+    legacy_env: BuildCache,
+    new_env: BuildCache,
+    comparator: Comparator,
+    timeout: Duration,
+    /// Saídas grandes das execuções, removidas no fim
+    outputs_dir: PathBuf,
+}
+
+impl DifferentialRun {
+    /// Prepara um teste salvo ou uma instância de um parametrizado (`abc[3]`);
+    /// pelo ID do próprio parametrizado, a primeira instância
+    pub async fn prepare(project_id: &str, test_id: &str, timeout_ms: Option<u64>, app: &tauri::AppHandle) -> Result<Self, String> {
+        let project = ProjectService::load_project(project_id, app).await?;
+        if project.project_type == "API" || project.project_type == "Web" {
+            return Err(format!("Projetos do tipo {} não executam testes por linha de comando", project.project_type));
+        }
+
+        let stored = match (TestStore::get(project_id, test_id, app).await, ParameterExpander::parent_id(test_id)) {
+            (Ok(stored), _) => stored,
+            (Err(_), Some(parent_id)) => TestStore::get(project_id, parent_id, app).await?,
+            (Err(e), None) => return Err(e),
+        };
+        let mut instances = ParameterExpander::expand(&project, &stored)?.into_iter();
+        let test = if stored.id == test_id {
+            instances.next()
+        } else {
+            instances.find(|instance| instance.id == test_id)
+        }.ok_or(format!("Instância '{}' do teste '{}' não encontrada", test_id, stored.name))?;
+        if test.interactive.is_some() {
+            return Err(format!("Teste '{}' é interativo; só testes com args/stdin podem ser variados", test.name));
+        }
+
+        let outputs_dir = std::env::temp_dir().join(format!("differential-{}", uuid::Uuid::new_v4()));
+        let legacy_cache = BuildCache::for_side(&project, "legacy", app)?.with_capture_dir(&outputs_dir);
+        let new_cache = BuildCache::for_side(&project, "new", app)?.with_capture_dir(&outputs_dir);
+        let legacy_env = EntryPointService::prepare(&legacy_cache, &project, "legacy", &test)?;
+        let new_env = EntryPointService::prepare(&new_cache, &project, "new", &test)?;
+
+        TestRunner::compile(&test.legacy_exec, &legacy_env, "legado")?;
+        TestRunner::compile(&test.new_exec, &new_env, "novo")?;
+
+        let timeout = Duration::from_millis(timeout_ms.or(test.timeout).unwrap_or(DEFAULT_RUN_TIMEOUT_MS));
+        let comparator = OutputComparator::for_test(&project, &test);
+
+        Ok(Self { project, test, legacy_env, new_env, comparator, timeout, outputs_dir })
+    }
+
+    /// Roda a entrada nos dois lados e compara, com os arquivos de `trackedFiles`
+    /// como nas execuções de testes
+    pub fn run(&self, args: &[String], stdin: Option<&str>) -> Result<DifferentialOutcome, String> {
+        let (legacy, legacy_effects) = SnapshotService::observe_effects(&self.test, &self.test.legacy_exec, || {
+            TestRunner::run_limited(&self.test.legacy_exec, args, stdin, Some(self.timeout), &self.legacy_env, "legado")
+        })?;
+        let (new, new_effects) = SnapshotService::observe_effects(&self.test, &self.test.new_exec, || {
+            TestRunner::run_limited(&self.test.new_exec, args, stdin, Some(self.timeout), &self.new_env, "novo")
+        })?;
+
+        let comparison = OutputComparator::compare(
+            &self.comparator,
+            &ComparedSide {
+                dir: Some(Path::new(&self.test.legacy_exec.working_directory)),
+                root: Some(&self.project.legacy_path),
+                effects: &legacy_effects,
+                ..ComparedSide::output(&legacy)
+            },
+            &ComparedSide {
+                dir: Some(Path::new(&self.test.new_exec.working_directory)),
+                root: Some(&self.project.new_path),
+                effects: &new_effects,
+                ..ComparedSide::output(&new)
+            }
+        );
+
+        let effect_differences = SnapshotService::compare_effects(&legacy_effects, &new_effects);
+
+        Ok(DifferentialOutcome { legacy, new, comparison, effect_differences })
+    }
+
+    /// Fixtures do projeto (`FIXTURES_DIR`, o mesmo para os dois lados)
+    pub fn fixtures_dir(&self) -> &Path {
+        self.legacy_env.fixtures_dir()
+    }
+
+    /// Resultado de um teste derivado, guardado como último resultado dele
+    pub fn test_case(test: &FullTestFromAI, outcome: &DifferentialOutcome, preview_bytes: usize) -> TestCase {
+        TestCase {
+            id: test.id.clone(),
+            name: test.name.clone(),
+            description: test.description.clone(),
+            full_code: test.full_code.clone(),
+            status: if outcome.diverges() { "failed" } else { "passed" }.to_string(),
+            execution_time: None,
+            legacy_output: Some(OutputCapture::preview(&outcome.legacy.display_output(), preview_bytes)),
+            new_output: Some(OutputCapture::preview(&outcome.new.display_output(), preview_bytes)),
+        }
+    }
+
+    /// Primeiro ID livre no formato `<base>-<sufixo>-<n>`
+    pub fn free_id(existing: &HashSet<String>, base: &str, suffix: &str) -> String {
+        (1..).map(|n| format!("{}-{}-{}", base, suffix, n))
+            .find(|id| !existing.contains(id))
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for DifferentialRun {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.outputs_dir);
+    }
+}
+
+/// Gerador pseudoaleatório xorshift: reprodutível pela semente, sem dependências
+struct Random(u64);
+
+impl Random {
+    fn new(seed: u64) -> Self {
+        // xorshift não sai do zero
+        Self(seed.max(1))
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n.max(1) as u64) as usize
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[self.below(items.len())]
+    }
+}
+
+/// Entrada de um teste: argumentos e stdin
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct FuzzInput {
+    args: Vec<String>,
+    stdin: Option<String>,
+}
+
+/// Fuzzing diferencial local: varia os argumentos e o stdin de um teste
+/// existente, roda legado e novo e guarda como teste cada entrada que diverge
+pub struct Fuzzer;
+
+impl Fuzzer {
+    pub async fn run(project_id: &str, test_id: &str, options: FuzzOptions, app: &tauri::AppHandle) -> Result<FuzzReport, String> {
+        let differential = DifferentialRun::prepare(project_id, test_id, options.timeout_ms, app).await?;
+        let seed_test = &differential.test;
+        let iterations = options.iterations.unwrap_or(DEFAULT_ITERATIONS);
+        let max_divergences = options.max_divergences.unwrap_or(DEFAULT_MAX_DIVERGENCES);
+        let seed = options.seed.unwrap_or_else(|| SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(1));
+        let preview_bytes = SettingsService::load(app)?.preview_bytes;
+
+        println!("🎲 Fuzzing de {} ({} entradas, semente {})", seed_test.name, iterations, seed);
+
+        let original = FuzzInput {
+            args: TestRunner::test_inputs(seed_test),
+            stdin: seed_test.stdin.clone(),
+        };
+
+        // Uma divergência que a entrada original já tem não é novidade
+        let mut signatures = HashSet::new();
+        let baseline = differential.run(&original.args, original.stdin.as_deref())?;
+        if baseline.diverges() {
+            println!("⚠️ A entrada original já diverge: {}", baseline.differences().join("; "));
+            signatures.insert(baseline.signature());
+        }
+
+        let mut random = Random::new(seed);
+        let mut tried = HashSet::from([original.clone()]);
+        let mut existing_ids: HashSet<String> = TestStore::list(project_id, app).await?
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+        let base_id = ParameterExpander::parent_id(&seed_test.id).unwrap_or(&seed_test.id).to_string();
+        let mut divergences = Vec::new();
+        let mut results = Vec::new();
+        let mut executed = 0;
+
+        while executed < iterations && divergences.len() < max_divergences {
+            // Entradas repetidas também contam, para não girar para sempre em testes com pouca variação
+            executed += 1;
+            let input = Self::mutate(&original, &mut random);
+            if !tried.insert(input.clone()) {
+                continue;
+            }
+
+            let outcome = differential.run(&input.args, input.stdin.as_deref())?;
+            if !outcome.diverges() || !signatures.insert(outcome.signature()) {
+                continue;
+            }
+
+            let differences = outcome.differences();
+            println!("💥 Divergência na entrada {}: {}", executed, differences.join("; "));
+
+            let id = DifferentialRun::free_id(&existing_ids, &base_id, "fuzz");
+            let test = FullTestFromAI {
+                id: id.clone(),
+                name: format!("{} (fuzz #{})", seed_test.name, divergences.len() + 1),
+                description: format!(
+                    "Entrada gerada por fuzzing a partir de '{}' (semente {}). Divergência: {}",
+                    seed_test.name,
+                    seed,
+                    differences.join("; ")
+                ),
+                args: Some(input.args.clone()),
+                stdin: input.stdin.clone(),
+                parameters: None,
+                ..seed_test.clone()
+            };
+
+            let saved = TestStore::create(project_id, test, app).await?;
+            existing_ids.insert(saved.id.clone());
+            results.push(DifferentialRun::test_case(&saved, &outcome, preview_bytes));
+
+            divergences.push(FuzzDivergence {
+                args: input.args,
+                stdin: input.stdin,
+                differences,
+                legacy_output: OutputCapture::preview(&outcome.legacy.display_output(), preview_bytes),
+                new_output: OutputCapture::preview(&outcome.new.display_output(), preview_bytes),
+                saved_as: Some(saved.id),
+            });
+        }
+
+        TestStore::record_results(project_id, &results, app).await?;
+
+        println!("✅ Fuzzing de {} concluído: {} entradas, {} divergências", seed_test.name, executed, divergences.len());
+        Ok(FuzzReport {
+            test_id: test_id.to_string(),
+            seed,
+            iterations: executed,
+            divergences,
+        })
+    }
+
+    /// Aplica de uma a três mutações à entrada original
+    fn mutate(original: &FuzzInput, random: &mut Random) -> FuzzInput {
+        let mut input = original.clone();
+
+        for _ in 0..=random.below(3) {
+            let stdin_mutation = input.stdin.is_some() && (input.args.is_empty() || random.below(2) == 0);
+            if stdin_mutation {
+                Self::mutate_stdin(&mut input, random);
+            } else {
+                Self::mutate_args(&mut input.args, random);
+            }
+        }
+
+        input
+    }
+
+    fn mutate_args(args: &mut Vec<String>, random: &mut Random) {
+        if args.is_empty() {
+            args.push(Self::random_arg(random));
+            return;
+        }
+
+        let i = random.below(args.len());
+        match random.below(6) {
+            0 | 1 => args[i] = Self::mutate_value(&args[i], random),
+            2 => args[i] = Self::huge_string(random),
+            3 => { args.remove(i); },
+            4 => { let copy = args[i].clone(); args.insert(i, copy); },
+            _ => args.insert(random.below(args.len() + 1), Self::random_arg(random)),
+        }
+    }
+
+    fn mutate_stdin(input: &mut FuzzInput, random: &mut Random) {
+        let stdin = input.stdin.get_or_insert_with(String::new);
+        let trailing_newline = stdin.ends_with('\n');
+        let mut lines: Vec<String> = stdin.lines().map(|l| l.to_string()).collect();
+
+        match random.below(7) {
+            0 => lines.clear(),
+            1 if !lines.is_empty() => { lines.remove(random.below(lines.len())); },
+            2 if !lines.is_empty() => {
+                let i = random.below(lines.len());
+                let copy = lines[i].clone();
+                lines.insert(i, copy);
+            },
+            3 => lines.insert(random.below(lines.len() + 1), Self::huge_string(random)),
+            4 => lines.insert(random.below(lines.len() + 1), random.pick(SPECIAL_STRINGS).to_string()),
+            _ if !lines.is_empty() => {
+                let i = random.below(lines.len());
+                lines[i] = Self::mutate_value(&lines[i], random);
+            },
+            _ => lines.push(random.pick(BOUNDARY_NUMBERS).to_string()),
+        }
+
+        let mut text = lines.join("\n");
+        if trailing_newline && !text.is_empty() {
+            text.push('\n');
+        }
+        *stdin = text;
+    }
+
+    /// Troca um dos números do valor por um número de limite; sem números, troca
+    /// o valor inteiro por uma string especial
+    fn mutate_value(value: &str, random: &mut Random) -> String {
+        let matches: Vec<_> = NUMBER.find_iter(value).collect();
+
+        if !matches.is_empty() && random.below(4) != 0 {
+            let found = matches[random.below(matches.len())];
+            return format!("{}{}{}", &value[..found.start()], random.pick(BOUNDARY_NUMBERS), &value[found.end()..]);
+        }
+
+        random.pick(SPECIAL_STRINGS).to_string()
+    }
+
+    fn random_arg(random: &mut Random) -> String {
+        if random.below(2) == 0 {
+            random.pick(BOUNDARY_NUMBERS).to_string()
+        } else {
+            random.pick(SPECIAL_STRINGS).to_string()
+        }
+    }
+
+    fn huge_string(random: &mut Random) -> String {
+        let unit = random.pick(&["A", "9", " ", "ç", "日", "🦀", "%s"]);
+        unit.repeat(HUGE_STRING_LEN / unit.len().max(1))
+    }
+}
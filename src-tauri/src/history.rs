@@ -0,0 +1,461 @@
+use crate::ai::{FullTestFromAI, TestCase};
+use crate::api::HttpCapture;
+use crate::compare::ComparisonResult;
+use crate::project::ProjectService;
+use crate::runner::SideOutput;
+use crate::settings::SettingsService;
+use crate::storage::{Storage, CURRENT_SCHEMA_VERSION};
+use crate::toolchain::ToolchainReport;
+use crate::web::PageCapture;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Resultado de um teste dentro de uma execução, com as saídas completas dos dois lados
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TestRunRecord {
+    #[serde(flatten)]
+    pub result: TestCase,
+    #[serde(rename = "legacyRun", default, skip_serializing_if = "Option::is_none")]
+    pub legacy_run: Option<SideOutput>,
+    #[serde(rename = "newRun", default, skip_serializing_if = "Option::is_none")]
+    pub new_run: Option<SideOutput>,
+    /// Respostas HTTP dos dois lados (projetos do tipo "API")
+    #[serde(rename = "legacyHttp", default, skip_serializing_if = "Option::is_none")]
+    pub legacy_http: Option<HttpCapture>,
+    #[serde(rename = "newHttp", default, skip_serializing_if = "Option::is_none")]
+    pub new_http: Option<HttpCapture>,
+    /// Páginas visitadas nos dois lados (projetos do tipo "Web")
+    #[serde(rename = "legacyPages", default, skip_serializing_if = "Vec::is_empty")]
+    pub legacy_pages: Vec<PageCapture>,
+    #[serde(rename = "newPages", default, skip_serializing_if = "Vec::is_empty")]
+    pub new_pages: Vec<PageCapture>,
+    /// Diferenças encontradas na comparação, quando ela vai além da saída textual
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub differences: Vec<String>,
+    /// Comparação das saídas com o comparador do teste, diferença por caminho
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comparison: Option<ComparisonResult>,
+}
+
+impl TestRunRecord {
+    /// Resultado de um teste que não chegou a rodar (ex.: erro de build, snapshot
+    /// ausente): status "not_run", fora da contagem de aprovados e reprovados
+    pub fn not_run(test: &FullTestFromAI, message: &str) -> Self {
+        println!("⚠️ Teste {} não executado: {}", test.name, message);
+
+        Self {
+            result: TestCase {
+                id: test.id.clone(),
+                name: test.name.clone(),
+                description: test.description.clone(),
+                full_code: test.full_code.clone(),
+                status: "not_run".to_string(),
+                execution_time: Some(0),
+                legacy_output: Some(message.to_string()),
+                new_output: Some(message.to_string()),
+            },
+            ..Default::default()
+        }
+    }
+}
+
+/// Resumo de uma execução (compatível com `Execution` do frontend)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExecutionSummary {
+    pub id: String,
+    #[serde(rename = "projectId")]
+    pub project_id: String,
+    #[serde(rename = "projectName")]
+    pub project_name: String,
+    #[serde(rename = "executedAt")]
+    pub executed_at: String,
+    #[serde(rename = "finishedAt")]
+    pub finished_at: String,
+    /// "live" (legado e novo executados) ou "snapshot" (novo contra snapshots do legado)
+    pub mode: String,
+    #[serde(rename = "totalTests")]
+    pub total_tests: usize,
+    #[serde(rename = "passedTests")]
+    pub passed_tests: usize,
+    #[serde(rename = "failedTests")]
+    pub failed_tests: usize,
+    /// Testes que não chegaram a rodar (status "not_run")
+    #[serde(rename = "notRunTests", default)]
+    pub not_run_tests: usize,
+    #[serde(rename = "totalExecutionTime")]
+    pub total_execution_time: u64,
+    pub status: String,
+}
+
+/// Execução completa persistida em `projects/<id>/executions/<execution_id>.json`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExecutionRecord {
+    #[serde(rename = "schemaVersion", default)]
+    pub schema_version: u32,
+    #[serde(flatten)]
+    pub summary: ExecutionSummary,
+    #[serde(rename = "toolVersions")]
+    pub tool_versions: BTreeMap<String, String>,
+    /// Ferramentas exigidas pelos testes que não estavam instaladas
+    #[serde(rename = "missingTools", default, skip_serializing_if = "Vec::is_empty")]
+    pub missing_tools: Vec<String>,
+    #[serde(rename = "testResults")]
+    pub test_results: Vec<TestRunRecord>,
+}
+
+/// Resposta de `execute_tests` (compatível com `TestExecutionResponse` do frontend)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TestExecutionResponse {
+    #[serde(rename = "projectId")]
+    pub project_id: String,
+    #[serde(rename = "executionId")]
+    pub execution_id: String,
+    #[serde(rename = "testResults")]
+    pub test_results: Vec<TestCase>,
+    #[serde(rename = "executedAt")]
+    pub executed_at: String,
+    #[serde(rename = "totalExecutionTime")]
+    pub total_execution_time: u64,
+    #[serde(rename = "missingTools", default, skip_serializing_if = "Vec::is_empty")]
+    pub missing_tools: Vec<String>,
+}
+
+impl ExecutionRecord {
+    /// Monta o registro de uma execução a partir dos resultados dos testes
+    pub fn new(
+        execution_id: &str,
+        project_id: &str,
+        project_name: &str,
+        mode: &str,
+        started_at: String,
+        toolchain: &ToolchainReport,
+        test_results: Vec<TestRunRecord>
+    ) -> Self {
+        let passed_tests = test_results.iter().filter(|t| t.result.status == "passed").count();
+        let failed_tests = test_results.iter().filter(|t| t.result.status == "failed").count();
+        let not_run_tests = test_results.iter().filter(|t| t.result.status == "not_run").count();
+
+        Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            summary: ExecutionSummary {
+                id: execution_id.to_string(),
+                project_id: project_id.to_string(),
+                project_name: project_name.to_string(),
+                executed_at: started_at,
+                finished_at: chrono::Utc::now().to_rfc3339(),
+                mode: mode.to_string(),
+                total_tests: test_results.len(),
+                passed_tests,
+                failed_tests,
+                not_run_tests,
+                total_execution_time: test_results.iter().filter_map(|t| t.result.execution_time).sum(),
+                status: if failed_tests == 0 && not_run_tests == 0 { "passed" } else { "failed" }.to_string(),
+            },
+            tool_versions: toolchain.versions(),
+            missing_tools: toolchain.missing(),
+            test_results,
+        }
+    }
+
+    /// Resposta enviada à UI ao final da execução
+    pub fn to_response(&self) -> TestExecutionResponse {
+        TestExecutionResponse {
+            project_id: self.summary.project_id.clone(),
+            execution_id: self.summary.id.clone(),
+            test_results: self.test_results.iter().map(|t| t.result.clone()).collect(),
+            executed_at: self.summary.executed_at.clone(),
+            total_execution_time: self.summary.total_execution_time,
+            missing_tools: self.missing_tools.clone(),
+        }
+    }
+}
+
+/// Teste citado na comparação entre duas execuções
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TestChange {
+    #[serde(rename = "testId")]
+    pub test_id: String,
+    pub name: String,
+    #[serde(rename = "baseStatus")]
+    pub base_status: Option<String>,
+    #[serde(rename = "targetStatus")]
+    pub target_status: Option<String>,
+}
+
+/// Variação de tempo de um teste presente nas duas execuções
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TimingChange {
+    #[serde(rename = "testId")]
+    pub test_id: String,
+    pub name: String,
+    #[serde(rename = "baseTime")]
+    pub base_time: u64,
+    #[serde(rename = "targetTime")]
+    pub target_time: u64,
+    #[serde(rename = "deltaMs")]
+    pub delta_ms: i64,
+    #[serde(rename = "deltaPercent")]
+    pub delta_percent: Option<f64>,
+}
+
+/// Diferença entre duas execuções do mesmo projeto
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExecutionComparison {
+    #[serde(rename = "projectId")]
+    pub project_id: String,
+    #[serde(rename = "baseExecutionId")]
+    pub base_execution_id: String,
+    #[serde(rename = "targetExecutionId")]
+    pub target_execution_id: String,
+    #[serde(rename = "newlyFailing")]
+    pub newly_failing: Vec<TestChange>,
+    #[serde(rename = "newlyPassing")]
+    pub newly_passing: Vec<TestChange>,
+    #[serde(rename = "stillFailing")]
+    pub still_failing: Vec<TestChange>,
+    /// Testes que não rodaram na execução alvo; ficam fora das transições
+    #[serde(rename = "notRun")]
+    pub not_run: Vec<TestChange>,
+    pub added: Vec<TestChange>,
+    pub removed: Vec<TestChange>,
+    /// Ordenado pela maior variação absoluta
+    #[serde(rename = "timingChanges")]
+    pub timing_changes: Vec<TimingChange>,
+    #[serde(rename = "totalTimeDeltaMs")]
+    pub total_time_delta_ms: i64,
+}
+
+/// Histórico de execuções de testes, mantido pelo backend
+pub struct HistoryService;
+
+impl HistoryService {
+    /// Persiste uma execução e aplica a política de retenção
+    pub fn save(record: &ExecutionRecord, app: &tauri::AppHandle) -> Result<(), String> {
+        let project_id = &record.summary.project_id;
+        let path = Self::executions_dir(project_id, app)?.join(format!("{}.json", record.summary.id));
+
+        Storage::write_json_atomic(&path, record)?;
+        println!("🗂️ Execução {} salva no histórico", record.summary.id);
+
+        let retention = SettingsService::load(app)?.execution_retention;
+        Self::prune(project_id, retention, app)?;
+
+        Ok(())
+    }
+
+    /// Lista as execuções do projeto, da mais recente para a mais antiga
+    pub fn list(project_id: &str, app: &tauri::AppHandle) -> Result<Vec<ExecutionSummary>, String> {
+        let mut summaries: Vec<ExecutionSummary> = Self::load_all(project_id, app)?
+            .into_iter()
+            .map(|record| record.summary)
+            .collect();
+
+        summaries.sort_by(|a, b| b.executed_at.cmp(&a.executed_at));
+        Ok(summaries)
+    }
+
+    /// Carrega uma execução com os resultados completos
+    pub fn get(project_id: &str, execution_id: &str, app: &tauri::AppHandle) -> Result<ExecutionRecord, String> {
+        let path = Self::executions_dir(project_id, app)?.join(format!("{}.json", execution_id));
+
+        Storage::read_json(&path)?
+            .ok_or(format!("Execução com ID '{}' não encontrada", execution_id))
+    }
+
+    /// Diretório com as saídas grandes gravadas em arquivo durante uma execução
+    pub fn outputs_dir(project_id: &str, execution_id: &str, app: &tauri::AppHandle) -> Result<PathBuf, String> {
+        Ok(ProjectService::project_data_dir(project_id, app)?.join("outputs").join(execution_id))
+    }
+
+    /// Remove uma execução (e as saídas gravadas em arquivo)
+    pub fn delete(project_id: &str, execution_id: &str, app: &tauri::AppHandle) -> Result<(), String> {
+        let path = Self::executions_dir(project_id, app)?.join(format!("{}.json", execution_id));
+
+        if !path.exists() {
+            return Err(format!("Execução com ID '{}' não encontrada", execution_id));
+        }
+
+        fs::remove_file(&path)
+            .map_err(|e| format!("Failed to remove execution file: {}", e))?;
+
+        let outputs_dir = Self::outputs_dir(project_id, execution_id, app)?;
+        if outputs_dir.exists() {
+            fs::remove_dir_all(&outputs_dir)
+                .map_err(|e| format!("Failed to remove execution outputs: {}", e))?;
+        }
+
+        println!("🗑️ Execução removida: {}", execution_id);
+        Ok(())
+    }
+
+    /// Remove todas as execuções do projeto
+    pub fn delete_all(project_id: &str, app: &tauri::AppHandle) -> Result<(), String> {
+        let _lock = Storage::lock_project(project_id, app)?;
+        let executions_dir = Self::executions_dir(project_id, app)?;
+
+        if executions_dir.exists() {
+            fs::remove_dir_all(&executions_dir)
+                .map_err(|e| format!("Failed to remove executions directory: {}", e))?;
+        }
+
+        let outputs_dir = ProjectService::project_data_dir(project_id, app)?.join("outputs");
+        if outputs_dir.exists() {
+            fs::remove_dir_all(&outputs_dir)
+                .map_err(|e| format!("Failed to remove outputs directory: {}", e))?;
+        }
+
+        Ok(())
+    }
+
+    /// Mantém apenas as `keep` execuções mais recentes; retorna quantas foram removidas
+    pub fn prune(project_id: &str, keep: usize, app: &tauri::AppHandle) -> Result<usize, String> {
+        let _lock = Storage::lock_project(project_id, app)?;
+        let summaries = Self::list(project_id, app)?;
+
+        let mut removed = 0;
+        for summary in summaries.iter().skip(keep) {
+            Self::delete(project_id, &summary.id, app)?;
+            removed += 1;
+        }
+
+        if removed > 0 {
+            println!("🧹 {} execuções antigas removidas (retenção: {})", removed, keep);
+        }
+
+        Ok(removed)
+    }
+
+    /// Compara duas execuções do projeto. Sem `base_id`, usa a execução imediatamente
+    /// anterior à `target_id`.
+    pub fn compare(
+        project_id: &str,
+        base_id: Option<&str>,
+        target_id: &str,
+        app: &tauri::AppHandle
+    ) -> Result<ExecutionComparison, String> {
+        let target = Self::get(project_id, target_id, app)?;
+
+        let base_id = match base_id {
+            Some(id) => id.to_string(),
+            None => Self::list(project_id, app)?
+                .into_iter()
+                .find(|s| s.executed_at < target.summary.executed_at)
+                .map(|s| s.id)
+                .ok_or("Não há execução anterior para comparar".to_string())?,
+        };
+        let base = Self::get(project_id, &base_id, app)?;
+
+        if base.summary.project_id != target.summary.project_id {
+            return Err("As execuções pertencem a projetos diferentes".to_string());
+        }
+
+        println!("🔀 Comparando execuções {} -> {}", base.summary.id, target.summary.id);
+
+        let base_results: BTreeMap<&str, &TestCase> = base.test_results.iter()
+            .map(|t| (t.result.id.as_str(), &t.result))
+            .collect();
+        let target_results: BTreeMap<&str, &TestCase> = target.test_results.iter()
+            .map(|t| (t.result.id.as_str(), &t.result))
+            .collect();
+
+        let change = |id: &str, base: Option<&TestCase>, target: Option<&TestCase>| TestChange {
+            test_id: id.to_string(),
+            name: target.or(base).map(|t| t.name.clone()).unwrap_or_default(),
+            base_status: base.map(|t| t.status.clone()),
+            target_status: target.map(|t| t.status.clone()),
+        };
+
+        let mut comparison = ExecutionComparison {
+            project_id: project_id.to_string(),
+            base_execution_id: base.summary.id.clone(),
+            target_execution_id: target.summary.id.clone(),
+            newly_failing: Vec::new(),
+            newly_passing: Vec::new(),
+            still_failing: Vec::new(),
+            not_run: Vec::new(),
+            added: Vec::new(),
+            removed: Vec::new(),
+            timing_changes: Vec::new(),
+            total_time_delta_ms: target.summary.total_execution_time as i64 - base.summary.total_execution_time as i64,
+        };
+
+        for (id, target_test) in &target_results {
+            let base_test = match base_results.get(id) {
+                Some(base_test) => *base_test,
+                None => {
+                    comparison.added.push(change(id, None, Some(target_test)));
+                    continue;
+                }
+            };
+
+            // Só há transição entre resultados de fato: um teste que não rodou
+            // (erro de build, snapshot ausente) não passou nem falhou
+            match (base_test.status.as_str(), target_test.status.as_str()) {
+                (_, "not_run") => comparison.not_run.push(change(id, Some(base_test), Some(target_test))),
+                ("passed", "failed") => comparison.newly_failing.push(change(id, Some(base_test), Some(target_test))),
+                ("failed", "passed") => comparison.newly_passing.push(change(id, Some(base_test), Some(target_test))),
+                ("failed", "failed") => comparison.still_failing.push(change(id, Some(base_test), Some(target_test))),
+                _ => {},
+            }
+
+            if let (Some(base_time), Some(target_time)) = (base_test.execution_time, target_test.execution_time) {
+                let delta_ms = target_time as i64 - base_time as i64;
+                comparison.timing_changes.push(TimingChange {
+                    test_id: id.to_string(),
+                    name: target_test.name.clone(),
+                    base_time,
+                    target_time,
+                    delta_ms,
+                    delta_percent: (base_time > 0).then(|| delta_ms as f64 * 100.0 / base_time as f64),
+                });
+            }
+        }
+
+        for (id, base_test) in &base_results {
+            if !target_results.contains_key(id) {
+                comparison.removed.push(change(id, Some(base_test), None));
+            }
+        }
+
+        comparison.timing_changes.sort_by_key(|t| std::cmp::Reverse(t.delta_ms.abs()));
+
+        println!("   ❌ {} novas falhas, ✅ {} corrigidos, ⚠️ {} ainda falhando, ⏭️ {} não executados",
+                 comparison.newly_failing.len(),
+                 comparison.newly_passing.len(),
+                 comparison.still_failing.len(),
+                 comparison.not_run.len());
+
+        Ok(comparison)
+    }
+
+    fn load_all(project_id: &str, app: &tauri::AppHandle) -> Result<Vec<ExecutionRecord>, String> {
+        let executions_dir = Self::executions_dir(project_id, app)?;
+
+        if !executions_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let entries = fs::read_dir(&executions_dir)
+            .map_err(|e| format!("Failed to read executions directory: {}", e))?;
+
+        let mut records = Vec::new();
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                match Storage::read_json::<ExecutionRecord>(&path) {
+                    Ok(Some(record)) => records.push(record),
+                    Ok(None) => {},
+                    Err(e) => println!("⚠️ Ignorando execução inválida: {}", e),
+                }
+            }
+        }
+
+        Ok(records)
+    }
+
+    fn executions_dir(project_id: &str, app: &tauri::AppHandle) -> Result<PathBuf, String> {
+        Ok(ProjectService::project_data_dir(project_id, app)?.join("executions"))
+    }
+}
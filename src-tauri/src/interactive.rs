@@ -0,0 +1,281 @@
+use crate::ai::ExecutionInfo;
+use crate::build_cache::BuildCache;
+use crate::encoding::StreamDecoder;
+use crate::runner::{SideOutput, TestRunner};
+use portable_pty::{native_pty_system, CommandBuilder, PtySize};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::LazyLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Tempo padrão de espera de um `expect`
+const DEFAULT_EXPECT_TIMEOUT_MS: u64 = 10000;
+
+/// Maior sequência ANSI incompleta guardada à espera do resto
+const MAX_PENDING_ESCAPE: usize = 256;
+
+/// Tamanho fixo do terminal, para que as saídas dos dois lados sejam comparáveis
+const PTY_SIZE: PtySize = PtySize { rows: 24, cols: 80, pixel_width: 0, pixel_height: 0 };
+
+/// Sequências ANSI (cores, cursor, título da janela) removidas da transcrição
+static ANSI: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\x1b\[[0-9;?]*[A-Za-z]|\x1b\][^\x07]*\x07|\x1b[()][A-Za-z0-9]|\x1b[=>]").unwrap()
+});
+
+/// Início de uma sequência ANSI que ainda pode ser completada
+static ANSI_PREFIX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\x1b(\[[0-9;?]*|\][^\x07]*|[()])?$").unwrap());
+
+/// Passo do roteiro de um teste interativo (`interactive` no teste)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum ExpectStep {
+    /// Espera a saída conter o padrão (regex; texto literal se a regex for inválida)
+    Expect {
+        pattern: String,
+        #[serde(rename = "timeoutMs", default, skip_serializing_if = "Option::is_none")]
+        timeout_ms: Option<u64>,
+    },
+    /// Envia o texto como foi escrito
+    Send { text: String },
+    /// Envia o texto seguido de Enter
+    SendLine { text: String },
+    /// Envia uma tecla de controle: "c" (Ctrl+C), "d", "enter", "tab", "escape", "up"...
+    SendControl { key: String },
+}
+
+/// Saída do programa rodando no pseudo-terminal, lida por uma thread
+struct Session {
+    output: Receiver<Vec<u8>>,
+    /// Codificação configurada do lado; a da sessão é fixada no primeiro pedaço
+    setting: Option<String>,
+    decoder: Option<StreamDecoder>,
+    /// Transcrição limpa (sem ANSI) do que já chegou
+    text: String,
+    /// Texto decodificado que termina no meio de uma sequência ANSI
+    pending: String,
+    /// Posição (em `text`) a partir da qual o próximo `expect` procura
+    searched_from: usize,
+    closed: bool,
+}
+
+impl Session {
+    fn new(output: Receiver<Vec<u8>>, setting: Option<&str>) -> Self {
+        Self {
+            output,
+            setting: setting.map(|s| s.to_string()),
+            decoder: None,
+            text: String::new(),
+            pending: String::new(),
+            searched_from: 0,
+            closed: false,
+        }
+    }
+
+    fn expect(&mut self, pattern: &str, deadline: Instant) -> Result<(), String> {
+        let regex = Regex::new(pattern).unwrap_or_else(|_| Regex::new(&regex::escape(pattern)).unwrap());
+
+        loop {
+            if let Some(found) = self.text.get(self.searched_from..).and_then(|rest| regex.find(rest)) {
+                self.searched_from += found.end();
+                return Ok(());
+            }
+
+            if self.closed {
+                return Err(format!("programa terminou sem exibir /{}/", pattern));
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self.output.recv_timeout(remaining) {
+                Ok(chunk) => self.push(&chunk),
+                Err(RecvTimeoutError::Timeout) => return Err(format!("tempo esgotado esperando /{}/", pattern)),
+                Err(RecvTimeoutError::Disconnected) => self.closed = true,
+            }
+        }
+    }
+
+    /// Lê o que já chegou (ou o que chegar até `wait`)
+    fn drain(&mut self, wait: Duration) {
+        let deadline = Instant::now() + wait;
+
+        while !self.closed {
+            match self.output.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(chunk) => self.push(&chunk),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => self.closed = true,
+            }
+        }
+    }
+
+    /// Decodifica e limpa só o pedaço novo; o que já está em `text` não muda,
+    /// e uma sequência ANSI cortada espera o resto no próximo pedaço
+    fn push(&mut self, chunk: &[u8]) {
+        let setting = self.setting.as_deref();
+        let decoder = self.decoder.get_or_insert_with(|| StreamDecoder::new(chunk, setting));
+        self.pending.push_str(&decoder.decode(chunk, false));
+
+        let cut = match self.pending.rfind('\x1b') {
+            Some(i) if Self::unfinished_escape(&self.pending[i..]) => i,
+            _ => self.pending.len(),
+        };
+        self.text.push_str(&InteractiveRunner::clean(&self.pending[..cut]));
+        self.pending.drain(..cut);
+    }
+
+    fn unfinished_escape(tail: &str) -> bool {
+tail.len() <= MAX_PENDING_ESCAPE && ANSI_PREFIX.is_match(tail)
+    }
+
+    /// Transcrição completa e a codificação usada
+    fn finish(mut self) -> (String, &'static str) {
+        let setting = self.setting.as_deref();
+        let decoder = self.decoder.get_or_insert_with(|| StreamDecoder::new(&[], setting));
+        self.pending.push_str(&decoder.decode(&[], true));
+        self.text.push_str(&InteractiveRunner::clean(&self.pending));
+
+        (self.text, decoder.name())
+    }
+}
+
+/// Executa programas interativos (menus, prompts) em um pseudo-terminal seguindo
+/// um roteiro no estilo `expect`
+pub struct InteractiveRunner;
+
+impl InteractiveRunner {
+    /// Roda um dos lados com o roteiro. A transcrição completa vai em `stdout` e a
+    /// falha do roteiro (se houver) em `scriptFailure`.
+    pub fn run(
+        exec: &ExecutionInfo,
+        inputs: &[String],
+        script: &[ExpectStep],
+        timeout_ms: u64,
+        cache: &BuildCache,
+        label: &str
+    ) -> Result<SideOutput, String> {
+        let command = TestRunner::build_command(exec, inputs);
+        println!("🖥️ Executando {} em pseudo-terminal: {}", label, command);
+
+        let pty = native_pty_system().openpty(PTY_SIZE)
+            .map_err(|e| format!("Erro ao abrir pseudo-terminal: {}", e))?;
+
+        let mut builder = CommandBuilder::new("sh");
+        builder.args(["-c", &command]);
+        builder.cwd(&exec.working_directory);
+        for (key, value) in cache.env() {
+            builder.env(key, value);
+        }
+        builder.env("TERM", "dumb");
+
+        let mut child = pty.slave.spawn_command(builder)
+            .map_err(|e| format!("Erro ao executar {}: {}", label, e))?;
+        // Sem a ponta escrava aberta aqui, a leitura termina quando o programa sai
+        drop(pty.slave);
+
+        let mut reader = pty.master.try_clone_reader()
+            .map_err(|e| format!("Erro ao ler pseudo-terminal: {}", e))?;
+        let mut writer = pty.master.take_writer()
+            .map_err(|e| format!("Erro ao escrever no pseudo-terminal: {}", e))?;
+
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let mut buffer = [0u8; 4096];
+            // No Linux a leitura falha com EIO quando o programa termina
+            while let Ok(n) = reader.read(&mut buffer) {
+                if n == 0 || sender.send(buffer[..n].to_vec()).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut session = Session::new(receiver, cache.encoding());
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+        let mut failure = None;
+
+        for (i, step) in script.iter().enumerate() {
+            let result = match step {
+                ExpectStep::Expect { pattern, timeout_ms } => {
+                    let step_deadline = Instant::now() + Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_EXPECT_TIMEOUT_MS));
+                    session.expect(pattern, step_deadline.min(deadline))
+                },
+                ExpectStep::Send { text } => Self::write(&mut writer, text.as_bytes()),
+                ExpectStep::SendLine { text } => Self::write(&mut writer, format!("{}\r", text).as_bytes()),
+                ExpectStep::SendControl { key } => Self::control_sequence(key)
+                    .and_then(|sequence| Self::write(&mut writer, sequence.as_bytes())),
+            };
+
+            if let Err(e) = result {
+                println!("❌ Roteiro {} parou no passo {}: {}", label, i + 1, e);
+                failure = Some(format!("Passo {}: {}", i + 1, e));
+                break;
+            }
+        }
+
+        // Depois do roteiro, o programa tem até o timeout do teste para terminar
+        let exit_code = loop {
+            session.drain(Duration::from_millis(20));
+
+            match child.try_wait() {
+                Ok(Some(status)) => break Some(status.exit_code() as i32),
+                Ok(None) if failure.is_none() && Instant::now() < deadline => continue,
+                _ => {
+                    // O pseudo-terminal abre uma sessão própria: o grupo leva os filhos de `sh -c`
+                    if let Some(pid) = child.process_id() {
+                        TestRunner::kill_group(pid);
+                    }
+                    let _ = child.kill();
+                    failure.get_or_insert_with(|| "Tempo esgotado esperando o programa terminar".to_string());
+                    break None;
+                }
+            }
+        };
+        session.drain(Duration::from_millis(200));
+
+        let (stdout, encoding) = session.finish();
+        Ok(SideOutput {
+            stdout,
+            exit_code,
+            script_failure: failure,
+            encoding: Some(encoding.to_string()),
+            ..Default::default()
+        })
+    }
+
+    fn write(writer: &mut Box<dyn Write + Send>, data: &[u8]) -> Result<(), String> {
+        writer.write_all(data)
+            .and_then(|_| writer.flush())
+            .map_err(|e| format!("erro ao enviar entrada: {}", e))
+    }
+
+    /// Bytes enviados para uma tecla de controle ("c", "ctrl-c", "^C", "enter"...)
+    fn control_sequence(key: &str) -> Result<String, String> {
+        let lower = key.to_lowercase();
+        let name = lower.trim_start_matches("ctrl-").trim_start_matches("ctrl+").trim_start_matches('^');
+
+        let sequence = match name {
+            "enter" | "return" => "\r",
+            "tab" => "\t",
+            "escape" | "esc" => "\x1b",
+            "backspace" => "\x7f",
+            "up" => "\x1b[A",
+            "down" => "\x1b[B",
+            "right" => "\x1b[C",
+            "left" => "\x1b[D",
+            "eof" => "\x04",
+            letter if letter.len() == 1 && letter.as_bytes()[0].is_ascii_lowercase() => {
+                return Ok(((letter.as_bytes()[0] & 0x1f) as char).to_string());
+            },
+            _ => return Err(format!("tecla de controle desconhecida: {}", key)),
+        };
+
+        Ok(sequence.to_string())
+    }
+
+    /// Transcrição sem sequências ANSI e com quebras de linha `\n`
+    fn clean(text: &str) -> String {
+        ANSI.replace_all(text, "")
+            .replace("\r\n", "\n")
+            .replace('\r', "")
+    }
+}
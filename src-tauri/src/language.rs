@@ -0,0 +1,206 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+/// Diretórios ignorados ao percorrer os projetos (dependências, saídas de build e controle de versão)
+const SKIPPED_DIRS: [&str; 5] = ["node_modules", "target", "venv", "__pycache__", "vendor"];
+
+/// Extensões de arquivos incluídos por outros (cabeçalhos, copybooks), que não são programas
+const HEADER_EXTENSIONS: [&str; 6] = ["h", "hh", "hpp", "hxx", "cpy", "inc"];
+
+/// Quanto do arquivo é lido para as heurísticas de conteúdo
+const SNIFF_LIMIT: u64 = 8 * 1024;
+
+/// Linguagem reconhecida pelo detector
+pub struct Language {
+    pub name: &'static str,
+    /// Extensões em minúsculas, sem o ponto
+    pub extensions: &'static [&'static str],
+    /// Interpretadores que identificam a linguagem no shebang
+    pub interpreters: &'static [&'static str],
+    /// Se arquivos da linguagem podem ser executados como programa
+    pub runnable: bool,
+}
+
+static LANGUAGES: [Language; 26] = [
+    Language { name: "C", extensions: &["c", "h"], interpreters: &[], runnable: true },
+    Language { name: "C++", extensions: &["cpp", "cc", "cxx", "c++", "hpp", "hh", "hxx"], interpreters: &[], runnable: true },
+    Language { name: "C#", extensions: &["cs"], interpreters: &[], runnable: true },
+    Language { name: "COBOL", extensions: &["cbl", "cob", "cobol", "cpy"], interpreters: &[], runnable: true },
+    Language { name: "Fortran", extensions: &["f", "for", "f77", "f90", "f95", "f03", "ftn"], interpreters: &[], runnable: true },
+    Language { name: "Pascal", extensions: &["pas", "pp", "dpr", "lpr"], interpreters: &[], runnable: true },
+    Language { name: "Ada", extensions: &["adb", "ads"], interpreters: &[], runnable: true },
+    Language { name: "Visual Basic", extensions: &["vb", "bas", "frm", "vbs"], interpreters: &[], runnable: true },
+    Language { name: "Perl", extensions: &["pl", "pm", "perl"], interpreters: &["perl"], runnable: true },
+    Language { name: "Python", extensions: &["py", "pyw"], interpreters: &["python", "python2", "python3", "pypy", "pypy3"], runnable: true },
+    Language { name: "Ruby", extensions: &["rb"], interpreters: &["ruby"], runnable: true },
+    Language { name: "PHP", extensions: &["php"], interpreters: &["php"], runnable: true },
+    Language { name: "JavaScript", extensions: &["js", "mjs", "cjs"], interpreters: &["node", "nodejs"], runnable: true },
+    Language { name: "TypeScript", extensions: &["ts"], interpreters: &["ts-node", "deno", "bun"], runnable: true },
+    Language { name: "Shell", extensions: &["sh", "bash", "ksh", "zsh", "csh"], interpreters: &["sh", "bash", "ksh", "zsh", "csh", "tcsh", "dash"], runnable: true },
+    Language { name: "Tcl", extensions: &["tcl"], interpreters: &["tclsh", "wish"], runnable: true },
+    Language { name: "Lua", extensions: &["lua"], interpreters: &["lua"], runnable: true },
+    Language { name: "AWK", extensions: &["awk"], interpreters: &["awk", "gawk", "mawk"], runnable: true },
+    Language { name: "Java", extensions: &["java"], interpreters: &[], runnable: true },
+    Language { name: "Kotlin", extensions: &["kt", "kts"], interpreters: &[], runnable: true },
+    Language { name: "Scala", extensions: &["scala"], interpreters: &[], runnable: true },
+    Language { name: "Go", extensions: &["go"], interpreters: &[], runnable: true },
+    Language { name: "Rust", extensions: &["rs"], interpreters: &[], runnable: true },
+    Language { name: "PL/I", extensions: &["pli", "pl1"], interpreters: &[], runnable: true },
+    Language { name: "RPG", extensions: &["rpg", "rpgle", "sqlrpgle"], interpreters: &[], runnable: true },
+    Language { name: "SQL", extensions: &["sql"], interpreters: &[], runnable: false },
+];
+
+/// Arquivos e linhas de uma linguagem em um dos lados
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LanguageStats {
+    pub language: String,
+    pub files: usize,
+    pub lines: usize,
+    /// Linhas não vazias
+    #[serde(rename = "codeLines")]
+    pub code_lines: usize,
+}
+
+/// Detecção de linguagem por extensão, shebang e conteúdo
+pub struct LanguageDetector;
+
+impl LanguageDetector {
+    /// Linguagem de um arquivo, ou `None` se não for código reconhecido
+    pub fn detect(path: &Path) -> Option<&'static Language> {
+        let extension = path.extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .unwrap_or_default();
+
+        if extension.is_empty() {
+            return Self::detect_by_content(path);
+        }
+
+        match extension.as_str() {
+            // `.h` é compartilhado por C e C++
+            "h" if Self::sniff(path).is_some_and(|c| Self::looks_like_cpp(&c)) => Self::by_name("C++"),
+            // `.cls` de VB6 vs. classes LaTeX/Apex
+            "cls" if Self::sniff(path).is_some_and(|c| c.contains("VB_Name") || c.starts_with("VERSION 1.0 CLASS")) => {
+                Self::by_name("Visual Basic")
+            },
+            // `.pl` também é Prolog
+            "pl" if Self::sniff(path).is_some_and(|c| c.contains(":-") && !c.contains("use ") && !c.contains("my ")) => None,
+            ext => LANGUAGES.iter().find(|l| l.extensions.contains(&ext)),
+        }
+    }
+
+    /// Se o arquivo pode ser ponto de entrada (linguagem executável e não é cabeçalho)
+    pub fn is_program(path: &Path) -> bool {
+        let extension = path.extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .unwrap_or_default();
+
+        !HEADER_EXTENSIONS.contains(&extension.as_str())
+            && Self::detect(path).is_some_and(|language| language.runnable)
+    }
+
+    /// Diretórios que não valem a pena percorrer (ocultos, dependências, builds)
+    pub fn is_skipped_dir(entry: &walkdir::DirEntry) -> bool {
+        let name = entry.file_name().to_string_lossy();
+        entry.depth() > 0
+            && entry.file_type().is_dir()
+            && (name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_ref()))
+    }
+
+    /// Arquivos de código do diretório, com a linguagem de cada um
+    pub fn source_files(root: &Path) -> Vec<(std::path::PathBuf, &'static Language)> {
+        walkdir::WalkDir::new(root)
+            .into_iter()
+            .filter_entry(|e| !Self::is_skipped_dir(e))
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .filter_map(|e| Self::detect(e.path()).map(|language| (e.into_path(), language)))
+            .collect()
+    }
+
+    /// Arquivos e linhas por linguagem, da linguagem com mais linhas para a com menos
+    pub fn summarize(root: &Path) -> Result<Vec<LanguageStats>, String> {
+        if !root.is_dir() {
+            return Err(format!("Diretório não encontrado: {}", root.display()));
+        }
+
+        let mut stats: BTreeMap<&str, LanguageStats> = BTreeMap::new();
+
+        for (path, language) in Self::source_files(root) {
+            let content = match fs::read(&path) {
+                Ok(data) => String::from_utf8_lossy(&data).to_string(),
+                Err(e) => {
+                    println!("⚠️ Erro lendo arquivo {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+
+            let entry = stats.entry(language.name).or_insert_with(|| LanguageStats {
+                language: language.name.to_string(),
+                files: 0,
+                lines: 0,
+                code_lines: 0,
+            });
+            entry.files += 1;
+            entry.lines += content.lines().count();
+            entry.code_lines += content.lines().filter(|l| !l.trim().is_empty()).count();
+        }
+
+        let mut stats: Vec<LanguageStats> = stats.into_values().collect();
+        stats.sort_by(|a, b| b.lines.cmp(&a.lines).then_with(|| a.language.cmp(&b.language)));
+        Ok(stats)
+    }
+
+    /// Arquivos sem extensão: shebang ou marcas típicas da linguagem
+    fn detect_by_content(path: &Path) -> Option<&'static Language> {
+        let content = Self::sniff(path)?;
+        let first_line = content.lines().next().unwrap_or("");
+
+        if let Some(shebang) = first_line.strip_prefix("#!") {
+            let mut parts = shebang.split_whitespace();
+            let mut program = parts.next().unwrap_or("").rsplit('/').next().unwrap_or("");
+            if program == "env" {
+                program = parts.find(|p| !p.starts_with('-')).unwrap_or("");
+            }
+
+            return LANGUAGES.iter().find(|l| l.interpreters.contains(&program)).or_else(|| {
+                // python3.11, perl5.30...
+                let base = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+                LANGUAGES.iter().find(|l| l.interpreters.contains(&base))
+            });
+        }
+
+        let upper = content.to_uppercase();
+        if upper.contains("IDENTIFICATION DIVISION") || upper.contains("PROCEDURE DIVISION") {
+            return Self::by_name("COBOL");
+        }
+
+        None
+    }
+
+    fn looks_like_cpp(content: &str) -> bool {
+        ["class ", "namespace ", "template<", "template <", "std::", "public:", "private:"]
+            .iter()
+            .any(|marker| content.contains(marker))
+    }
+
+    fn by_name(name: &str) -> Option<&'static Language> {
+        LANGUAGES.iter().find(|l| l.name == name)
+    }
+
+    /// Início do arquivo como texto (se parecer texto)
+    fn sniff(path: &Path) -> Option<String> {
+        let mut buffer = Vec::new();
+        fs::File::open(path).ok()?.take(SNIFF_LIMIT).read_to_end(&mut buffer).ok()?;
+
+        if buffer.contains(&0) {
+            return None;
+        }
+
+        Some(String::from_utf8_lossy(&buffer).to_string())
+    }
+}
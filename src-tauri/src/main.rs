@@ -2,13 +2,53 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod ai;
+mod api;
+mod build_cache;
+mod build_system;
+mod bundle;
+mod capture;
+mod compare;
+mod encoding;
+mod entry_point;
+mod fuzz;
+mod history;
+mod interactive;
+mod language;
+mod migrations;
+mod minimize;
+mod parameters;
+mod paths;
 mod project;
-
-use ai::{GroqService, TestCase, ProjectInfo, FullTestFromAI, ExecutionInfo};
+mod runner;
+mod settings;
+mod snapshot;
+mod storage;
+mod test_store;
+mod toolchain;
+mod web;
+
+use ai::{GroqService, TestCase, ProjectInfo, FullTestFromAI};
+use api::{ApiConfig, ApiRunner};
+use build_cache::{BuildArtifact, BuildCache};
+use build_system::{BuildDetector, BuildPlan};
+use bundle::{BundleService, BundleManifest, BundlePreview};
+use capture::OutputCapture;
+use compare::{ComparedSide, Comparator, OutputComparator};
+use entry_point::{EntryPoint, EntryPointCandidate, EntryPointService};
+use fuzz::{FuzzOptions, FuzzReport, Fuzzer};
+use history::{HistoryService, ExecutionComparison, ExecutionRecord, ExecutionSummary, TestExecutionResponse, TestRunRecord};
+use language::{LanguageDetector, LanguageStats};
+use migrations::Migrations;
+use minimize::{MinimizeOptions, MinimizeReport, Minimizer};
+use parameters::ParameterExpander;
 use project::ProjectService;
-use std::process::Command;
-use std::fs;
-use std::io::Write;
+use runner::TestRunner;
+use settings::{AppSettings, SettingsService};
+use snapshot::{SnapshotService, LegacySnapshot, SnapshotSummary};
+use test_store::TestStore;
+use toolchain::{ToolchainConfig, ToolchainReport, ToolchainService};
+use web::WebRunner;
+use std::path::Path;
 use std::time::Instant;
 
 #[tauri::command]
@@ -36,33 +76,21 @@ async fn generate_tests(project_id: String, app: tauri::AppHandle) -> Result<Vec
 
     println!("✅ {} FullTestFromAI gerados com sucesso!", full_tests.len());
 
-    // Converte para TestCase (para UI)
-    let test_cases: Vec<TestCase> = full_tests.iter().map(|full_test| TestCase {
-        id: full_test.id.clone(),
-        name: full_test.name.clone(),
-        description: full_test.description.clone(),
-        full_code: full_test.full_code.clone(),
-        status: "pending".to_string(),
-        execution_time: None,
-        legacy_output: None,
-        new_output: None,
-    }).collect();
-
-    // Salva ambos os arquivos
-    ProjectService::save_tests(&project_id, test_cases, &app).await?;
-    ProjectService::save_full_tests(&project_id, full_tests.clone(), &app).await?;
+    // Troca só os testes gerados antes; os escritos à mão ou importados continuam
+    TestStore::replace_generated(&project_id, full_tests, &app).await?;
 
-    println!("✅ Testes salvos em ambos os arquivos (tests.json e full_tests.json)");
+    println!("✅ Testes salvos em tests.json");
 
-    Ok(full_tests)
+    // Devolve com `${LEGACY_ROOT}`/`${NEW_ROOT}` resolvidos para as raízes do projeto
+    TestStore::list(&project_id, &app).await
 }
 
 #[tauri::command]
-async fn execute_tests(project_id: String, app: tauri::AppHandle) -> Result<Vec<TestCase>, String> {
+async fn execute_tests(project_id: String, app: tauri::AppHandle) -> Result<TestExecutionResponse, String> {
     println!("🚀 Carregando FullTestFromAI do projeto: {}", project_id);
 
     // Carrega FullTestFromAI persistido
-    let tests = ProjectService::load_full_tests(&project_id, &app).await?;
+    let tests = TestStore::list(&project_id, &app).await?;
 
     if tests.is_empty() {
         return Err("Nenhum FullTestFromAI encontrado. Por favor, gere testes primeiro.".to_string());
@@ -70,116 +98,100 @@ async fn execute_tests(project_id: String, app: tauri::AppHandle) -> Result<Vec<
 
     println!("🚀 Executando {} testes com metadados da IA", tests.len());
 
-    let mut executed_tests = Vec::new();
+    let started_at = chrono::Utc::now().to_rfc3339();
+    let execution_id = uuid::Uuid::new_v4().to_string();
+    let outputs_dir = HistoryService::outputs_dir(&project_id, &execution_id, &app)?;
+    let project = ProjectService::load_project(&project_id, &app).await?;
+    let (tests, unexpanded) = ParameterExpander::expand_all(&project, tests);
+    let mut executed_tests: Vec<TestRunRecord> = unexpanded.iter()
+        .map(|(test, e)| TestRunRecord::not_run(test, e))
+        .collect();
+    let toolchain = ToolchainService::check(&project, &tests, &["legacy", "new"]);
+    let legacy_cache = BuildCache::for_side(&project, "legacy", &app)?.with_capture_dir(&outputs_dir);
+    let new_cache = BuildCache::for_side(&project, "new", &app)?.with_capture_dir(&outputs_dir);
+
+    let server_tests = match project.project_type.as_str() {
+        "API" => Some(ApiRunner::run_tests(&project, &tests, &legacy_cache, &new_cache).await?),
+        "Web" => Some(WebRunner::run_tests(&project, &tests, &legacy_cache, &new_cache).await?),
+        _ => None,
+    };
+    if let Some(server_tests) = server_tests {
+        executed_tests.extend(server_tests);
+        let record = finish_execution(&execution_id, &project_id, "live", started_at, &toolchain, executed_tests, &app).await?;
+        return Ok(record.to_response());
+    }
 
     for (i, test) in tests.into_iter().enumerate() {
         println!("⚡ [{}/{}] Executando: {}", i + 1, executed_tests.len() + 1, test.name);
         let start_time = Instant::now();
 
-        if let Some(compile_cmd) = &test.legacy_exec.compile_command {
-            println!("🔨 Compilando legado: {}", compile_cmd);
-            match Command::new("sh").arg("-c").arg(compile_cmd).output() {
-                Ok(output) if output.status.success() => {
-                    println!("✅ Compilação legado bem-sucedida");
-                },
-                Ok(output) => {
-                    let stderr = String::from_utf8_lossy(&output.stderr);
-                    println!("❌ Erro na compilação legado: {}", stderr);
-                    continue;
-                },
-                Err(e) => {
-                    println!("❌ Erro ao compilar legado: {}", e);
-                    continue;
-                }
-            }
+        let mut missing = toolchain.missing_for("legacy", &test.legacy_exec);
+        missing.extend(toolchain.missing_for("new", &test.new_exec));
+        if !missing.is_empty() {
+            executed_tests.push(missing_tools_record(&test, &missing));
+            continue;
         }
 
-        if let Some(compile_cmd) = &test.new_exec.compile_command {
-            println!("🔨 Compilando novo: {}", compile_cmd);
-            match Command::new("sh").arg("-c").arg(compile_cmd).output() {
-                Ok(output) if output.status.success() => {
-                    println!("✅ Compilação novo bem-sucedida");
-                },
-                Ok(output) => {
-                    let stderr = String::from_utf8_lossy(&output.stderr);
-                    println!("❌ Erro na compilação novo: {}", stderr);
-                    continue;
-                },
-                Err(e) => {
-                    println!("❌ Erro ao compilar novo: {}", e);
-                    continue;
-                }
+        let prepared = EntryPointService::prepare(&legacy_cache, &project, "legacy", &test)
+            .and_then(|legacy| Ok((legacy, EntryPointService::prepare(&new_cache, &project, "new", &test)?)));
+        let (legacy_env, new_env) = match prepared {
+            Ok(envs) => envs,
+            Err(e) => {
+                executed_tests.push(TestRunRecord::not_run(&test, &e));
+                continue;
             }
+        };
+
+        let compiled = TestRunner::compile(&test.legacy_exec, &legacy_env, "legado")
+            .and_then(|_| TestRunner::compile(&test.new_exec, &new_env, "novo"));
+        if let Err(e) = compiled {
+            executed_tests.push(TestRunRecord::not_run(&test, &e));
+            continue;
         }
 
-        let test_inputs = extract_test_inputs(&test.full_code);
+        let test_inputs = TestRunner::test_inputs(&test);
 
         println!("🔍 Test inputs extraídos: {:?}", test_inputs);
         println!("📝 Código do teste: {}", test.full_code);
 
-        // Monta o comando completo com argumentos
-        // Escapa caracteres especiais do shell (*, ?, etc)
-        let escaped_inputs: Vec<String> = test_inputs.iter().map(|input| {
-            if input == "*" {
-                "'*'".to_string()
-            } else if input == "?" {
-                "'?'".to_string()
-            } else {
-                input.clone()
-            }
-        }).collect();
-
-        let legacy_cmd = if test_inputs.is_empty() {
-            test.legacy_exec.execute_command.clone()
-        } else {
-            format!("{} {}", test.legacy_exec.execute_command, escaped_inputs.join(" "))
-        };
-        let new_cmd = if test_inputs.is_empty() {
-            test.new_exec.execute_command.clone()
-        } else {
-            format!("{} {}", test.new_exec.execute_command, escaped_inputs.join(" "))
-        };
-
-        println!("🎯 Comando legado: {}", legacy_cmd);
-        println!("🎯 Comando novo: {}", new_cmd);
-
-        println!("🎯 Executando legado: {}", legacy_cmd);
-        let legacy_result = Command::new("sh")
-            .arg("-c")
-            .arg(&legacy_cmd)
-            .current_dir(&test.legacy_exec.working_directory)
-            .output();
-
-        println!("🎯 Executando novo: {}", new_cmd);
-        let new_result = Command::new("sh")
-            .arg("-c")
-            .arg(&new_cmd)
-            .current_dir(&test.new_exec.working_directory)
-            .output();
+        // Arquivos afetados só são observados quando o teste declara `trackedFiles`;
+        // aí entram no veredito (como na execução por snapshot) e vão para o comparador externo
+        let comparator = OutputComparator::for_test(&project, &test);
+        let legacy_result = SnapshotService::run_with_effects(&test, &test.legacy_exec, &test_inputs, &legacy_env, "legado");
+        let new_result = SnapshotService::run_with_effects(&test, &test.new_exec, &test_inputs, &new_env, "novo");
 
         match (legacy_result, new_result) {
-            (Ok(legacy_output), Ok(new_output)) => {
+            (Ok((legacy_output, legacy_effects)), Ok((new_output, new_effects))) => {
                 let execution_time = start_time.elapsed().as_millis() as u64;
 
-                let legacy_out = String::from_utf8_lossy(&legacy_output.stdout).to_string();
-                let new_out = String::from_utf8_lossy(&new_output.stdout).to_string();
-
-                let legacy_final = if legacy_out.trim().is_empty() {
-                    String::from_utf8_lossy(&legacy_output.stderr).to_string()
-                } else {
-                    legacy_out
-                };
-
-                let new_final = if new_out.trim().is_empty() {
-                    String::from_utf8_lossy(&new_output.stderr).to_string()
-                } else {
-                    new_out
-                };
-
-                // Compara outputs - considera sucesso se forem idênticos ou se ambos são mensagens de uso
-                let success = legacy_final.trim() == new_final.trim() ||
-                             (legacy_final.contains("Uso:") && new_final.contains("Uso:") &&
-                              legacy_final.contains("<numero1>") && new_final.contains("<numero1>"));
+                let legacy_final = legacy_output.display_output();
+                let new_final = new_output.display_output();
+
+                let comparison = OutputComparator::compare(
+                    &comparator,
+                    &ComparedSide {
+                        output: &legacy_output,
+                        effects: &legacy_effects,
+                        dir: Some(Path::new(&test.legacy_exec.working_directory)),
+                        root: Some(&project.legacy_path),
+                    },
+                    &ComparedSide {
+                        output: &new_output,
+                        effects: &new_effects,
+                        dir: Some(Path::new(&test.new_exec.working_directory)),
+                        root: Some(&project.new_path),
+                    }
+                );
+                let effect_differences = SnapshotService::compare_effects(&legacy_effects, &new_effects);
+
+                let differences: Vec<String> = comparison.differences.iter()
+                    .map(|d| d.describe())
+                    .chain(effect_differences.iter().cloned())
+                    .collect();
+                for difference in &differences {
+                    println!("   ↳ {}", difference);
+                }
+                let success = comparison.passed(&effect_differences);
 
                 let test_case = TestCase {
                     id: test.id.clone(),
@@ -192,7 +204,14 @@ async fn execute_tests(project_id: String, app: tauri::AppHandle) -> Result<Vec<
                     new_output: Some(new_final),
                 };
 
-                executed_tests.push(test_case);
+                executed_tests.push(TestRunRecord {
+                    result: test_case,
+                    legacy_run: Some(legacy_output),
+                    new_run: Some(new_output),
+                    differences,
+                    comparison: Some(comparison),
+                    ..Default::default()
+                });
 
                 println!("{} Teste {} concluído em {}ms - Status: {}",
                         if success { "✅" } else { "❌" },
@@ -214,107 +233,577 @@ async fn execute_tests(project_id: String, app: tauri::AppHandle) -> Result<Vec<
                     new_output: Some(format!("Erro: {}", e)),
                 };
 
-                executed_tests.push(test_case);
+                executed_tests.push(TestRunRecord { result: test_case, ..Default::default() });
             }
         }
     }
 
+    let record = finish_execution(&execution_id, &project_id, "live", started_at, &toolchain, executed_tests, &app).await?;
+
     println!("✅ {} testes executados! Passaram: {}, Falharam: {}",
-             executed_tests.len(),
-             executed_tests.iter().filter(|t| t.status == "passed").count(),
-             executed_tests.iter().filter(|t| t.status == "failed").count());
+             record.summary.total_tests,
+             record.summary.passed_tests,
+             record.summary.failed_tests);
+
+    Ok(record.to_response())
+}
+
+/// Guarda o último resultado de cada teste e registra a execução no histórico.
+/// As saídas em `TestCase` (enviadas à UI e guardadas em tests.json) viram prévias.
+async fn finish_execution(
+    execution_id: &str,
+    project_id: &str,
+    mode: &str,
+    started_at: String,
+    toolchain: &ToolchainReport,
+    mut executed_tests: Vec<TestRunRecord>,
+    app: &tauri::AppHandle
+) -> Result<ExecutionRecord, String> {
+    let preview_bytes = SettingsService::load(app)?.preview_bytes;
+    for test in &mut executed_tests {
+        for output in [&mut test.result.legacy_output, &mut test.result.new_output].into_iter().flatten() {
+            *output = OutputCapture::preview(output, preview_bytes);
+        }
+    }
+
+    let results: Vec<TestCase> = executed_tests.iter().map(|t| t.result.clone()).collect();
+    TestStore::record_results(project_id, &results, app).await?;
+
+    let project_name = ProjectService::load_project(project_id, app).await
+        .map(|p| p.name)
+        .unwrap_or_default();
+
+    let record = ExecutionRecord::new(execution_id, project_id, &project_name, mode, started_at, toolchain, executed_tests);
+    HistoryService::save(&record, app)?;
 
-    Ok(executed_tests)
+    Ok(record)
 }
 
+/// Resultado de um teste que não pôde rodar por falta de compilador/interpretador
+fn missing_tools_record(test: &FullTestFromAI, missing: &[String]) -> TestRunRecord {
+    TestRunRecord::not_run(test, &format!("Ferramenta não encontrada no PATH: {}", missing.join(", ")))
+}
 
-fn extract_test_inputs(test_code: &str) -> Vec<String> {
-    // Remove comentários e linhas vazias
-    let clean_code = test_code
-        .lines()
-        .filter(|line| !line.trim().starts_with("//") && !line.trim().is_empty())
-        .collect::<Vec<_>>()
-        .join(" ");
+/// Verifica se os compiladores/interpretadores exigidos pelos testes estão instalados
+/// (a UI chama antes de executar para avisar sobre ferramentas ausentes)
+#[tauri::command]
+async fn check_toolchains(project_id: String, app: tauri::AppHandle) -> Result<ToolchainReport, String> {
+    let project = ProjectService::load_project(&project_id, &app).await?;
+    let tests = TestStore::list(&project_id, &app).await?;
 
-    // Padrão 1: Extrai números e operadores diretamente do código
-    use regex::Regex;
-    let re_basic = Regex::new(r#"(\d+\.?\d*|[+\-*/%^])"#).unwrap();
+    Ok(ToolchainService::check(&project, &tests, &["legacy", "new"]))
+}
 
-    let inputs: Vec<String> = re_basic.find_iter(&clean_code)
-        .map(|m| m.as_str().to_string())
-        .collect();
+/// Grava a saída do legado (snapshot) para os testes selecionados, ou para todos se nenhum for informado
+#[tauri::command]
+async fn record_snapshots(project_id: String, test_ids: Option<Vec<String>>, app: tauri::AppHandle) -> Result<Vec<LegacySnapshot>, String> {
+    let tests = TestStore::list(&project_id, &app).await?;
 
-    // Se já encontramos inputs suficientes (pelo menos 3 para num1 op num2), retorna
-    if inputs.len() >= 3 {
-        return inputs;
+    if tests.is_empty() {
+        return Err("Nenhum FullTestFromAI encontrado. Por favor, gere testes primeiro.".to_string());
     }
 
-    // Padrão 2: Casos incompletos como "2 +" ou "+ 3" - detectar erro esperado
-    if inputs.len() == 2 {
-        // Verifica se temos número + operador ou operador + número
-        let has_number = inputs.iter().any(|i| i.chars().next().unwrap().is_numeric());
-        let has_operator = inputs.iter().any(|i| "+-*/%^".contains(i));
+    let project = ProjectService::load_project(&project_id, &app).await?;
+    if matches!(project.project_type.as_str(), "API" | "Web") {
+        return Err(format!("Snapshots ainda não são suportados em projetos do tipo {}", project.project_type));
+    }
 
-        if has_number && has_operator {
-            // É um caso incompleto esperando erro (como "2 +" ou "+ 3")
-            // Retorna os inputs existentes para que o programa mostre erro de argumentos faltando
-            return inputs;
-        }
+    // Selecionar um teste parametrizado grava todas as suas instâncias; os que não
+    // puderam ser expandidos ficam sem snapshot (o erro já foi registrado no log)
+    let (tests, _) = ParameterExpander::expand_all(&project, tests);
+    let selected: Vec<FullTestFromAI> = match &test_ids {
+        Some(ids) => tests.into_iter()
+            .filter(|t| ids.contains(&t.id) || ParameterExpander::parent_id(&t.id).is_some_and(|p| ids.iter().any(|id| id == p)))
+            .collect(),
+        None => tests,
+    };
+
+    println!("📸 Gravando snapshots de {} testes", selected.len());
+    let legacy_cache = BuildCache::for_side(&project, "legacy", &app)?;
+
+    let mut snapshots = Vec::new();
+    for test in &selected {
+        let legacy_env = EntryPointService::prepare(&legacy_cache, &project, "legacy", test)?;
+        snapshots.push(SnapshotService::record(&project_id, test, &legacy_env, &app)?);
     }
 
-    // Padrão 3: Procura por declarações de variáveis com valores
-    let re_vars = Regex::new(r#"(?:let|const|int|float|double)\s+\w+\s*=\s*(\d+\.?\d*)\s*;?"#).unwrap();
-    let var_values: Vec<String> = re_vars.captures_iter(&clean_code)
-        .map(|cap| cap[1].to_string())
-        .collect();
+    Ok(snapshots)
+}
 
-    if var_values.len() >= 2 {
-        // Se temos pelo menos 2 variáveis numéricas, procuramos o operador
-        let re_op = Regex::new(r#"([+\-*/^])"#).unwrap();
-        let op_match = re_op.find(&clean_code);
+#[tauri::command]
+async fn list_snapshots(project_id: String, app: tauri::AppHandle) -> Result<Vec<SnapshotSummary>, String> {
+    SnapshotService::list(&project_id, &app)
+}
 
-        let mut result = var_values;
-        if let Some(op) = op_match {
-            result.insert(1, op.as_str().to_string());
-        }
-        return result;
+/// Executa apenas a nova implementação e compara com os snapshots gravados do legado
+#[tauri::command]
+async fn execute_tests_from_snapshots(project_id: String, app: tauri::AppHandle) -> Result<TestExecutionResponse, String> {
+    let tests = TestStore::list(&project_id, &app).await?;
+
+    if tests.is_empty() {
+        return Err("Nenhum FullTestFromAI encontrado. Por favor, gere testes primeiro.".to_string());
     }
 
-    // Padrão 4: Procura por chamadas de função com argumentos
-    let re_func = Regex::new(r#"[a-zA-Z_]\w*\s*\(\s*(\d+\.?\d*)\s*,\s*([+\-*/^])\s*,\s*(\d+\.?\d*)\s*\)"#).unwrap();
-    if let Some(cap) = re_func.captures(&clean_code) {
-        return vec![
-            cap[1].to_string(),
-            cap[2].to_string(),
-            cap[3].to_string()
-        ];
+    println!("🚀 Executando {} testes contra os snapshots do legado", tests.len());
+
+    let started_at = chrono::Utc::now().to_rfc3339();
+    let project = ProjectService::load_project(&project_id, &app).await?;
+    if matches!(project.project_type.as_str(), "API" | "Web") {
+        return Err(format!("Snapshots ainda não são suportados em projetos do tipo {}", project.project_type));
     }
 
-    // Padrão 5: Testes com variáveis (a + b) - usar valores de exemplo
-    if clean_code.contains("a") && clean_code.contains("b") {
-        if let Some(op_match) = re_basic.find(&clean_code) {
-            let op = op_match.as_str();
-            if op.chars().any(|c| "+-*/%^".contains(c)) {
-                return vec!["5".to_string(), op.to_string(), "3".to_string()];
+    let (tests, unexpanded) = ParameterExpander::expand_all(&project, tests);
+    let toolchain = ToolchainService::check(&project, &tests, &["new"]);
+    let execution_id = uuid::Uuid::new_v4().to_string();
+    let outputs_dir = HistoryService::outputs_dir(&project_id, &execution_id, &app)?;
+    let new_cache = BuildCache::for_side(&project, "new", &app)?.with_capture_dir(&outputs_dir);
+    let mut executed_tests: Vec<TestRunRecord> = unexpanded.iter()
+        .map(|(test, e)| TestRunRecord::not_run(test, e))
+        .collect();
+
+    for test in tests {
+        let start_time = Instant::now();
+
+        let snapshot = match SnapshotService::load_latest(&project_id, &test.id, &app)? {
+            Some(snapshot) => snapshot,
+            None => {
+                executed_tests.push(TestRunRecord::not_run(&test, "Nenhum snapshot gravado para este teste"));
+                continue;
             }
+        };
+
+        let missing = toolchain.missing_for("new", &test.new_exec);
+        if !missing.is_empty() {
+            executed_tests.push(missing_tools_record(&test, &missing));
+            continue;
         }
-    }
 
-    // Padrão 6: Teste de uso (vazio ou sem argumentos) - não precisa de inputs
-    if clean_code.trim().is_empty() || clean_code.to_lowercase().contains("usage") ||
-       clean_code.to_lowercase().contains("sem argumentos") || clean_code.to_lowercase().contains("no args") {
-        return vec![];  // Teste de uso intencionalmente vazio
+        let new_env = match EntryPointService::prepare(&new_cache, &project, "new", &test) {
+            Ok(env) => env,
+            Err(e) => {
+                executed_tests.push(TestRunRecord::not_run(&test, &e));
+                continue;
+            }
+        };
+
+        if let Err(e) = TestRunner::compile(&test.new_exec, &new_env, "novo") {
+            executed_tests.push(TestRunRecord::not_run(&test, &e));
+            continue;
+        }
+
+        let test_inputs = TestRunner::test_inputs(&test);
+        let legacy_final = snapshot.output.display_output();
+
+        let (status, new_final, new_run, comparison, differences) = match SnapshotService::run_with_effects(&test, &test.new_exec, &test_inputs, &new_env, "novo") {
+            Ok((new_output, new_effects)) => {
+                let new_final = new_output.display_output();
+                let comparison = OutputComparator::compare(
+                    &OutputComparator::for_test(&project, &test),
+                    &ComparedSide {
+                        output: &snapshot.output,
+                        effects: &snapshot.file_effects,
+                        dir: None,
+                        root: Some(&project.legacy_path),
+                    },
+                    &ComparedSide {
+                        output: &new_output,
+                        effects: &new_effects,
+                        dir: Some(Path::new(&test.new_exec.working_directory)),
+                        root: Some(&project.new_path),
+                    }
+                );
+                // Snapshots antigos observavam o diretório inteiro; só vale o que o teste declara
+                let effect_differences = if SnapshotService::tracks_effects(&test) {
+                    SnapshotService::compare_effects(&snapshot.file_effects, &new_effects)
+                } else {
+                    Vec::new()
+                };
+
+                let differences: Vec<String> = comparison.differences.iter()
+                    .map(|d| d.describe())
+                    .chain(effect_differences.iter().cloned())
+                    .collect();
+                for difference in &differences {
+                    println!("   ↳ {}", difference);
+                }
+
+                let success = comparison.passed(&effect_differences);
+
+                (if success { "passed" } else { "failed" }, new_final, Some(new_output), Some(comparison), differences)
+            },
+            Err(e) => {
+                println!("❌ Erro executando teste {}: {}", test.name, e);
+                ("failed", format!("Erro: {}", e), None, None, Vec::new())
+            }
+        };
+
+        let execution_time = start_time.elapsed().as_millis() as u64;
+        println!("{} Teste {} (snapshot v{}) concluído em {}ms",
+                 if status == "passed" { "✅" } else { "❌" },
+                 test.name,
+                 snapshot.version,
+                 execution_time);
+
+        let test_case = TestCase {
+            id: test.id.clone(),
+            name: test.name.clone(),
+            description: test.description.clone(),
+            full_code: test.full_code.clone(),
+            status: status.to_string(),
+            execution_time: Some(execution_time),
+            legacy_output: Some(legacy_final),
+            new_output: Some(new_final),
+        };
+
+        executed_tests.push(TestRunRecord {
+            result: test_case,
+            legacy_run: Some(snapshot.output),
+            new_run,
+            differences,
+            comparison,
+            ..Default::default()
+        });
     }
 
-    // Padrão 7: Fallback para operadores não suportados - usar valores de exemplo
-    if !inputs.is_empty() {
-        return inputs;
+    let record = finish_execution(&execution_id, &project_id, "snapshot", started_at, &toolchain, executed_tests, &app).await?;
+    Ok(record.to_response())
+}
+
+
+#[tauri::command]
+async fn list_executions(project_id: String, app: tauri::AppHandle) -> Result<Vec<ExecutionSummary>, String> {
+    HistoryService::list(&project_id, &app)
+}
+
+#[tauri::command]
+async fn get_execution(project_id: String, execution_id: String, app: tauri::AppHandle) -> Result<ExecutionRecord, String> {
+    HistoryService::get(&project_id, &execution_id, &app)
+}
+
+#[tauri::command]
+async fn delete_execution(project_id: String, execution_id: String, app: tauri::AppHandle) -> Result<(), String> {
+    HistoryService::delete(&project_id, &execution_id, &app)
+}
+
+#[tauri::command]
+async fn delete_all_executions(project_id: String, app: tauri::AppHandle) -> Result<(), String> {
+    HistoryService::delete_all(&project_id, &app)
+}
+
+/// Compara duas execuções (novas falhas, correções, testes adicionados/removidos e tempos).
+/// Sem `base_execution_id`, compara com a execução anterior.
+#[tauri::command]
+async fn compare_executions(
+    project_id: String,
+    base_execution_id: Option<String>,
+    target_execution_id: String,
+    app: tauri::AppHandle
+) -> Result<ExecutionComparison, String> {
+    HistoryService::compare(&project_id, base_execution_id.as_deref(), &target_execution_id, &app)
+}
+
+/// Remove execuções antigas mantendo as `keep` mais recentes (padrão: retenção configurada)
+#[tauri::command]
+async fn prune_executions(project_id: String, keep: Option<usize>, app: tauri::AppHandle) -> Result<usize, String> {
+    let keep = match keep {
+        Some(keep) => keep,
+        None => SettingsService::load(&app)?.execution_retention,
+    };
+
+    HistoryService::prune(&project_id, keep, &app)
+}
+
+#[tauri::command]
+async fn get_settings(app: tauri::AppHandle) -> Result<AppSettings, String> {
+    SettingsService::load(&app)
+}
+
+#[tauri::command]
+async fn update_settings(settings: AppSettings, app: tauri::AppHandle) -> Result<AppSettings, String> {
+    SettingsService::save(&settings, &app)?;
+    SettingsService::load(&app)
+}
+
+#[tauri::command]
+async fn list_full_tests(project_id: String, app: tauri::AppHandle) -> Result<Vec<FullTestFromAI>, String> {
+    TestStore::list(&project_id, &app).await
+}
+
+/// Lista os testes com o último resultado, no formato exibido pela UI
+#[tauri::command]
+async fn list_test_cases(project_id: String, app: tauri::AppHandle) -> Result<Vec<TestCase>, String> {
+    TestStore::list_test_cases(&project_id, &app).await
+}
+
+#[tauri::command]
+async fn delete_all_tests(project_id: String, app: tauri::AppHandle) -> Result<(), String> {
+    TestStore::delete_all(&project_id, &app).await
+}
+
+#[tauri::command]
+async fn get_full_test(project_id: String, test_id: String, app: tauri::AppHandle) -> Result<FullTestFromAI, String> {
+    TestStore::get(&project_id, &test_id, &app).await
+}
+
+#[tauri::command]
+async fn create_full_test(project_id: String, test: FullTestFromAI, app: tauri::AppHandle) -> Result<FullTestFromAI, String> {
+    TestStore::create(&project_id, test, &app).await
+}
+
+#[tauri::command]
+async fn update_full_test(project_id: String, test: FullTestFromAI, app: tauri::AppHandle) -> Result<FullTestFromAI, String> {
+    TestStore::update(&project_id, test, &app).await
+}
+
+#[tauri::command]
+async fn delete_full_test(project_id: String, test_id: String, app: tauri::AppHandle) -> Result<(), String> {
+    TestStore::delete(&project_id, &test_id, &app).await
+}
+
+#[tauri::command]
+async fn duplicate_full_test(project_id: String, test_id: String, app: tauri::AppHandle) -> Result<FullTestFromAI, String> {
+    TestStore::duplicate(&project_id, &test_id, &app).await
+}
+
+#[tauri::command]
+async fn list_projects(app: tauri::AppHandle) -> Result<Vec<ProjectInfo>, String> {
+    ProjectService::list_projects(&app)
+}
+
+#[tauri::command]
+async fn create_project(
+    name: String,
+    legacy_path: String,
+    new_path: String,
+    project_type: String,
+    app: tauri::AppHandle
+) -> Result<ProjectInfo, String> {
+    ProjectService::create_project(&name, &legacy_path, &new_path, &project_type, &app)
+}
+
+#[tauri::command]
+async fn update_project(project: ProjectInfo, app: tauri::AppHandle) -> Result<ProjectInfo, String> {
+    ProjectService::update_project(project, &app)
+}
+
+#[tauri::command]
+async fn rename_project(project_id: String, name: String, app: tauri::AppHandle) -> Result<ProjectInfo, String> {
+    ProjectService::rename_project(&project_id, &name, &app)
+}
+
+#[tauri::command]
+async fn touch_project(project_id: String, app: tauri::AppHandle) -> Result<ProjectInfo, String> {
+    ProjectService::touch_project(&project_id, &app)
+}
+
+#[tauri::command]
+async fn delete_project(project_id: String, app: tauri::AppHandle) -> Result<(), String> {
+    ProjectService::delete_project(&project_id, &app)
+}
+
+/// Sistemas de build detectados nos dois lados do projeto
+#[derive(serde::Serialize)]
+struct ProjectBuildSystems {
+    legacy: Vec<BuildPlan>,
+    new: Vec<BuildPlan>,
+    /// Testes cujos comandos ignoram o sistema de build detectado
+    warnings: Vec<String>,
+}
+
+#[tauri::command]
+async fn detect_build_systems(project_id: String, app: tauri::AppHandle) -> Result<ProjectBuildSystems, String> {
+    let project = ProjectService::load_project(&project_id, &app).await?;
+
+    let legacy = BuildDetector::detect(std::path::Path::new(&project.legacy_path));
+    let new = BuildDetector::detect(std::path::Path::new(&project.new_path));
+
+    let mut warnings = Vec::new();
+    for test in TestStore::list(&project_id, &app).await? {
+        if let Some(warning) = BuildDetector::check_exec(&test.legacy_exec, &legacy) {
+            warnings.push(format!("{} (legado): {}", test.id, warning));
+        }
+        if let Some(warning) = BuildDetector::check_exec(&test.new_exec, &new) {
+            warnings.push(format!("{} (novo): {}", test.id, warning));
+        }
     }
 
-    // Se nada funcionou, retorna vazio para fallback
-    vec![]
+    println!("🔧 Sistemas de build: {} planos no legado, {} no novo, {} avisos", legacy.len(), new.len(), warnings.len());
+    Ok(ProjectBuildSystems { legacy, new, warnings })
 }
 
+/// Linguagens dos dois lados do projeto, com arquivos e linhas de cada uma
+#[derive(serde::Serialize)]
+struct ProjectSummary {
+    legacy: Vec<LanguageStats>,
+    new: Vec<LanguageStats>,
+}
+
+#[tauri::command]
+async fn project_summary(project_id: String, app: tauri::AppHandle) -> Result<ProjectSummary, String> {
+    let project = ProjectService::load_project(&project_id, &app).await?;
+
+    let legacy = LanguageDetector::summarize(std::path::Path::new(&project.legacy_path))?;
+    let new = LanguageDetector::summarize(std::path::Path::new(&project.new_path))?;
+
+    println!("📊 Linguagens: {} no legado, {} no novo", legacy.len(), new.len());
+    Ok(ProjectSummary { legacy, new })
+}
+
+/// Usa um plano detectado como bloco de execução de um lado ("legacy" ou "new") em todos os testes
+#[tauri::command]
+async fn apply_build_plan(project_id: String, side: String, plan: BuildPlan, app: tauri::AppHandle) -> Result<Vec<FullTestFromAI>, String> {
+    let project = ProjectService::load_project(&project_id, &app).await?;
+    let root = match side.as_str() {
+        "legacy" => &project.legacy_path,
+        "new" => &project.new_path,
+        _ => return Err(format!("Lado inválido: {} (use legacy ou new)", side)),
+    };
+
+    TestStore::replace_exec(&project_id, &side, plan.to_exec_info(root), &app).await?;
+    TestStore::list(&project_id, &app).await
+}
+
+/// Compila os executáveis principais dos dois lados no diretório de build do projeto
+#[tauri::command]
+async fn build_project_executables(project_id: String, app: tauri::AppHandle) -> Result<(String, String), String> {
+    let project = ProjectService::load_project(&project_id, &app).await?;
+    ProjectService::ensure_both_executables(&project, &app)
+}
+
+/// Define como subir os servidores de legado e novo de um projeto do tipo API
+#[tauri::command]
+async fn set_api_config(
+    project_id: String,
+    config: Option<ApiConfig>,
+    app: tauri::AppHandle
+) -> Result<ProjectInfo, String> {
+    ProjectService::set_api_config(&project_id, config, &app)
+}
+
+/// Define a codificação das saídas de um lado ("legacy" ou "new"): "auto", "ISO-8859-1", "CP850"...
+#[tauri::command]
+async fn set_project_encoding(
+    project_id: String,
+    side: String,
+    encoding: Option<String>,
+    app: tauri::AppHandle
+) -> Result<ProjectInfo, String> {
+    ProjectService::set_encoding(&project_id, &side, encoding, &app)
+}
+
+/// Define o comparador padrão (texto, JSON, CSV, XML ou programa externo) dos testes do projeto
+#[tauri::command]
+async fn set_project_comparator(
+    project_id: String,
+    comparator: Option<Comparator>,
+    app: tauri::AppHandle
+) -> Result<ProjectInfo, String> {
+    ProjectService::set_comparator(&project_id, comparator, &app)
+}
+
+/// Fuzzing diferencial a partir de um teste: varia args/stdin, roda legado e novo
+/// e salva como novos testes as entradas que divergem
+#[tauri::command]
+async fn fuzz_test(
+    project_id: String,
+    test_id: String,
+    options: Option<FuzzOptions>,
+    app: tauri::AppHandle
+) -> Result<FuzzReport, String> {
+    Fuzzer::run(&project_id, &test_id, options.unwrap_or_default(), &app).await
+}
+
+/// Reduz args, linhas do stdin e fixtures de um teste que diverge, mantendo a
+/// divergência, e salva a reprodução mínima como novo teste
+#[tauri::command]
+async fn minimize_test(
+    project_id: String,
+    test_id: String,
+    options: Option<MinimizeOptions>,
+    app: tauri::AppHandle
+) -> Result<MinimizeReport, String> {
+    Minimizer::run(&project_id, &test_id, options.unwrap_or_default(), &app).await
+}
+
+/// Candidatos a ponto de entrada de um lado ("legacy" ou "new"), do mais provável ao menos
+#[tauri::command]
+async fn list_entry_point_candidates(
+    project_id: String,
+    side: String,
+    app: tauri::AppHandle
+) -> Result<Vec<EntryPointCandidate>, String> {
+    let project = ProjectService::load_project(&project_id, &app).await?;
+    let root = match side.as_str() {
+        "legacy" => &project.legacy_path,
+        "new" => &project.new_path,
+        _ => return Err(format!("Lado inválido: {} (use legacy ou new)", side)),
+    };
+
+    EntryPointService::discover(root)
+}
+
+/// Fixa os pontos de entrada de um lado, na ordem de preferência
+#[tauri::command]
+async fn set_entry_points(
+    project_id: String,
+    side: String,
+    entry_points: Vec<EntryPoint>,
+    app: tauri::AppHandle
+) -> Result<ProjectInfo, String> {
+    ProjectService::set_entry_points(&project_id, &side, entry_points, &app)
+}
+
+#[tauri::command]
+async fn list_build_artifacts(project_id: String, app: tauri::AppHandle) -> Result<Vec<BuildArtifact>, String> {
+    BuildCache::list(&project_id, &app)
+}
+
+/// Remove os artefatos de compilação do projeto (guardados em app data); retorna os bytes liberados
+#[tauri::command]
+async fn clean_build_artifacts(project_id: String, app: tauri::AppHandle) -> Result<u64, String> {
+    BuildCache::clean(&project_id, &app)
+}
+
+/// Fixa compiladores/interpretadores, PATH extra e flags de um lado ("legacy" ou "new")
+#[tauri::command]
+async fn set_project_toolchain(
+    project_id: String,
+    side: String,
+    toolchain: Option<ToolchainConfig>,
+    app: tauri::AppHandle
+) -> Result<ProjectInfo, String> {
+    ProjectService::set_toolchain(&project_id, &side, toolchain, &app)
+}
+
+/// Exporta o projeto para um `.zip` portátil (projeto, testes, configurações e snapshots)
+#[tauri::command]
+async fn export_project(
+    project_id: String,
+    destination: String,
+    include_snapshots: Option<bool>,
+    app: tauri::AppHandle
+) -> Result<BundleManifest, String> {
+    BundleService::export(&project_id, &destination, include_snapshots.unwrap_or(true), &app).await
+}
+
+#[tauri::command]
+async fn preview_project_bundle(bundle_path: String, app: tauri::AppHandle) -> Result<BundlePreview, String> {
+    BundleService::preview(&bundle_path, &app)
+}
+
+/// Importa um pacote como novo projeto, trocando os diretórios da máquina de origem;
+/// as configurações do pacote só são aplicadas com `apply_settings`
+#[tauri::command]
+async fn import_project(
+    bundle_path: String,
+    name: Option<String>,
+    legacy_path: Option<String>,
+    new_path: Option<String>,
+    apply_settings: Option<bool>,
+    app: tauri::AppHandle
+) -> Result<ProjectInfo, String> {
+    BundleService::import(&bundle_path, name, legacy_path, new_path, apply_settings.unwrap_or(false), &app).await
+}
 
 #[tauri::command]
 async fn validate_project(project_id: String, app: tauri::AppHandle) -> Result<ProjectInfo, String> {
@@ -334,7 +823,60 @@ fn main() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_fs::init())
-        .invoke_handler(tauri::generate_handler![generate_tests, execute_tests, validate_project])
+        .setup(|app| {
+            if let Err(e) = Migrations::run(app.handle()) {
+                println!("❌ Erro ao migrar dados: {}", e);
+            }
+            Ok(())
+        })
+        .invoke_handler(tauri::generate_handler![
+            generate_tests,
+            execute_tests,
+            check_toolchains,
+            set_project_toolchain,
+            validate_project,
+            list_projects,
+            create_project,
+            update_project,
+            rename_project,
+            touch_project,
+            delete_project,
+            detect_build_systems,
+            project_summary,
+            apply_build_plan,
+            build_project_executables,
+            list_entry_point_candidates,
+            set_entry_points,
+            set_api_config,
+            set_project_comparator,
+            fuzz_test,
+            minimize_test,
+            set_project_encoding,
+            list_build_artifacts,
+            clean_build_artifacts,
+            export_project,
+            preview_project_bundle,
+            import_project,
+            record_snapshots,
+            list_snapshots,
+            execute_tests_from_snapshots,
+            list_executions,
+            get_execution,
+            delete_execution,
+            delete_all_executions,
+            prune_executions,
+            compare_executions,
+            get_settings,
+            update_settings,
+            list_full_tests,
+            list_test_cases,
+            delete_all_tests,
+            get_full_test,
+            create_full_test,
+            update_full_test,
+            delete_full_test,
+            duplicate_full_test
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -0,0 +1,292 @@
+use crate::ai::{FullTestFromAI, ProjectInfo, TestCase};
+use crate::history::{ExecutionRecord, ExecutionSummary};
+use crate::paths::PortablePaths;
+use crate::project::ProjectsDocument;
+use crate::storage::{Storage, CURRENT_SCHEMA_VERSION};
+use crate::test_store::{StoredTest, TestResult, TestSuite};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+/// Só a versão de um documento, lida sem montar o documento inteiro
+#[derive(Deserialize)]
+struct DocumentVersion {
+    #[serde(rename = "schemaVersion", default)]
+    schema_version: u32,
+}
+
+/// Migrações dos dados persistidos, executadas na inicialização do app
+pub struct Migrations;
+
+impl Migrations {
+    /// Atualiza todos os documentos em app data para o formato atual
+    pub fn run(app: &tauri::AppHandle) -> Result<(), String> {
+        let app_data_dir = app.path().app_data_dir()
+            .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+        if !app_data_dir.exists() {
+            return Ok(());
+        }
+
+        println!("🔄 Verificando migrações de dados em {:?}", app_data_dir);
+
+        {
+            let _lock = Storage::lock_projects(app)?;
+            Self::migrate_projects_document(&app_data_dir.join("projects.json"))?;
+        }
+        Self::upgrade_document(&app_data_dir.join("settings.json"))?;
+
+        let projects: ProjectsDocument = Storage::read_json(&app_data_dir.join("projects.json"))?.unwrap_or_default();
+
+        for project_id in Self::known_project_ids(&app_data_dir) {
+            let _lock = Storage::lock_project(&project_id, app)?;
+            let project_dir = app_data_dir.join("projects").join(&project_id);
+
+            Self::migrate_test_stores(&project_id, &app_data_dir, &project_dir)?;
+            Self::migrate_execution_history(&project_id, &app_data_dir, &project_dir)?;
+
+            if let Some(project) = projects.projects.iter().find(|p| p.id == project_id) {
+                Self::migrate_portable_paths(project, &project_dir)?;
+            }
+
+            for document in Self::versioned_documents(&project_dir) {
+                Self::upgrade_document(&document)?;
+            }
+        }
+
+        println!("✅ Migrações concluídas");
+        Ok(())
+    }
+
+    /// `projects.json` era uma lista; passa a ser `{ schemaVersion, projects }`
+    fn migrate_projects_document(path: &Path) -> Result<(), String> {
+        let value: Value = match Storage::read_json(path)? {
+            Some(value) => value,
+            None => return Ok(()),
+        };
+
+        if let Value::Array(projects) = value {
+            println!("🔄 Migrando projects.json para o formato versionado");
+            return Storage::write_json_atomic(path, &json!({
+                "schemaVersion": CURRENT_SCHEMA_VERSION,
+                "projects": projects,
+            }));
+        }
+
+        Self::upgrade_document(path)
+    }
+
+    /// Importa `full_tests.json`, `tests-<id>.json` e o antigo `tests.json` (lista de TestCase)
+    /// para a suíte única. Os arquivos antigos ficam como `*.migrated` (o `tests.json`
+    /// antigo é copiado antes de ser substituído), com os resultados que não foram migrados.
+    fn migrate_test_stores(project_id: &str, app_data_dir: &Path, project_dir: &Path) -> Result<(), String> {
+        let full_tests_path = project_dir.join("full_tests.json");
+        let ui_tests_path = app_data_dir.join(format!("tests-{}.json", project_id));
+        let suite_path = project_dir.join("tests.json");
+
+        let existing: Option<Value> = Storage::read_json(&suite_path)?;
+        if existing.as_ref().is_some_and(|v| v.is_object()) {
+            return Ok(());
+        }
+
+        let definitions: Vec<FullTestFromAI> = Storage::read_json(&full_tests_path)?.unwrap_or_default();
+
+        let mut results: HashMap<String, TestCase> = Storage::read_json(&ui_tests_path)?.unwrap_or_default();
+        if let Some(old_tests) = existing.and_then(|v| serde_json::from_value::<Vec<TestCase>>(v).ok()) {
+            for test in old_tests {
+                results.entry(test.id.clone()).or_insert(test);
+            }
+        }
+
+        if definitions.is_empty() && results.is_empty() {
+            return Ok(());
+        }
+
+        println!("🔄 Migrando arquivos de testes antigos do projeto {}", project_id);
+
+        let tests: Vec<StoredTest> = definitions.into_iter().map(|definition| {
+            let last_result = results.remove(&definition.id)
+                .filter(|r| r.status != "pending")
+                .map(|r| TestResult {
+                    status: r.status,
+                    execution_time: r.execution_time,
+                    legacy_output: r.legacy_output,
+                    new_output: r.new_output,
+                });
+
+            StoredTest { definition, last_result }
+        }).collect();
+
+        for orphan in results.keys() {
+            println!("⚠️ Teste {} sem metadados de execução, não foi migrado (resultado mantido nos arquivos .migrated)", orphan);
+        }
+
+        if suite_path.exists() {
+            fs::copy(&suite_path, project_dir.join("tests.json.migrated"))
+                .map_err(|e| format!("Failed to back up legacy tests file: {}", e))?;
+        }
+
+        // Grava no formato da versão 1; os passos seguintes (paths portáveis) se aplicam depois
+        let suite = TestSuite { schema_version: 1, tests };
+        Storage::write_json_atomic(&suite_path, &suite)?;
+
+        for legacy_path in [&full_tests_path, &ui_tests_path] {
+            if legacy_path.exists() {
+                let mut migrated = legacy_path.clone().into_os_string();
+                migrated.push(".migrated");
+                fs::rename(legacy_path, migrated)
+                    .map_err(|e| format!("Failed to rename legacy tests file: {}", e))?;
+            }
+        }
+
+        println!("✅ {} testes migrados para {:?}", suite.tests.len(), suite_path);
+        Ok(())
+    }
+
+    /// Importa o histórico mantido pelo frontend (`executions-<id>.json`) para
+    /// `projects/<id>/executions/`. Essas execuções não têm os resultados por teste.
+    fn migrate_execution_history(project_id: &str, app_data_dir: &Path, project_dir: &Path) -> Result<(), String> {
+        let history_path = app_data_dir.join(format!("executions-{}.json", project_id));
+
+        let history: Value = match Storage::read_json(&history_path)? {
+            Some(history) => history,
+            None => return Ok(()),
+        };
+
+        let executions = history.get("executions").cloned().unwrap_or(Value::Array(Vec::new()));
+        let summaries: Vec<Value> = serde_json::from_value(executions).unwrap_or_default();
+
+        println!("🔄 Migrando {} execuções do histórico antigo do projeto {}", summaries.len(), project_id);
+
+        for mut summary in summaries {
+            if let Some(object) = summary.as_object_mut() {
+                let executed_at = object.get("executedAt").cloned().unwrap_or(Value::Null);
+                object.entry("finishedAt").or_insert(executed_at);
+                object.entry("mode").or_insert(json!("live"));
+            }
+
+            let summary: ExecutionSummary = match serde_json::from_value(summary) {
+                Ok(summary) => summary,
+                Err(e) => {
+                    println!("⚠️ Execução antiga inválida ignorada: {}", e);
+                    continue;
+                }
+            };
+
+            let path = project_dir.join("executions").join(format!("{}.json", summary.id));
+            Storage::write_json_atomic(&path, &ExecutionRecord {
+                schema_version: CURRENT_SCHEMA_VERSION,
+                summary,
+                tool_versions: Default::default(),
+                missing_tools: Vec::new(),
+                test_results: Vec::new(),
+            })?;
+        }
+
+        let mut migrated = history_path.clone().into_os_string();
+        migrated.push(".migrated");
+        fs::rename(&history_path, migrated)
+            .map_err(|e| format!("Failed to rename legacy executions file: {}", e))
+    }
+
+    /// Versão 2: `workingDirectory`, `sourceFile` e comandos dentro das raízes do projeto
+    /// passam a usar `${LEGACY_ROOT}`/`${NEW_ROOT}` em vez de paths absolutos
+    fn migrate_portable_paths(project: &ProjectInfo, project_dir: &Path) -> Result<(), String> {
+        let suite_path = project_dir.join("tests.json");
+
+        let mut suite: TestSuite = match Storage::read_json(&suite_path)? {
+            Some(suite) => suite,
+            None => return Ok(()),
+        };
+
+        if suite.schema_version >= 2 {
+            return Ok(());
+        }
+
+        println!("🔄 Convertendo paths absolutos dos testes do projeto {} para paths portáveis", project.id);
+
+        for stored in &mut suite.tests {
+            PortablePaths::relativize(&mut stored.definition, project);
+        }
+
+        suite.schema_version = 2;
+        Storage::write_json_atomic(&suite_path, &suite)
+    }
+
+    /// Aplica, em ordem, os passos de migração de um documento até a versão atual
+    fn upgrade_document(path: &Path) -> Result<(), String> {
+        // Documentos já na versão atual (quase todos) não são carregados nem reescritos
+        if let Ok(Some(document)) = Storage::read_json::<DocumentVersion>(path) {
+            if document.schema_version >= CURRENT_SCHEMA_VERSION {
+                return Ok(());
+            }
+        }
+
+        let mut value: Value = match Storage::read_json(path)? {
+            Some(value) => value,
+            None => return Ok(()),
+        };
+
+        let object = match value.as_object_mut() {
+            Some(object) => object,
+            None => return Ok(()),
+        };
+
+        let version = object.get("schemaVersion").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+        if version >= CURRENT_SCHEMA_VERSION {
+            return Ok(());
+        }
+
+        // 0 -> 1: documentos passam a declarar `schemaVersion`
+        // 1 -> 2: só `tests.json` muda de conteúdo (ver `migrate_portable_paths`)
+        object.insert("schemaVersion".to_string(), json!(CURRENT_SCHEMA_VERSION));
+
+        println!("🔄 {:?}: schemaVersion {} -> {}", path, version, CURRENT_SCHEMA_VERSION);
+        Storage::write_json_atomic(path, &value)
+    }
+
+    /// IDs de projetos com dados em app data (diretórios e arquivos `tests-<id>.json`
+    /// e `executions-<id>.json` antigos)
+    fn known_project_ids(app_data_dir: &Path) -> BTreeSet<String> {
+        let mut ids = BTreeSet::new();
+
+        if let Ok(entries) = fs::read_dir(app_data_dir.join("projects")) {
+            for entry in entries.filter_map(|e| e.ok()) {
+                if entry.path().is_dir() {
+                    ids.insert(entry.file_name().to_string_lossy().to_string());
+                }
+            }
+        }
+
+        if let Ok(entries) = fs::read_dir(app_data_dir) {
+            for entry in entries.filter_map(|e| e.ok()) {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let id = name.strip_prefix("tests-")
+                    .or_else(|| name.strip_prefix("executions-"))
+                    .and_then(|n| n.strip_suffix(".json"));
+
+                if let Some(id) = id {
+                    ids.insert(id.to_string());
+                }
+            }
+        }
+
+        ids
+    }
+
+    /// Documentos JSON versionados dentro do diretório de um projeto (o diretório
+    /// de build fica de fora: tem JSONs de ferramentas como o cargo)
+    fn versioned_documents(project_dir: &Path) -> Vec<PathBuf> {
+        walkdir::WalkDir::new(project_dir)
+            .into_iter()
+            .filter_entry(|e| !(e.depth() == 1 && e.file_name() == "build"))
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .map(|e| e.into_path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+            .collect()
+    }
+}
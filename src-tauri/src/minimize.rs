@@ -0,0 +1,404 @@
+use crate::ai::FullTestFromAI;
+use crate::fuzz::{DifferentialOutcome, DifferentialRun};
+use crate::parameters::ParameterExpander;
+use crate::paths::FIXTURES_ROOT;
+use crate::runner::TestRunner;
+use crate::settings::SettingsService;
+use crate::storage::Storage;
+use crate::test_store::TestStore;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Execuções (dos dois lados) permitidas por padrão em uma minimização
+const DEFAULT_MAX_RUNS: usize = 500;
+
+/// Opções da minimização
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MinimizeOptions {
+    /// Limite de execuções; ao atingi-lo fica a menor entrada encontrada até ali (padrão 500)
+    #[serde(rename = "maxRuns", default, skip_serializing_if = "Option::is_none")]
+    pub max_runs: Option<usize>,
+    /// Tempo máximo de cada execução (padrão: timeout do teste ou 10s)
+    #[serde(rename = "timeoutMs", default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+/// Arquivo de fixture (passado como argumento) reduzido
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MinimizedFixture {
+    pub original: String,
+    /// Argumento da cópia reduzida, nas fixtures do projeto (`${FIXTURES_DIR}/<uuid>/<nome>`)
+    pub minimized: String,
+    #[serde(rename = "linesBefore")]
+    pub lines_before: usize,
+    #[serde(rename = "linesAfter")]
+    pub lines_after: usize,
+}
+
+/// Resultado da minimização de um teste
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MinimizeReport {
+    #[serde(rename = "testId")]
+    pub test_id: String,
+    /// ID do teste criado com a entrada mínima
+    #[serde(rename = "savedAs")]
+    pub saved_as: String,
+    pub args: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stdin: Option<String>,
+    #[serde(rename = "argsBefore")]
+    pub args_before: usize,
+    #[serde(rename = "stdinLinesBefore")]
+    pub stdin_lines_before: usize,
+    #[serde(rename = "stdinLinesAfter")]
+    pub stdin_lines_after: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fixtures: Vec<MinimizedFixture>,
+    /// Divergência que se manteve durante a minimização
+    pub differences: Vec<String>,
+    /// Execuções feitas
+    pub runs: usize,
+}
+
+/// Entrada sendo reduzida e o orçamento de execuções
+struct Reduction<'a> {
+    differential: &'a DifferentialRun,
+    /// Tipo da divergência original, que precisa se manter
+    signature: String,
+    args: Vec<String>,
+    stdin: Option<String>,
+    runs: usize,
+    max_runs: usize,
+    /// Resultado da última entrada que manteve a divergência
+    last: DifferentialOutcome,
+}
+
+impl Reduction<'_> {
+    /// A divergência continua a mesma com esta entrada? Sem orçamento, responde não
+    fn still_diverges(&mut self, args: &[String], stdin: Option<&str>) -> Result<bool, String> {
+        if self.runs >= self.max_runs {
+            return Ok(false);
+        }
+        self.runs += 1;
+
+        let outcome = self.differential.run(args, stdin)?;
+        let keeps = outcome.diverges() && outcome.signature() == self.signature;
+        if keeps {
+            self.last = outcome;
+        }
+        Ok(keeps)
+    }
+}
+
+/// Minimização por delta debugging (ddmin): reduz argumentos, linhas do stdin e
+/// linhas dos arquivos de fixture enquanto legado e novo continuam divergindo
+/// do mesmo jeito, e salva a reprodução mínima como novo teste
+pub struct Minimizer;
+
+impl Minimizer {
+    pub async fn run(project_id: &str, test_id: &str, options: MinimizeOptions, app: &tauri::AppHandle) -> Result<MinimizeReport, String> {
+        let differential = DifferentialRun::prepare(project_id, test_id, options.timeout_ms, app).await?;
+        let original = &differential.test;
+        let args = TestRunner::test_inputs(original);
+        let stdin = original.stdin.clone();
+
+        let baseline = differential.run(&args, stdin.as_deref())?;
+        if !baseline.diverges() {
+            return Err(format!("Teste '{}' não diverge entre legado e novo; não há o que minimizar", original.name));
+        }
+
+        println!("✂️ Minimizando {} ({} argumentos, {} linhas de stdin)",
+                 original.name,
+                 args.len(),
+                 stdin.as_deref().map(|s| s.lines().count()).unwrap_or(0));
+
+        let mut reduction = Reduction {
+            differential: &differential,
+            signature: baseline.signature(),
+            args: args.clone(),
+            stdin: stdin.clone(),
+            runs: 1,
+            max_runs: options.max_runs.unwrap_or(DEFAULT_MAX_RUNS),
+            last: baseline,
+        };
+
+        Self::reduce_args(&mut reduction)?;
+        Self::reduce_stdin(&mut reduction)?;
+        let fixtures = Self::reduce_fixtures(&mut reduction)?;
+
+        let differences = reduction.last.differences();
+        let stdin_lines = |stdin: &Option<String>| stdin.as_deref().map(|s| s.lines().count()).unwrap_or(0);
+        let summary = format!(
+            "{} → {} argumentos, {} → {} linhas de stdin{}",
+            args.len(),
+            reduction.args.len(),
+            stdin_lines(&stdin),
+            stdin_lines(&reduction.stdin),
+            fixtures.iter()
+                .map(|f| format!(", {}: {} → {} linhas", f.original, f.lines_before, f.lines_after))
+                .collect::<String>()
+        );
+        println!("✅ {} minimizado em {} execuções: {}", original.name, reduction.runs, summary);
+
+        let existing_ids: HashSet<String> = TestStore::list(project_id, app).await?
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+        let base_id = ParameterExpander::parent_id(&original.id).unwrap_or(&original.id);
+        let test = FullTestFromAI {
+            id: DifferentialRun::free_id(&existing_ids, base_id, "min"),
+            name: format!("{} (minimizado)", original.name),
+            description: format!(
+                "Reprodução mínima de '{}' ({}). Divergência: {}",
+                original.name,
+                summary,
+                differences.join("; ")
+            ),
+            args: Some(reduction.args.clone()),
+            stdin: reduction.stdin.clone(),
+            parameters: None,
+            ..original.clone()
+        };
+
+        let saved = TestStore::create(project_id, test, app).await?;
+        let preview_bytes = SettingsService::load(app)?.preview_bytes;
+        TestStore::record_results(project_id, &[DifferentialRun::test_case(&saved, &reduction.last, preview_bytes)], app).await?;
+
+        Ok(MinimizeReport {
+            test_id: test_id.to_string(),
+            saved_as: saved.id,
+            args_before: args.len(),
+            stdin_lines_before: stdin_lines(&stdin),
+            stdin_lines_after: stdin_lines(&reduction.stdin),
+            args: reduction.args,
+            stdin: reduction.stdin,
+            fixtures,
+            differences,
+            runs: reduction.runs,
+        })
+    }
+
+    fn reduce_args(reduction: &mut Reduction) -> Result<(), String> {
+        let stdin = reduction.stdin.clone();
+        let args = reduction.args.clone();
+
+        reduction.args = Self::ddmin(args, |args| reduction.still_diverges(args, stdin.as_deref()))?;
+        Ok(())
+    }
+
+    fn reduce_stdin(reduction: &mut Reduction) -> Result<(), String> {
+        let stdin = match reduction.stdin.clone() {
+            Some(stdin) if !stdin.is_empty() => stdin,
+            _ => return Ok(()),
+        };
+        let trailing_newline = stdin.ends_with('\n');
+        let join = |lines: &[String]| {
+            let mut text = lines.join("\n");
+            if trailing_newline && !text.is_empty() {
+                text.push('\n');
+            }
+            text
+        };
+
+        let args = reduction.args.clone();
+        let lines: Vec<String> = stdin.lines().map(|l| l.to_string()).collect();
+        let lines = Self::ddmin(lines, |lines| reduction.still_diverges(&args, Some(&join(lines))))?;
+
+        reduction.stdin = Some(join(&lines));
+        Ok(())
+    }
+
+    /// Argumentos que apontam para arquivos (absolutos, ou relativos com o mesmo
+    /// conteúdo nos dois diretórios de trabalho) têm as linhas reduzidas em uma
+    /// cópia nas fixtures do projeto, exportadas junto com ele; o argumento passa a
+    /// ser `${FIXTURES_DIR}/<uuid>/<nome>`. Os fontes do usuário não são tocados.
+    fn reduce_fixtures(reduction: &mut Reduction) -> Result<Vec<MinimizedFixture>, String> {
+        let legacy_dir = PathBuf::from(&reduction.differential.test.legacy_exec.working_directory);
+        let new_dir = PathBuf::from(&reduction.differential.test.new_exec.working_directory);
+        let mut fixtures = Vec::new();
+        let mut attempted = false;
+
+        for i in 0..reduction.args.len() {
+            let arg = reduction.args[i].clone();
+            let path = Path::new(&arg);
+            let copies: Vec<PathBuf> = if path.is_absolute() {
+                vec![path.to_path_buf()]
+            } else {
+                vec![legacy_dir.join(path), new_dir.join(path)]
+            };
+
+            let content = match fs::read(&copies[0]) {
+                Ok(content) if copies[0].is_file() => content,
+                _ => continue,
+            };
+            if copies.iter().skip(1).any(|copy| fs::read(copy).ok().as_ref() != Some(&content)) {
+                println!("⚠️ Fixture {} difere entre legado e novo; não será reduzida", arg);
+                continue;
+            }
+
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            let relative = format!("{}/{}", uuid::Uuid::new_v4(), file_name);
+            let target = reduction.differential.fixtures_dir().join(&relative);
+            if target.exists() {
+                return Err(format!("{} já existe; fixture não será sobrescrita", target.display()));
+            }
+            let write = |lines: &[Vec<u8>]| Storage::write_atomic(&target, &lines.join(&b'\n'));
+            let remove = || if let Some(dir) = target.parent() {
+                let _ = fs::remove_dir_all(dir);
+            };
+
+            attempted = true;
+            let lines: Vec<Vec<u8>> = content.split(|b| *b == b'\n').map(|l| l.to_vec()).collect();
+            let lines_before = lines.len();
+            let minimized_arg = format!("{}/{}", FIXTURES_ROOT, relative);
+            let mut args = reduction.args.clone();
+            args[i] = minimized_arg.clone();
+            let stdin = reduction.stdin.clone();
+
+            let minimized = Self::ddmin(lines, |lines| {
+                write(lines)?;
+                reduction.still_diverges(&args, stdin.as_deref())
+            });
+            let minimized = match minimized {
+                Ok(lines) if lines.len() < lines_before => lines,
+                other => {
+                    remove();
+                    other?;
+                    continue;
+                }
+            };
+
+            write(&minimized)?;
+            println!("✂️ Fixture {} reduzida de {} para {} linhas", arg, lines_before, minimized.len());
+
+            reduction.args[i] = minimized_arg.clone();
+            fixtures.push(MinimizedFixture {
+                original: arg,
+                minimized: minimized_arg,
+                lines_before,
+                lines_after: minimized.len(),
+            });
+        }
+
+        // O resultado guardado tem que ser o da entrada final (com os arquivos finais)
+        if attempted {
+            let (args, stdin) = (reduction.args.clone(), reduction.stdin.clone());
+            let outcome = reduction.differential.run(&args, stdin.as_deref())?;
+            reduction.runs += 1;
+            reduction.last = outcome;
+        }
+
+        Ok(fixtures)
+    }
+
+    /// ddmin de Zeller: tenta cada pedaço e cada complemento; quando nenhum mantém
+    /// a falha, divide em pedaços menores, até pedaços de um item
+    fn ddmin<T: Clone>(
+        items: Vec<T>,
+        mut still_fails: impl FnMut(&[T]) -> Result<bool, String>
+    ) -> Result<Vec<T>, String> {
+        let mut items = items;
+        if items.is_empty() {
+            return Ok(items);
+        }
+        if still_fails(&[])? {
+            return Ok(Vec::new());
+        }
+
+        let mut granularity = 2;
+        while items.len() >= 2 {
+            let chunk_size = items.len().div_ceil(granularity);
+            let chunks: Vec<Vec<T>> = items.chunks(chunk_size).map(|c| c.to_vec()).collect();
+            let mut reduced = false;
+
+            for chunk in &chunks {
+                if still_fails(chunk)? {
+                    items = chunk.clone();
+                    granularity = 2;
+                    reduced = true;
+                    break;
+                }
+            }
+
+            if !reduced && chunks.len() > 2 {
+                for skip in 0..chunks.len() {
+                    let complement: Vec<T> = chunks.iter()
+                        .enumerate()
+                        .filter(|(i, _)| *i != skip)
+                        .flat_map(|(_, c)| c.iter().cloned())
+                        .collect();
+
+                    if still_fails(&complement)? {
+                        items = complement;
+                        granularity = (granularity - 1).max(2);
+                        reduced = true;
+                        break;
+                    }
+                }
+            }
+
+            if !reduced {
+                if granularity >= items.len() {
+                    break;
+                }
+                granularity = (granularity * 2).min(items.len());
+            }
+        }
+
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Falha quando todos os itens da causa estão presentes
+    fn fails_with(cause: &[u32]) -> impl FnMut(&[u32]) -> Result<bool, String> + '_ {
+        move |items| Ok(cause.iter().all(|c| items.contains(c)))
+    }
+
+    #[test]
+    fn ddmin_finds_single_cause() {
+        let result = Minimizer::ddmin((1..=8).collect(), fails_with(&[5])).unwrap();
+        assert_eq!(result, [5]);
+    }
+
+    #[test]
+    fn ddmin_result_is_one_minimal() {
+        let cause = [2, 7, 11];
+        let result = Minimizer::ddmin((1..=16).collect(), fails_with(&cause)).unwrap();
+        assert_eq!(result, cause);
+
+        // Remover qualquer item faz a falha sumir
+        for i in 0..result.len() {
+            let mut smaller = result.clone();
+            smaller.remove(i);
+            assert!(!fails_with(&cause)(&smaller).unwrap());
+        }
+    }
+
+    #[test]
+    fn ddmin_keeps_order_of_remaining_items() {
+        let result = Minimizer::ddmin(vec!["-v", "--x", "a", "b", "--y"], |items: &[&str]| {
+            Ok(items.contains(&"--y") && items.contains(&"-v"))
+        }).unwrap();
+        assert_eq!(result, ["-v", "--y"]);
+    }
+
+    #[test]
+    fn ddmin_returns_empty_when_failure_needs_no_items() {
+        let result = Minimizer::ddmin(vec![1, 2, 3], |_: &[u32]| Ok(true)).unwrap();
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn ddmin_stops_on_errors() {
+        let result = Minimizer::ddmin(vec![1, 2, 3], |items: &[u32]| {
+            if items.len() == 1 { Err("orçamento esgotado".to_string()) } else { Ok(false) }
+        });
+        assert_eq!(result, Err("orçamento esgotado".to_string()));
+    }
+}
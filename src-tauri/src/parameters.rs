@@ -0,0 +1,198 @@
+use crate::ai::{FullTestFromAI, ProjectInfo};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+/// `{{nome}}` dentro das strings de um teste parametrizado
+static PLACEHOLDER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{\{\s*([A-Za-z0-9_.-]+)\s*\}\}").unwrap());
+
+/// Parâmetros de um teste data-driven (`parameters` no teste). Cada linha vira
+/// uma instância do teste, com `{{nome}}` trocado pelo valor em todos os campos.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TestParameters {
+    /// Tabela de valores escrita no próprio teste
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rows: Vec<BTreeMap<String, String>>,
+    /// Arquivo com as linhas: CSV com cabeçalho ou JSON (array de objetos),
+    /// relativo à raiz do legado
+    #[serde(rename = "dataFile", default, skip_serializing_if = "Option::is_none")]
+    pub data_file: Option<String>,
+    /// Pasta de fixtures (relativa à raiz do legado): uma instância por arquivo,
+    /// com `{{file}}` (caminho completo) e `{{fileName}}`
+    #[serde(rename = "filesIn", default, skip_serializing_if = "Option::is_none")]
+    pub files_in: Option<String>,
+}
+
+impl TestParameters {
+    pub fn validate(&self) -> Result<(), String> {
+        let sources = [!self.rows.is_empty(), self.data_file.is_some(), self.files_in.is_some()];
+        if sources.iter().filter(|s| **s).count() != 1 {
+            return Err("Parâmetros do teste devem vir de uma única fonte: rows, dataFile ou filesIn".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+/// Expansão dos testes parametrizados em instâncias individuais
+pub struct ParameterExpander;
+
+impl ParameterExpander {
+    /// Troca cada teste parametrizado pelas suas instâncias, na mesma posição.
+    /// Os que não puderam ser expandidos (arquivo de dados ilegível, pasta vazia...)
+    /// voltam à parte com o erro, para serem registrados sem interromper os demais.
+    pub fn expand_all(project: &ProjectInfo, tests: Vec<FullTestFromAI>) -> (Vec<FullTestFromAI>, Vec<(FullTestFromAI, String)>) {
+        let mut expanded = Vec::new();
+        let mut failed = Vec::new();
+
+        for test in tests {
+            if test.parameters.is_none() {
+                expanded.push(test);
+                continue;
+            }
+
+            match Self::expand(project, &test) {
+                Ok(instances) => expanded.extend(instances),
+                Err(e) => {
+                    println!("⚠️ {}", e);
+                    failed.push((test, e));
+                }
+            }
+        }
+
+        (expanded, failed)
+    }
+
+    /// Instâncias de um teste: IDs `<id>[1]`, `<id>[2]`... e nomes com os valores
+    pub fn expand(project: &ProjectInfo, test: &FullTestFromAI) -> Result<Vec<FullTestFromAI>, String> {
+        let parameters = match &test.parameters {
+            Some(parameters) => parameters,
+            None => return Ok(vec![test.clone()]),
+        };
+
+        let rows = Self::rows(project, parameters)
+            .map_err(|e| format!("Teste '{}': {}", test.name, e))?;
+        if rows.is_empty() {
+            return Err(format!("Teste '{}': nenhuma linha de parâmetros", test.name));
+        }
+
+        println!("🧮 Teste {} expandido em {} instâncias", test.name, rows.len());
+
+        let template = serde_json::to_value(FullTestFromAI { parameters: None, ..test.clone() })
+            .map_err(|e| format!("Erro ao serializar teste '{}': {}", test.name, e))?;
+
+        rows.iter().enumerate().map(|(i, row)| {
+            let mut instance: FullTestFromAI = serde_json::from_value(Self::substitute(&template, row))
+                .map_err(|e| format!("Teste '{}', linha {}: {}", test.name, i + 1, e))?;
+
+            instance.id = format!("{}[{}]", test.id, i + 1);
+            if !test.name.contains("{{") {
+                let values: Vec<String> = row.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+                instance.name = format!("{} [{}]", test.name, values.join(", "));
+            }
+
+            Ok(instance)
+        }).collect()
+    }
+
+    /// ID do teste parametrizado de onde veio uma instância (`abc[3]` → `abc`)
+    pub fn parent_id(instance_id: &str) -> Option<&str> {
+        let (parent, index) = instance_id.strip_suffix(']')?.rsplit_once('[')?;
+        (!index.is_empty() && index.chars().all(|c| c.is_ascii_digit())).then_some(parent)
+    }
+
+    fn rows(project: &ProjectInfo, parameters: &TestParameters) -> Result<Vec<BTreeMap<String, String>>, String> {
+        parameters.validate()?;
+
+        if let Some(data_file) = &parameters.data_file {
+            let path = Self::resolve(project, data_file);
+            let text = fs::read_to_string(&path)
+                .map_err(|e| format!("Erro ao ler arquivo de dados {}: {}", path.display(), e))?;
+
+            return match path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()).as_deref() {
+                Some("json") => Self::json_rows(&text),
+                Some("csv") => Self::csv_rows(&text, b','),
+                Some("tsv") => Self::csv_rows(&text, b'\t'),
+                _ => Err(format!("Arquivo de dados deve ser .csv, .tsv ou .json: {}", path.display())),
+            };
+        }
+
+        if let Some(files_in) = &parameters.files_in {
+            let dir = Self::resolve(project, files_in);
+            let mut files: Vec<PathBuf> = fs::read_dir(&dir)
+                .map_err(|e| format!("Erro ao listar {}: {}", dir.display(), e))?
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.is_file() && !p.file_name().is_some_and(|n| n.to_string_lossy().starts_with('.')))
+                .collect();
+            files.sort();
+
+            return Ok(files.iter().map(|file| BTreeMap::from([
+                ("file".to_string(), file.to_string_lossy().to_string()),
+                ("fileName".to_string(), file.file_name().unwrap_or_default().to_string_lossy().to_string()),
+            ])).collect());
+        }
+
+        Ok(parameters.rows.clone())
+    }
+
+    fn json_rows(text: &str) -> Result<Vec<BTreeMap<String, String>>, String> {
+        let rows: Vec<serde_json::Map<String, Value>> = serde_json::from_str(text)
+            .map_err(|e| format!("Arquivo de dados JSON deve ser um array de objetos: {}", e))?;
+
+        Ok(rows.into_iter().map(|row| row.into_iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    Value::String(s) => s,
+                    Value::Null => String::new(),
+                    other => other.to_string(),
+                };
+                (key, value)
+            })
+            .collect()).collect())
+    }
+
+    fn csv_rows(text: &str, delimiter: u8) -> Result<Vec<BTreeMap<String, String>>, String> {
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .trim(csv::Trim::All)
+            .from_reader(text.as_bytes());
+
+        let headers = reader.headers()
+            .map_err(|e| format!("Cabeçalho do CSV inválido: {}", e))?
+            .clone();
+
+        reader.records()
+            .map(|record| record
+                .map(|r| headers.iter().map(|h| h.to_string()).zip(r.iter().map(|v| v.to_string())).collect())
+                .map_err(|e| format!("CSV inválido: {}", e)))
+            .collect()
+    }
+
+    /// Troca `{{nome}}` em todas as strings do teste; nomes sem valor ficam como estão
+    fn substitute(value: &Value, row: &BTreeMap<String, String>) -> Value {
+        match value {
+            Value::String(text) => Value::String(PLACEHOLDER.replace_all(text, |captures: &Captures| {
+                row.get(&captures[1]).cloned().unwrap_or_else(|| captures[0].to_string())
+            }).into_owned()),
+            Value::Array(items) => Value::Array(items.iter().map(|item| Self::substitute(item, row)).collect()),
+            Value::Object(fields) => Value::Object(fields.iter()
+                .map(|(key, item)| (key.clone(), Self::substitute(item, row)))
+                .collect()),
+            other => other.clone(),
+        }
+    }
+
+    fn resolve(project: &ProjectInfo, path: &str) -> PathBuf {
+        let path = Path::new(path);
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            Path::new(&project.legacy_path).join(path)
+        }
+    }
+}
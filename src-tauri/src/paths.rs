@@ -0,0 +1,115 @@
+use crate::ai::{ExecutionInfo, FullTestFromAI, ProjectInfo};
+use std::fs;
+
+/// Marcador gravado no lugar do diretório raiz do projeto legado
+pub const LEGACY_ROOT: &str = "${LEGACY_ROOT}";
+/// Marcador gravado no lugar do diretório raiz do projeto novo
+pub const NEW_ROOT: &str = "${NEW_ROOT}";
+/// Prefixo dos argumentos que apontam para fixtures guardadas em app data
+/// (`projects/<id>/fixtures`); o runner o troca por `FIXTURES_DIR`
+pub const FIXTURES_ROOT: &str = "${FIXTURES_DIR}";
+
+/// Coloca um valor entre aspas simples para interpolá-lo num comando de shell
+/// (paths de app data têm espaços no macOS, ex.: "Application Support")
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Conversão entre paths absolutos e paths relativos às raízes do projeto.
+/// Os testes são gravados com os marcadores e resolvidos ao serem carregados,
+/// para continuarem válidos quando o repositório muda de lugar.
+pub struct PortablePaths;
+
+impl PortablePaths {
+    /// Troca as raízes absolutas do projeto pelos marcadores
+    pub fn relativize(test: &mut FullTestFromAI, project: &ProjectInfo) {
+        let mut roots = Vec::new();
+
+        for (root, marker) in [(&project.legacy_path, LEGACY_ROOT), (&project.new_path, NEW_ROOT)] {
+            roots.push((root.clone(), marker.to_string()));
+
+            // A IA às vezes devolve o path canônico (symlinks resolvidos)
+            if let Ok(canonical) = fs::canonicalize(root) {
+                roots.push((canonical.to_string_lossy().to_string(), marker.to_string()));
+            }
+        }
+
+        PathRemap::new(roots).apply_to_test(test);
+    }
+
+    /// Troca os marcadores pelas raízes atuais do projeto
+    pub fn resolve(test: &mut FullTestFromAI, project: &ProjectInfo) {
+        PathRemap::new(vec![
+            (LEGACY_ROOT.to_string(), project.legacy_path.clone()),
+            (NEW_ROOT.to_string(), project.new_path.clone()),
+        ]).apply_to_test(test);
+    }
+}
+
+/// Substituição de diretórios raiz (ou marcadores) dentro dos campos de um teste
+pub struct PathRemap {
+    roots: Vec<(String, String)>,
+}
+
+impl PathRemap {
+    pub fn new(roots: Vec<(String, String)>) -> Self {
+        let mut roots: Vec<(String, String)> = roots.into_iter()
+            .map(|(from, to)| (from.trim_end_matches('/').to_string(), to.trim_end_matches('/').to_string()))
+            .filter(|(from, to)| !from.is_empty() && from != to)
+            .collect();
+
+        // Raiz mais longa primeiro, para `/a/app-new` não ser confundida com `/a/app`
+        roots.sort_by_key(|(from, _)| std::cmp::Reverse(from.len()));
+
+        Self { roots }
+    }
+
+    /// Troca cada ocorrência de uma raiz antiga em uma única passada, para que o
+    /// texto já substituído não seja reprocessado
+    pub fn apply(&self, text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+
+        'outer: while !rest.is_empty() {
+            for (from, to) in &self.roots {
+                if let Some(after) = rest.strip_prefix(from.as_str()) {
+                    if after.is_empty() || !after.starts_with(|c: char| c.is_alphanumeric() || c == '_' || c == '-' || c == '.') {
+                        result.push_str(to);
+                        rest = after;
+                        continue 'outer;
+                    }
+                }
+            }
+
+            let ch = rest.chars().next().unwrap();
+            result.push(ch);
+            rest = &rest[ch.len_utf8()..];
+        }
+
+        result
+    }
+
+    pub fn apply_to_test(&self, test: &mut FullTestFromAI) {
+        self.apply_to_exec(&mut test.legacy_exec);
+        self.apply_to_exec(&mut test.new_exec);
+        test.full_code = self.apply(&test.full_code);
+
+        if let Some(args) = &mut test.args {
+            for arg in args.iter_mut() {
+                *arg = self.apply(arg);
+            }
+        }
+
+        if let Some(parameters) = &mut test.parameters {
+            parameters.data_file = parameters.data_file.as_deref().map(|p| self.apply(p));
+            parameters.files_in = parameters.files_in.as_deref().map(|p| self.apply(p));
+        }
+    }
+
+    fn apply_to_exec(&self, exec: &mut ExecutionInfo) {
+        exec.working_directory = self.apply(&exec.working_directory);
+        exec.source_file = self.apply(&exec.source_file);
+        exec.execute_command = self.apply(&exec.execute_command);
+        exec.compile_command = exec.compile_command.as_deref().map(|c| self.apply(c));
+    }
+}
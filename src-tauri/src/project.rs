@@ -1,20 +1,42 @@
-use crate::ai::{ProjectInfo, TestCase, FullTestFromAI};
+use crate::ai::ProjectInfo;
+use crate::api::ApiConfig;
+use crate::build_cache::BuildCache;
+use crate::build_system::BuildDetector;
+use crate::compare::Comparator;
+use crate::encoding::OutputEncoding;
+use crate::entry_point::{EntryPoint, EntryPointService};
+use crate::language::LanguageDetector;
+use crate::paths::shell_quote;
+use crate::storage::{Storage, CURRENT_SCHEMA_VERSION};
+use crate::toolchain::ToolchainConfig;
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::Path;
-use std::process::Command;
-use serde_json;
+use std::path::{Path, PathBuf};
 use tauri::Manager;
 
+/// Conteúdo de `projects.json`
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ProjectsDocument {
+    #[serde(rename = "schemaVersion", default)]
+    pub schema_version: u32,
+    pub projects: Vec<ProjectInfo>,
+}
+
 /// Serviço para gerenciar projetos e ler código
 pub struct ProjectService;
 
 impl ProjectService {
-    /// Carrega informações do projeto salvo pelo frontend
-    pub async fn load_project(project_id: &str, app: &tauri::AppHandle) -> Result<ProjectInfo, String> {
-        let app_dir = app.path().app_data_dir()
-            .map_err(|e| format!("Não foi possível obter diretório de dados: {}", e))?;
+    /// Diretório de dados de um projeto (`<app_data>/projects/<id>`)
+    pub fn project_data_dir(project_id: &str, app: &tauri::AppHandle) -> Result<PathBuf, String> {
+        let app_data_dir = app.path().app_data_dir()
+            .map_err(|e| format!("Failed to get app data directory: {}", e))?;
 
-        let projects_file = app_dir.join("projects.json");
+        Ok(app_data_dir.join("projects").join(project_id))
+    }
+
+    /// Carrega informações de um projeto salvo em `projects.json`
+    pub async fn load_project(project_id: &str, app: &tauri::AppHandle) -> Result<ProjectInfo, String> {
+        let projects_file = Self::projects_file(app)?;
 
         println!("📂 Lendo arquivo de projetos: {:?}", projects_file);
 
@@ -22,13 +44,10 @@ impl ProjectService {
             return Err(format!("Arquivo de projetos não encontrado: {:?}", projects_file));
         }
 
-        let content = fs::read_to_string(&projects_file)
-            .map_err(|e| format!("Erro ao ler arquivo de projetos: {}", e))?;
-
-        let projects: Vec<ProjectInfo> = serde_json::from_str(&content)
-            .map_err(|e| format!("Erro ao parsear JSON dos projetos: {}", e))?;
+        let document: ProjectsDocument = Storage::read_json(&projects_file)?
+            .ok_or(format!("Arquivo de projetos não encontrado: {:?}", projects_file))?;
 
-        let project = projects.into_iter()
+        let project = document.projects.into_iter()
             .find(|p| p.id == project_id)
             .ok_or(format!("Projeto com ID '{}' não encontrado", project_id))?;
 
@@ -46,206 +65,381 @@ impl ProjectService {
         Ok(project)
     }
 
-    /// Lê todo o código-fonte de um diretório
-    pub async fn read_project_code(project_path: &str) -> Result<String, String> {
-        println!("📚 Analisando código em: {}", project_path);
+    /// Lista os projetos cadastrados
+    pub fn list_projects(app: &tauri::AppHandle) -> Result<Vec<ProjectInfo>, String> {
+        Ok(Self::load_projects_document(app)?.projects)
+    }
 
-        // Extensões de arquivo que vamos ler
-        let code_extensions = vec!["py", "js", "ts", "sh", "bash", "rb", "php", "java", "cpp", "c", "rs"];
+    /// Cadastra um novo projeto após validar nome, tipo e diretórios
+    pub fn create_project(
+        name: &str,
+        legacy_path: &str,
+        new_path: &str,
+        project_type: &str,
+        app: &tauri::AppHandle
+    ) -> Result<ProjectInfo, String> {
+        let project = ProjectInfo {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.trim().to_string(),
+            legacy_path: legacy_path.to_string(),
+            new_path: new_path.to_string(),
+            project_type: project_type.to_string(),
+            last_opened: Some(chrono::Utc::now().to_rfc3339()),
+            legacy_toolchain: None,
+            new_toolchain: None,
+            legacy_entry_points: Vec::new(),
+            new_entry_points: Vec::new(),
+            api_config: None,
+            comparator: None,
+            legacy_encoding: None,
+            new_encoding: None,
+        };
 
-        let mut all_code = String::new();
-        let mut file_count = 0;
+        Self::validate_project_info(&project)?;
 
-        // Lê todos os arquivos recursivamente
-        for entry in walkdir::WalkDir::new(project_path)
-            .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(|e| e.file_type().is_file()) {
-
-            let path = entry.path();
-
-            // Verifica extensão
-            if let Some(extension) = path.extension() {
-                if let Some(ext_str) = extension.to_str() {
-                    if code_extensions.contains(&ext_str) {
-                        match fs::read_to_string(path) {
-                            Ok(content) => {
-                                // Adiciona header com info do arquivo
-                                all_code.push_str(&format!(
-                                    "\n// === ARQUIVO: {} ===\n",
-                                    path.display()
-                                ));
-                                all_code.push_str(&content);
-                                all_code.push('\n');
-                                file_count += 1;
-                            },
-                            Err(e) => {
-                                println!("⚠️ Erro lendo arquivo {}: {}", path.display(), e);
-                            }
-                        }
-                    }
-                }
+        let _lock = Storage::lock_projects(app)?;
+        let mut document = Self::load_projects_document(app)?;
+        document.projects.push(project.clone());
+        Self::save_projects_document(&document, app)?;
+
+        println!("✅ Projeto criado: {} ({})", project.name, project.id);
+        Ok(project)
+    }
+
+    /// Substitui os dados de um projeto existente
+    pub fn update_project(project: ProjectInfo, app: &tauri::AppHandle) -> Result<ProjectInfo, String> {
+        Self::validate_project_info(&project)?;
+
+        let _lock = Storage::lock_projects(app)?;
+        let mut document = Self::load_projects_document(app)?;
+        let existing = document.projects.iter_mut()
+            .find(|p| p.id == project.id)
+            .ok_or(format!("Projeto com ID '{}' não encontrado", project.id))?;
+
+        *existing = project.clone();
+        Self::save_projects_document(&document, app)?;
+
+        println!("✅ Projeto atualizado: {} ({})", project.name, project.id);
+        Ok(project)
+    }
+
+    /// Renomeia um projeto
+    pub fn rename_project(project_id: &str, name: &str, app: &tauri::AppHandle) -> Result<ProjectInfo, String> {
+        if name.trim().is_empty() {
+            return Err("O nome do projeto não pode ser vazio".to_string());
+        }
+
+        Self::modify_project(project_id, app, |project| project.name = name.trim().to_string())
+    }
+
+    /// Define (ou remove, com `None`) o toolchain fixado de um lado ("legacy" ou "new")
+    pub fn set_toolchain(
+        project_id: &str,
+        side: &str,
+        toolchain: Option<ToolchainConfig>,
+        app: &tauri::AppHandle
+    ) -> Result<ProjectInfo, String> {
+        let label = match side {
+            "legacy" => "legado",
+            "new" => "novo",
+            _ => return Err(format!("Lado inválido: {} (use legacy ou new)", side)),
+        };
+
+        let toolchain = toolchain.filter(|t| !t.is_empty());
+        if let Some(toolchain) = &toolchain {
+            toolchain.validate(label)?;
+        }
+
+        Self::modify_project(project_id, app, |project| {
+            if side == "legacy" {
+                project.legacy_toolchain = toolchain;
+            } else {
+                project.new_toolchain = toolchain;
             }
+        })
+    }
+
+    /// Fixa os pontos de entrada de um lado ("legacy" ou "new"); a ordem é a de
+    /// preferência e o primeiro é o usado quando o teste não escolhe um
+    pub fn set_entry_points(
+        project_id: &str,
+        side: &str,
+        entry_points: Vec<EntryPoint>,
+        app: &tauri::AppHandle
+    ) -> Result<ProjectInfo, String> {
+        if side != "legacy" && side != "new" {
+            return Err(format!("Lado inválido: {} (use legacy ou new)", side));
         }
 
-        if all_code.is_empty() {
-            return Err(format!("Nenhum arquivo de código encontrado em: {}", project_path));
+        let entry_points: Vec<EntryPoint> = entry_points.into_iter()
+            .map(|e| EntryPoint { name: e.name.trim().to_string(), path: e.path.replace('\\', "/") })
+            .collect();
+
+        let project = Self::list_projects(app)?.into_iter()
+            .find(|p| p.id == project_id)
+            .ok_or(format!("Projeto com ID '{}' não encontrado", project_id))?;
+        let (root, label) = if side == "legacy" { (&project.legacy_path, "legado") } else { (&project.new_path, "novo") };
+        EntryPointService::validate(root, &entry_points, label)?;
+
+        Self::modify_project(project_id, app, |project| {
+            if side == "legacy" {
+                project.legacy_entry_points = entry_points;
+            } else {
+                project.new_entry_points = entry_points;
+            }
+        })
+    }
+
+    /// Define (ou remove, com `None`) como subir os servidores de um projeto do tipo API
+    pub fn set_api_config(
+        project_id: &str,
+        config: Option<ApiConfig>,
+        app: &tauri::AppHandle
+    ) -> Result<ProjectInfo, String> {
+        if let Some(config) = &config {
+            let project = Self::list_projects(app)?.into_iter()
+                .find(|p| p.id == project_id)
+                .ok_or(format!("Projeto com ID '{}' não encontrado", project_id))?;
+            config.validate(&project.legacy_path, &project.new_path)?;
         }
 
-        println!("✅ Lidos {} arquivos de código", file_count);
+        Self::modify_project(project_id, app, |project| project.api_config = config)
+    }
 
-        Ok(all_code)
+    /// Define a codificação das saídas de um lado ("legacy" ou "new"); `None` ou
+    /// "auto" detecta pelos bytes
+    pub fn set_encoding(
+        project_id: &str,
+        side: &str,
+        encoding: Option<String>,
+        app: &tauri::AppHandle
+    ) -> Result<ProjectInfo, String> {
+        if side != "legacy" && side != "new" {
+            return Err(format!("Lado inválido: {} (use legacy ou new)", side));
+        }
+
+        let encoding = encoding.map(|e| e.trim().to_string()).filter(|e| !e.is_empty());
+        if let Some(encoding) = &encoding {
+            OutputEncoding::validate(encoding)?;
+        }
+
+        Self::modify_project(project_id, app, |project| {
+            if side == "legacy" {
+                project.legacy_encoding = encoding;
+            } else {
+                project.new_encoding = encoding;
+            }
+        })
     }
 
-    /// Lê código de ambos os projetos (legado e novo)
-    pub async fn read_both_project_codes(project: &ProjectInfo) -> Result<(String, String), String> {
-        println!("📖 Lendo código dos dois projetos...");
+    /// Define (ou remove, com `None`) o comparador padrão dos testes do projeto
+    pub fn set_comparator(
+        project_id: &str,
+        comparator: Option<Comparator>,
+        app: &tauri::AppHandle
+    ) -> Result<ProjectInfo, String> {
+        if let Some(comparator) = &comparator {
+            comparator.validate()?;
+        }
 
-        let legacy_code = Self::read_project_code(&project.legacy_path).await?;
-        let new_code = Self::read_project_code(&project.new_path).await?;
+        Self::modify_project(project_id, app, |project| project.comparator = comparator)
+    }
 
-        println!("✅ Código legado: {} caracteres", legacy_code.len());
-        println!("✅ Código novo: {} caracteres", new_code.len());
+    /// Marca o projeto como aberto agora
+    pub fn touch_project(project_id: &str, app: &tauri::AppHandle) -> Result<ProjectInfo, String> {
+        Self::modify_project(project_id, app, |project| {
+            project.last_opened = Some(chrono::Utc::now().to_rfc3339());
+        })
+    }
 
-        Ok((legacy_code, new_code))
+    /// Remove o projeto de `projects.json` e apaga seus dados em app data
+    pub fn delete_project(project_id: &str, app: &tauri::AppHandle) -> Result<(), String> {
+        {
+            let _lock = Storage::lock_projects(app)?;
+            let mut document = Self::load_projects_document(app)?;
+            let count_before = document.projects.len();
+
+            document.projects.retain(|p| p.id != project_id);
+
+            if document.projects.len() == count_before {
+                return Err(format!("Projeto com ID '{}' não encontrado", project_id));
+            }
+
+            Self::save_projects_document(&document, app)?;
+        }
+
+        let project_dir = Self::project_data_dir(project_id, app)?;
+        if project_dir.exists() {
+            fs::remove_dir_all(&project_dir)
+                .map_err(|e| format!("Failed to remove project data directory: {}", e))?;
+        }
+
+        // Arquivos do formato antigo que ficavam na raiz de app data
+        let app_data_dir = app.path().app_data_dir()
+            .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+        for prefix in ["tests", "executions"] {
+            for suffix in ["json", "json.migrated"] {
+                let _ = fs::remove_file(app_data_dir.join(format!("{}-{}.{}", prefix, project_id, suffix)));
+            }
+        }
+
+        println!("🗑️ Projeto removido: {}", project_id);
+        Ok(())
     }
 
-    /// Valida se os diretórios do projeto são válidos
-    pub fn validate_project_paths(project: &ProjectInfo) -> Result<(), String> {
-        // Valida se paths existem
-        if !Path::new(&project.legacy_path).exists() {
-            return Err(format!("Diretório legado não encontrado: {}", project.legacy_path));
+    /// Valida nome, tipo e diretórios de um projeto antes de salvá-lo
+    pub fn validate_project_info(project: &ProjectInfo) -> Result<(), String> {
+        if project.name.trim().is_empty() {
+            return Err("O nome do projeto não pode ser vazio".to_string());
         }
 
-        if !Path::new(&project.new_path).exists() {
-            return Err(format!("Diretório novo não encontrado: {}", project.new_path));
+        if !["API", "Web", "Terminal"].contains(&project.project_type.as_str()) {
+            return Err(format!("Tipo de projeto inválido: {} (use API, Web ou Terminal)", project.project_type));
         }
 
-        // Valida se são diretórios (não arquivos)
-        if !Path::new(&project.legacy_path).is_dir() {
-            return Err(format!("Path legado não é um diretório: {}", project.legacy_path));
+        Self::validate_project_paths(project)?;
+
+        if let Some(toolchain) = &project.legacy_toolchain {
+            toolchain.validate("legado")?;
+        }
+        if let Some(toolchain) = &project.new_toolchain {
+            toolchain.validate("novo")?;
         }
 
-        if !Path::new(&project.new_path).is_dir() {
-            return Err(format!("Path novo não é um diretório: {}", project.new_path));
+        EntryPointService::validate(&project.legacy_path, &project.legacy_entry_points, "legado")?;
+        EntryPointService::validate(&project.new_path, &project.new_entry_points, "novo")?;
+
+        if let Some(api_config) = &project.api_config {
+            api_config.validate(&project.legacy_path, &project.new_path)?;
+        }
+
+        if let Some(comparator) = &project.comparator {
+            comparator.validate()?;
+        }
+
+        for encoding in project.legacy_encoding.iter().chain(project.new_encoding.iter()) {
+            OutputEncoding::validate(encoding)?;
+        }
+
+        let legacy = fs::canonicalize(&project.legacy_path)
+            .map_err(|e| format!("Erro ao resolver path legado: {}", e))?;
+        let new = fs::canonicalize(&project.new_path)
+            .map_err(|e| format!("Erro ao resolver path novo: {}", e))?;
+
+        if legacy == new {
+            return Err("Os diretórios legado e novo devem ser diferentes".to_string());
         }
 
-        println!("✅ Paths do projeto validados com sucesso");
         Ok(())
     }
 
-    /// Identifica o arquivo executável principal usando heurísticas
-    pub fn find_main_executable(project_path: &str) -> Result<String, String> {
-        println!("🔍 Procurando executável principal em: {}", project_path);
+    fn modify_project(
+        project_id: &str,
+        app: &tauri::AppHandle,
+        change: impl FnOnce(&mut ProjectInfo)
+    ) -> Result<ProjectInfo, String> {
+        let _lock = Storage::lock_projects(app)?;
+        let mut document = Self::load_projects_document(app)?;
+        let project = document.projects.iter_mut()
+            .find(|p| p.id == project_id)
+            .ok_or(format!("Projeto com ID '{}' não encontrado", project_id))?;
 
-        // Extensões que consideramos para projetos de terminal
-        let code_extensions = vec!["py", "js", "ts", "sh", "bash", "rb", "php", "java", "cpp", "c", "rs"];
+        change(project);
+        let project = project.clone();
+        Self::save_projects_document(&document, app)?;
 
-        // Nomes de arquivo comuns (ordem de prioridade)
-        let priority_names = vec![
-            "main", "app", "index", "start", "run", "cli", "server", "client",
-            "entry", "init", "bootstrap", "program", "application"
-        ];
+        Ok(project)
+    }
 
-        let mut candidates = Vec::new();
+    fn projects_file(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+        let app_dir = app.path().app_data_dir()
+            .map_err(|e| format!("Não foi possível obter diretório de dados: {}", e))?;
 
-        // Lê todos os arquivos recursivamente
-        for entry in walkdir::WalkDir::new(project_path)
-            .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(|e| e.file_type().is_file()) {
+        Ok(app_dir.join("projects.json"))
+    }
 
-            let path = entry.path();
+    fn load_projects_document(app: &tauri::AppHandle) -> Result<ProjectsDocument, String> {
+        Ok(Storage::read_json(&Self::projects_file(app)?)?.unwrap_or_default())
+    }
 
-            // Verifica extensão
-            if let Some(extension) = path.extension() {
-                if let Some(ext_str) = extension.to_str() {
-                    if code_extensions.contains(&ext_str) {
-                        let file_name = path.file_name()
-                            .and_then(|n| n.to_str())
-                            .unwrap_or("");
+    fn save_projects_document(document: &ProjectsDocument, app: &tauri::AppHandle) -> Result<(), String> {
+        let mut document = document.clone();
+        document.schema_version = CURRENT_SCHEMA_VERSION;
 
-                        let score = Self::calculate_executable_score(file_name, &priority_names);
-                        candidates.push((score, path.to_path_buf(), file_name.to_string()));
+        Storage::write_json_atomic(&Self::projects_file(app)?, &document)
+    }
 
-                        // Se encontrou arquivo com nome exato de alta prioridade, retorna imediatamente
-                        if score >= 90 {
-                            println!("✅ Executável principal encontrado (alta prioridade): {}", path.display());
-                            return Ok(path.to_string_lossy().to_string());
-                        }
-                    }
+    /// Lê todo o código-fonte de um diretório
+    pub async fn read_project_code(project_path: &str) -> Result<String, String> {
+        println!("📚 Analisando código em: {}", project_path);
+
+        let mut all_code = String::new();
+        let mut file_count = 0;
+
+        for (path, language) in LanguageDetector::source_files(Path::new(project_path)) {
+            // Fontes legados nem sempre são UTF-8 (ex.: COBOL em Latin-1)
+            match fs::read(&path) {
+                Ok(data) => {
+                    // Adiciona header com info do arquivo
+                    all_code.push_str(&format!(
+                        "\n// === ARQUIVO: {} ({}) ===\n",
+                        path.display(),
+                        language.name
+                    ));
+                    all_code.push_str(&String::from_utf8_lossy(&data));
+                    all_code.push('\n');
+                    file_count += 1;
+                },
+                Err(e) => {
+                    println!("⚠️ Erro lendo arquivo {}: {}", path.display(), e);
                 }
             }
         }
 
-        // Ordena por score (maior primeiro)
-        candidates.sort_by(|a, b| b.0.cmp(&a.0));
-
-        if candidates.is_empty() {
+        if all_code.is_empty() {
             return Err(format!("Nenhum arquivo de código encontrado em: {}", project_path));
         }
 
-        // Retorna o candidato com maior score
-        let best_candidate = &candidates[0];
-        println!("✅ Executável principal selecionado: {} (score: {})",
-                best_candidate.1.display(), best_candidate.0);
+        println!("✅ Lidos {} arquivos de código", file_count);
 
-        Ok(best_candidate.1.to_string_lossy().to_string())
+        Ok(all_code)
     }
 
-    /// Calcula score para arquivo executável baseado em heurísticas
-    fn calculate_executable_score(file_name: &str, priority_names: &[&str]) -> i32 {
-        let mut score = 0;
+    /// Lê código de ambos os projetos (legado e novo)
+    pub async fn read_both_project_codes(project: &ProjectInfo) -> Result<(String, String), String> {
+        println!("📖 Lendo código dos dois projetos...");
 
-        // Converte para minúsculas para comparação
-        let name_lower = file_name.to_lowercase();
+        let legacy_code = Self::read_project_code(&project.legacy_path).await?;
+        let new_code = Self::read_project_code(&project.new_path).await?;
 
-        // Remove extensão para análise do nome base
-        let name_without_ext = if let Some(dot_pos) = name_lower.rfind('.') {
-            &name_lower[..dot_pos]
-        } else {
-            &name_lower
-        };
+        println!("✅ Código legado: {} caracteres", legacy_code.len());
+        println!("✅ Código novo: {} caracteres", new_code.len());
 
-        // Verifica se o nome é exatamente um dos prioritários
-        for (i, priority_name) in priority_names.iter().enumerate() {
-            if name_without_ext == *priority_name {
-                // Maior score para nomes no início da lista
-                score += 100 - (i * 5);
-                break;
-            }
-        }
+        Ok((legacy_code, new_code))
+    }
 
-        // Bônus se contém palavras-chave de executáveis
-        if name_lower.contains("main") || name_lower.contains("start") || name_lower.contains("run") {
-            score += 50;
-        }
-        if name_lower.contains("app") || name_lower.contains("cli") || name_lower.contains("server") {
-            score += 40;
-        }
-        if name_lower.contains("index") || name_lower.contains("entry") || name_lower.contains("init") {
-            score += 30;
+    /// Valida se os diretórios do projeto são válidos
+    pub fn validate_project_paths(project: &ProjectInfo) -> Result<(), String> {
+        // Valida se paths existem
+        if !Path::new(&project.legacy_path).exists() {
+            return Err(format!("Diretório legado não encontrado: {}", project.legacy_path));
         }
 
-        // Bônus por estar em diretório raiz (provavelmente o ponto de entrada)
-        if !file_name.contains('/') && !file_name.contains('\\') {
-            score += 20;
+        if !Path::new(&project.new_path).exists() {
+            return Err(format!("Diretório novo não encontrado: {}", project.new_path));
         }
 
-        // Penalidade por ser arquivo de configuração ou testes
-        if name_lower.contains("config") || name_lower.contains("test") ||
-           name_lower.contains("spec") || name_lower.starts_with('.') {
-            score -= 30;
+        // Valida se são diretórios (não arquivos)
+        if !Path::new(&project.legacy_path).is_dir() {
+            return Err(format!("Path legado não é um diretório: {}", project.legacy_path));
         }
 
-        // Bônus por extensões de linguagens comuns para CLI
-        if name_lower.ends_with(".py") { score += 10; }
-        if name_lower.ends_with(".js") || name_lower.ends_with(".ts") { score += 8; }
-        if name_lower.ends_with(".sh") || name_lower.ends_with(".bash") { score += 15; }
-        if name_lower.ends_with(".rs") { score += 12; }
-        if name_lower.ends_with(".go") { score += 12; }
+        if !Path::new(&project.new_path).is_dir() {
+            return Err(format!("Path novo não é um diretório: {}", project.new_path));
+        }
 
-        score.max(0) as i32
+        println!("✅ Paths do projeto validados com sucesso");
+        Ok(())
     }
 
     /// Verifica se arquivo tem shebang (indicando que é executável)
@@ -259,12 +453,13 @@ impl ProjectService {
         }
     }
 
-    /// Identifica os executáveis principais para ambos os projetos
+    /// Identifica os executáveis principais para ambos os projetos (o primeiro
+    /// ponto de entrada fixado de cada lado, ou o melhor candidato encontrado)
     pub fn find_both_executables(project: &ProjectInfo) -> Result<(String, String), String> {
         println!("🎯 Procurando executáveis nos dois projetos...");
 
-        let legacy_executable = Self::find_main_executable(&project.legacy_path)?;
-        let new_executable = Self::find_main_executable(&project.new_path)?;
+        let legacy_executable = EntryPointService::resolve(project, "legacy", None)?.to_string_lossy().to_string();
+        let new_executable = EntryPointService::resolve(project, "new", None)?.to_string_lossy().to_string();
 
         println!("✅ Executáveis encontrados:");
         println!("   Legado: {}", legacy_executable);
@@ -273,8 +468,9 @@ impl ProjectService {
         Ok((legacy_executable, new_executable))
     }
 
-    /// Compila um programa se necessário e retorna o caminho do executável
-    pub fn ensure_executable(source_path: &str) -> Result<String, String> {
+    /// Compila um programa se necessário e retorna o caminho do executável.
+    /// A saída da compilação vai para o diretório de build do projeto em app data.
+    pub fn ensure_executable(source_path: &str, cache: &BuildCache) -> Result<String, String> {
         let path = Path::new(source_path);
 
         if !path.exists() {
@@ -293,8 +489,8 @@ impl ProjectService {
             .unwrap_or("");
 
         match extension {
-            "c" => Self::compile_c(source_path),
-            "cpp" | "cc" | "cxx" => Self::compile_cpp(source_path),
+            "c" => Self::compile_c(source_path, cache),
+            "cpp" | "cc" | "cxx" => Self::compile_cpp(source_path, cache),
             "py" => {
                 println!("✅ Python não precisa de compilação: {}", source_path);
                 Ok(source_path.to_string())
@@ -303,9 +499,9 @@ impl ProjectService {
                 println!("✅ JavaScript/TypeScript não precisa de compilação para execução: {}", source_path);
                 Ok(source_path.to_string())
             },
-            "rs" => Self::compile_rust(source_path),
-            "go" => Self::compile_go(source_path),
-            "java" => Self::compile_java(source_path),
+            "rs" => Self::compile_rust(source_path, cache),
+            "go" => Self::compile_go(source_path, cache),
+            "java" => Self::compile_java(source_path, cache),
             _ => {
                 println!("⚠️ Extensão '{}' desconhecida, tentando executar diretamente: {}", extension, source_path);
                 Ok(source_path.to_string())
@@ -314,7 +510,7 @@ impl ProjectService {
     }
 
     /// Verifica se um arquivo já é executável
-    fn is_executable(file_path: &str) -> bool {
+    pub fn is_executable(file_path: &str) -> bool {
         #[cfg(unix)]
         {
             use std::os::unix::fs::PermissionsExt;
@@ -333,18 +529,16 @@ impl ProjectService {
     }
 
     /// Compila arquivo C usando gcc
-    fn compile_c(source_path: &str) -> Result<String, String> {
-        let path = Path::new(source_path);
-        let source_dir = path.parent().unwrap_or_else(|| Path::new("."));
-        let source_name = path.file_stem()
-            .and_then(|name| name.to_str())
-            .ok_or("Nome do arquivo inválido")?;
+    fn compile_c(source_path: &str, cache: &BuildCache) -> Result<String, String> {
+        if let Some(executable) = cache.reusable(source_path) {
+            return Ok(executable);
+        }
 
-        let executable_path = source_dir.join(source_name);
+        let executable_path = cache.artifact_path(source_path);
 
         println!("🔨 Compilando arquivo C: {} -> {}", source_path, executable_path.display());
 
-        let output = Command::new("gcc")
+        let output = cache.command("gcc")
             .arg("-o")
             .arg(&executable_path)
             .arg(source_path)
@@ -353,7 +547,9 @@ impl ProjectService {
 
         if output.status.success() {
             println!("✅ Compilação C bem-sucedida: {}", executable_path.display());
-            Ok(executable_path.to_string_lossy().to_string())
+            let executable = executable_path.to_string_lossy().to_string();
+            cache.record(source_path, &executable, true)?;
+            Ok(executable)
         } else {
             let stderr = String::from_utf8_lossy(&output.stderr);
             Err(format!("Erro na compilação C: {}", stderr))
@@ -361,18 +557,16 @@ impl ProjectService {
     }
 
     /// Compila arquivo C++ usando g++
-    fn compile_cpp(source_path: &str) -> Result<String, String> {
-        let path = Path::new(source_path);
-        let source_dir = path.parent().unwrap_or_else(|| Path::new("."));
-        let source_name = path.file_stem()
-            .and_then(|name| name.to_str())
-            .ok_or("Nome do arquivo inválido")?;
+    fn compile_cpp(source_path: &str, cache: &BuildCache) -> Result<String, String> {
+        if let Some(executable) = cache.reusable(source_path) {
+            return Ok(executable);
+        }
 
-        let executable_path = source_dir.join(source_name);
+        let executable_path = cache.artifact_path(source_path);
 
         println!("🔨 Compilando arquivo C++: {} -> {}", source_path, executable_path.display());
 
-        let output = Command::new("g++")
+        let output = cache.command("g++")
             .arg("-o")
             .arg(&executable_path)
             .arg(source_path)
@@ -381,7 +575,9 @@ impl ProjectService {
 
         if output.status.success() {
             println!("✅ Compilação C++ bem-sucedida: {}", executable_path.display());
-            Ok(executable_path.to_string_lossy().to_string())
+            let executable = executable_path.to_string_lossy().to_string();
+            cache.record(source_path, &executable, true)?;
+            Ok(executable)
         } else {
             let stderr = String::from_utf8_lossy(&output.stderr);
             Err(format!("Erro na compilação C++: {}", stderr))
@@ -389,43 +585,50 @@ impl ProjectService {
     }
 
     /// Compila arquivo Rust usando cargo ou rustc
-    fn compile_rust(source_path: &str) -> Result<String, String> {
+    fn compile_rust(source_path: &str, cache: &BuildCache) -> Result<String, String> {
         let path = Path::new(source_path);
-
-        // Se houver Cargo.toml no diretório, usa cargo build
         let source_dir = path.parent().unwrap_or_else(|| Path::new("."));
-        let cargo_toml = source_dir.join("Cargo.toml");
 
-        if cargo_toml.exists() {
-            println!("🦀 Compilando projeto Rust com cargo em: {}", source_dir.display());
+        // Se o arquivo pertence a um pacote Cargo (ex.: src/main.rs), usa cargo build
+        let crate_dir = source_dir.ancestors().find(|dir| dir.join("Cargo.toml").is_file());
+
+        if let Some(crate_dir) = crate_dir {
+            println!("🦀 Compilando projeto Rust com cargo em: {}", crate_dir.display());
+
+            let binary_name = BuildDetector::cargo_binary_for(crate_dir, path)?;
+
+            // Cargo recompila de forma incremental; só o target dir muda de lugar
+            let target_dir = cache.dir().join("cargo-target");
 
-            let output = Command::new("cargo")
-                .args(&["build", "--release"])
-                .current_dir(source_dir)
+            let output = cache.command("cargo")
+                .args(["build", "--release", "--bin", &binary_name])
+                .arg("--target-dir")
+                .arg(&target_dir)
+                .current_dir(crate_dir)
                 .output()
                 .map_err(|e| format!("Erro ao executar cargo: {}", e))?;
 
             if output.status.success() {
-                let executable_path = source_dir.join("target").join("release").join(
-                    source_dir.file_name().unwrap_or_else(|| std::ffi::OsStr::new("program"))
-                );
+                let executable_path = target_dir.join("release").join(&binary_name);
                 println!("✅ Compilação Rust com cargo bem-sucedida: {}", executable_path.display());
-                Ok(executable_path.to_string_lossy().to_string())
+                let executable = executable_path.to_string_lossy().to_string();
+                cache.record(source_path, &executable, false)?;
+                Ok(executable)
             } else {
                 let stderr = String::from_utf8_lossy(&output.stderr);
                 Err(format!("Erro na compilação Rust com cargo: {}", stderr))
             }
         } else {
             // Compila arquivo único com rustc
-            let source_name = path.file_stem()
-                .and_then(|name| name.to_str())
-                .ok_or("Nome do arquivo inválido")?;
+            if let Some(executable) = cache.reusable(source_path) {
+                return Ok(executable);
+            }
 
-            let executable_path = source_dir.join(source_name);
+            let executable_path = cache.artifact_path(source_path);
 
             println!("🦀 Compilando arquivo Rust único: {} -> {}", source_path, executable_path.display());
 
-            let output = Command::new("rustc")
+            let output = cache.command("rustc")
                 .arg("-o")
                 .arg(&executable_path)
                 .arg(source_path)
@@ -434,7 +637,9 @@ impl ProjectService {
 
             if output.status.success() {
                 println!("✅ Compilação Rust bem-sucedida: {}", executable_path.display());
-                Ok(executable_path.to_string_lossy().to_string())
+                let executable = executable_path.to_string_lossy().to_string();
+                cache.record(source_path, &executable, true)?;
+                Ok(executable)
             } else {
                 let stderr = String::from_utf8_lossy(&output.stderr);
                 Err(format!("Erro na compilação Rust: {}", stderr))
@@ -443,18 +648,16 @@ impl ProjectService {
     }
 
     /// Compila arquivo Go
-    fn compile_go(source_path: &str) -> Result<String, String> {
-        let path = Path::new(source_path);
-        let source_dir = path.parent().unwrap_or_else(|| Path::new("."));
-        let source_name = path.file_stem()
-            .and_then(|name| name.to_str())
-            .ok_or("Nome do arquivo inválido")?;
+    fn compile_go(source_path: &str, cache: &BuildCache) -> Result<String, String> {
+        if let Some(executable) = cache.reusable(source_path) {
+            return Ok(executable);
+        }
 
-        let executable_path = source_dir.join(source_name);
+        let executable_path = cache.artifact_path(source_path);
 
         println!("🐹 Compilando arquivo Go: {} -> {}", source_path, executable_path.display());
 
-        let output = Command::new("go")
+        let output = cache.command("go")
             .arg("build")
             .arg("-o")
             .arg(&executable_path)
@@ -464,7 +667,9 @@ impl ProjectService {
 
         if output.status.success() {
             println!("✅ Compilação Go bem-sucedida: {}", executable_path.display());
-            Ok(executable_path.to_string_lossy().to_string())
+            let executable = executable_path.to_string_lossy().to_string();
+            cache.record(source_path, &executable, true)?;
+            Ok(executable)
         } else {
             let stderr = String::from_utf8_lossy(&output.stderr);
             Err(format!("Erro na compilação Go: {}", stderr))
@@ -472,13 +677,26 @@ impl ProjectService {
     }
 
     /// Compila arquivo Java
-    fn compile_java(source_path: &str) -> Result<String, String> {
+    fn compile_java(source_path: &str, cache: &BuildCache) -> Result<String, String> {
         let path = Path::new(source_path);
         let source_dir = path.parent().unwrap_or_else(|| Path::new("."));
+        let class_name = path.file_stem()
+            .and_then(|name| name.to_str())
+            .ok_or("Nome do arquivo inválido")?;
+
+        // Para Java, retornamos um launcher que executa a classe, para o programa
+        // continuar sendo um único caminho (`"${ENTRY_POINT}"`)
+        if let Some(classes_dir) = cache.reusable(source_path) {
+            return Self::java_launcher(Path::new(&classes_dir), class_name);
+        }
+
+        let classes_dir = cache.artifact_path(source_path);
 
-        println!("☕ Compilando arquivo Java: {}", source_path);
+        println!("☕ Compilando arquivo Java: {} -> {}", source_path, classes_dir.display());
 
-        let output = Command::new("javac")
+        let output = cache.command("javac")
+            .arg("-d")
+            .arg(&classes_dir)
             .arg(source_path)
             .current_dir(source_dir)
             .output()
@@ -486,24 +704,43 @@ impl ProjectService {
 
         if output.status.success() {
             println!("✅ Compilação Java bem-sucedida: {}", source_path);
-            // Para Java, retornamos o comando para executar a classe
-            let class_name = path.file_stem()
-                .and_then(|name| name.to_str())
-                .ok_or("Nome do arquivo inválido")?;
-            Ok(format!("java {}", class_name))
+            cache.record(source_path, &classes_dir.to_string_lossy(), true)?;
+            Self::java_launcher(&classes_dir, class_name)
         } else {
             let stderr = String::from_utf8_lossy(&output.stderr);
             Err(format!("Erro na compilação Java: {}", stderr))
         }
     }
 
+    /// Script `run` dentro do diretório de classes que chama `java -cp` com os paths entre aspas
+    fn java_launcher(classes_dir: &Path, class_name: &str) -> Result<String, String> {
+        let launcher = classes_dir.join("run");
+        let script = format!(
+            "#!/bin/sh\nexec java -cp {} {} \"$@\"\n",
+            shell_quote(&classes_dir.to_string_lossy()),
+            shell_quote(class_name)
+        );
+
+        fs::write(&launcher, script)
+            .map_err(|e| format!("Erro ao criar launcher Java {:?}: {}", launcher, e))?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&launcher, fs::Permissions::from_mode(0o755))
+                .map_err(|e| format!("Erro ao tornar o launcher Java executável: {}", e))?;
+        }
+
+        Ok(launcher.to_string_lossy().to_string())
+    }
+
     /// Garante que ambos os programas estejam compilados e prontos para execução
-    pub fn ensure_both_executables(project: &ProjectInfo) -> Result<(String, String), String> {
+    pub fn ensure_both_executables(project: &ProjectInfo, app: &tauri::AppHandle) -> Result<(String, String), String> {
         println!("🔨 Verificando/Compilando ambos os programas...");
 
         let (legacy_source, new_source) = Self::find_both_executables(project)?;
-        let legacy_executable = Self::ensure_executable(&legacy_source)?;
-        let new_executable = Self::ensure_executable(&new_source)?;
+        let legacy_executable = Self::ensure_executable(&legacy_source, &BuildCache::for_side(project, "legacy", app)?)?;
+        let new_executable = Self::ensure_executable(&new_source, &BuildCache::for_side(project, "new", app)?)?;
 
         println!("✅ Programas prontos para execução:");
         println!("   Legado: {}", legacy_executable);
@@ -511,87 +748,4 @@ impl ProjectService {
 
         Ok((legacy_executable, new_executable))
     }
-
-    /// Salva TestCase básicos para UI
-    pub async fn save_tests(project_id: &str, tests: Vec<TestCase>, app: &tauri::AppHandle) -> Result<(), String> {
-        let app_data_dir = app.path().app_data_dir()
-            .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-
-        let tests_path = app_data_dir.join(format!("tests-{}.json", project_id));
-
-        // Converte para objeto com ID como chave (formato que o TestService espera)
-        let mut tests_map = std::collections::HashMap::new();
-        for test in tests {
-            tests_map.insert(test.id.clone(), test);
-        }
-
-        let json_data = serde_json::to_string_pretty(&tests_map)
-            .map_err(|e| format!("Failed to serialize tests: {}", e))?;
-
-        fs::write(tests_path, json_data)
-            .map_err(|e| format!("Failed to write tests file: {}", e))?;
-
-        println!("✅ {} TestCase salvos para o projeto {}", tests_map.len(), project_id);
-        Ok(())
-    }
-
-    /// Carrega TestCase básicos para UI
-    pub async fn load_tests(project_id: &str, app: &tauri::AppHandle) -> Result<Vec<TestCase>, String> {
-        let app_data_dir = app.path().app_data_dir()
-            .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-
-        let tests_path = app_data_dir.join("projects").join(project_id).join("tests.json");
-
-        if !tests_path.exists() {
-            return Ok(Vec::new());
-        }
-
-        let json_data = fs::read_to_string(tests_path)
-            .map_err(|e| format!("Failed to read tests file: {}", e))?;
-
-        let tests: Vec<TestCase> = serde_json::from_str(&json_data)
-            .map_err(|e| format!("Failed to deserialize tests: {}", e))?;
-
-        Ok(tests)
-    }
-
-    /// Salva FullTestFromAI completo para execução
-    pub async fn save_full_tests(project_id: &str, full_tests: Vec<FullTestFromAI>, app: &tauri::AppHandle) -> Result<(), String> {
-        let app_data_dir = app.path().app_data_dir()
-            .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-
-        let project_dir = app_data_dir.join("projects").join(project_id);
-        fs::create_dir_all(&project_dir)
-            .map_err(|e| format!("Failed to create project directory: {}", e))?;
-
-        let full_tests_path = project_dir.join("full_tests.json");
-        let json_data = serde_json::to_string_pretty(&full_tests)
-            .map_err(|e| format!("Failed to serialize full tests: {}", e))?;
-
-        fs::write(full_tests_path, json_data)
-            .map_err(|e| format!("Failed to write full tests file: {}", e))?;
-
-        println!("✅ {} FullTestFromAI salvos para o projeto {}", full_tests.len(), project_id);
-        Ok(())
-    }
-
-    /// Carrega FullTestFromAI completo para execução
-    pub async fn load_full_tests(project_id: &str, app: &tauri::AppHandle) -> Result<Vec<FullTestFromAI>, String> {
-        let app_data_dir = app.path().app_data_dir()
-            .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-
-        let full_tests_path = app_data_dir.join("projects").join(project_id).join("full_tests.json");
-
-        if !full_tests_path.exists() {
-            return Ok(Vec::new());
-        }
-
-        let json_data = fs::read_to_string(full_tests_path)
-            .map_err(|e| format!("Failed to read full tests file: {}", e))?;
-
-        let full_tests: Vec<FullTestFromAI> = serde_json::from_str(&json_data)
-            .map_err(|e| format!("Failed to deserialize full tests: {}", e))?;
-
-        Ok(full_tests)
-    }
-}
\ No newline at end of file
+}
@@ -0,0 +1,421 @@
+use crate::build_cache::BuildCache;
+use crate::ai::{ExecutionInfo, FullTestFromAI};
+use crate::capture::{CaptureInfo, CapturedStream, OutputCapture};
+use crate::encoding::OutputEncoding;
+use crate::interactive::InteractiveRunner;
+use crate::paths::{shell_quote, FIXTURES_ROOT};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::thread;
+use std::time::{Duration, Instant};
+use std::process::{Child, Command, ExitStatus, Stdio};
+
+/// Saída capturada de um dos lados (legado ou novo) ao executar um teste
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SideOutput {
+    /// Saída decodificada para UTF-8 conforme a codificação do lado
+    pub stdout: String,
+    pub stderr: String,
+    #[serde(rename = "exitCode")]
+    pub exit_code: Option<i32>,
+    /// Codificação usada na decodificação ("UTF-8", "windows-1252", "IBM850"...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+    /// stdout não é texto; a comparação é feita byte a byte
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub binary: bool,
+    /// Bytes originais em base64, guardados quando diferem do texto em UTF-8
+    /// (saída transcodificada ou binária)
+    #[serde(rename = "stdoutBytes", default, skip_serializing_if = "Option::is_none")]
+    pub stdout_bytes: Option<String>,
+    #[serde(rename = "stderrBytes", default, skip_serializing_if = "Option::is_none")]
+    pub stderr_bytes: Option<String>,
+    /// Saídas acima do limite em memória: `stdout`/`stderr` têm só a prévia e o
+    /// conteúdo completo está no arquivo
+    #[serde(rename = "stdoutCapture", default, skip_serializing_if = "Option::is_none")]
+    pub stdout_capture: Option<CaptureInfo>,
+    #[serde(rename = "stderrCapture", default, skip_serializing_if = "Option::is_none")]
+    pub stderr_capture: Option<CaptureInfo>,
+    /// Falha do roteiro de um teste interativo (`expect` que esgotou o tempo,
+    /// programa que terminou antes); reprova o teste mesmo com saídas iguais
+    #[serde(rename = "scriptFailure", default, skip_serializing_if = "Option::is_none")]
+    pub script_failure: Option<String>,
+}
+
+impl SideOutput {
+    /// Decodifica as saídas com a codificação do lado (`None` detecta), mantendo
+    /// os bytes originais quando a decodificação não os preserva
+    pub fn from_bytes(stdout: &[u8], stderr: &[u8], exit_code: Option<i32>, encoding: Option<&str>) -> Self {
+        let (stdout_text, used) = OutputEncoding::decode(stdout, encoding);
+        let (stderr_text, _) = OutputEncoding::decode(stderr, encoding);
+        let keep = |bytes: &[u8], text: &str| (bytes != text.as_bytes()).then(|| BASE64.encode(bytes));
+
+        Self {
+            stdout_bytes: keep(stdout, &stdout_text),
+            stderr_bytes: keep(stderr, &stderr_text),
+            stdout: stdout_text,
+            stderr: stderr_text,
+            exit_code,
+            encoding: Some(used.to_string()),
+            binary: OutputEncoding::is_binary(stdout),
+            stdout_capture: None,
+            stderr_capture: None,
+            script_failure: None,
+        }
+    }
+
+    /// Bytes originais do stdout (o próprio texto quando já era UTF-8)
+    pub fn stdout_raw(&self) -> Vec<u8> {
+        Self::raw(&self.stdout, &self.stdout_bytes)
+    }
+
+    pub fn stderr_raw(&self) -> Vec<u8> {
+        Self::raw(&self.stderr, &self.stderr_bytes)
+    }
+
+    /// stdout completo: o arquivo da captura (até `maxOutputBytes`) ou os bytes em memória
+    pub fn full_stdout_raw(&self) -> Result<Vec<u8>, String> {
+        match &self.stdout_capture {
+            Some(capture) => fs::read(&capture.file)
+                .map_err(|e| format!("Erro lendo saída gravada {}: {}", capture.file, e)),
+            None => Ok(self.stdout_raw()),
+        }
+    }
+
+    /// sha256 dos bytes do stdout
+    pub fn stdout_sha256(&self) -> String {
+        match &self.stdout_capture {
+            Some(capture) => capture.sha256.clone(),
+            None => OutputCapture::sha256(&self.stdout_raw()),
+        }
+    }
+
+    pub fn stdout_size(&self) -> u64 {
+        match &self.stdout_capture {
+            Some(capture) => capture.size,
+            None => self.stdout_raw().len() as u64,
+        }
+    }
+
+    pub fn stderr_sha256(&self) -> String {
+        match &self.stderr_capture {
+            Some(capture) => capture.sha256.clone(),
+            None => OutputCapture::sha256(&self.stderr_raw()),
+        }
+    }
+
+    pub fn stderr_size(&self) -> u64 {
+        match &self.stderr_capture {
+            Some(capture) => capture.size,
+            None => self.stderr_raw().len() as u64,
+        }
+    }
+
+    fn raw(text: &str, bytes: &Option<String>) -> Vec<u8> {
+        bytes.as_ref()
+            .and_then(|b| BASE64.decode(b).ok())
+            .unwrap_or_else(|| text.as_bytes().to_vec())
+    }
+
+    /// Saída exibida na UI: stdout, ou stderr quando stdout está vazio
+    pub fn display_output(&self) -> String {
+        if self.stdout.trim().is_empty() {
+            self.stderr.clone()
+        } else {
+            self.stdout.clone()
+        }
+    }
+}
+
+/// Executa os comandos de compilação e execução descritos em um `ExecutionInfo`
+pub struct TestRunner;
+
+impl TestRunner {
+    /// Roda o comando de compilação (se houver) de um dos lados
+    pub fn compile(exec: &ExecutionInfo, cache: &BuildCache, label: &str) -> Result<(), String> {
+        let compile_cmd = match &exec.compile_command {
+            Some(cmd) => cmd,
+            None => return Ok(()),
+        };
+
+        println!("🔨 Compilando {}: {}", label, compile_cmd);
+        match Command::new("sh").arg("-c").arg(compile_cmd).envs(cache.env()).output() {
+            Ok(output) if output.status.success() => {
+                println!("✅ Compilação {} bem-sucedida", label);
+                Ok(())
+            },
+            Ok(output) => {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                println!("❌ Erro na compilação {}: {}", label, stderr);
+                Err(format!("Erro na compilação {}: {}", label, stderr))
+            },
+            Err(e) => {
+                println!("❌ Erro ao compilar {}: {}", label, e);
+                Err(format!("Erro ao compilar {}: {}", label, e))
+            }
+        }
+    }
+
+    /// Monta o comando completo com argumentos; cada argumento vai entre aspas
+    /// simples, para o shell não expandir `*`, `$`, `;`, espaços etc.
+    pub fn build_command(exec: &ExecutionInfo, inputs: &[String]) -> String {
+        let quoted_inputs: Vec<String> = inputs.iter().map(|input| {
+            match input.strip_prefix(FIXTURES_ROOT) {
+                // Fixture guardada em app data: só a variável fica fora das aspas
+                Some(rest) => format!("\"${{FIXTURES_DIR}}\"{}", shell_quote(rest)),
+                None => shell_quote(input),
+            }
+        }).collect();
+
+        if inputs.is_empty() {
+            exec.execute_command.clone()
+        } else {
+            format!("{} {}", exec.execute_command, quoted_inputs.join(" "))
+        }
+    }
+
+    /// Argumentos do teste: os explícitos (`args`) ou os extraídos de `fullCode`
+    pub fn test_inputs(test: &FullTestFromAI) -> Vec<String> {
+        match &test.args {
+            Some(args) => args.clone(),
+            None => extract_test_inputs(&test.full_code),
+        }
+    }
+
+    /// Executa um dos lados de um teste: sob pseudo-terminal quando o teste tem
+    /// roteiro interativo, ou com stdin/stdout comuns
+    pub fn run_test(
+        test: &FullTestFromAI,
+        exec: &ExecutionInfo,
+        inputs: &[String],
+        cache: &BuildCache,
+        label: &str
+    ) -> Result<SideOutput, String> {
+        match &test.interactive {
+            Some(script) => InteractiveRunner::run(exec, inputs, script, test.timeout.unwrap_or(30000), cache, label),
+            None => Self::run(exec, inputs, test.stdin.as_deref(), cache, label),
+        }
+    }
+
+    /// Executa um dos lados com os inputs do teste e captura stdout, stderr e exit code
+    pub fn run(
+        exec: &ExecutionInfo,
+        inputs: &[String],
+        stdin: Option<&str>,
+        cache: &BuildCache,
+        label: &str
+    ) -> Result<SideOutput, String> {
+        Self::run_limited(exec, inputs, stdin, None, cache, label)
+    }
+
+    /// Como `run`, mas encerra o programa (e os processos filhos) depois de `timeout`;
+    /// nesse caso o exit code fica vazio e o motivo vai no fim do stderr
+    pub fn run_limited(
+        exec: &ExecutionInfo,
+        inputs: &[String],
+        stdin: Option<&str>,
+        timeout: Option<Duration>,
+        cache: &BuildCache,
+        label: &str
+    ) -> Result<SideOutput, String> {
+        let command = Self::build_command(exec, inputs);
+        println!("🎯 Executando {}: {}", label, command);
+
+        let mut process = Command::new("sh");
+        process
+            .arg("-c")
+            .arg(&command)
+            .current_dir(&exec.working_directory)
+            .envs(cache.env())
+            .stdin(if stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        // Grupo próprio para o timeout encerrar também os filhos de `sh -c`
+        #[cfg(unix)]
+        if timeout.is_some() {
+            use std::os::unix::process::CommandExt;
+            process.process_group(0);
+        }
+
+        let mut child = process.spawn()
+            .map_err(|e| format!("Erro ao executar {}: {}", label, e))?;
+
+        if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+            // Em outra thread, para um programa que escreve antes de ler todo o stdin
+            // não travar; programas que não leem o stdin fecham o pipe, o que não é erro
+            let input = input.to_string();
+            thread::spawn(move || {
+                let _ = pipe.write_all(input.as_bytes());
+            });
+        }
+
+        // stdout e stderr lidos em paralelo, para nenhum dos pipes encher e travar o programa
+        let capture = cache.capture();
+        let stdout = child.stdout.take().map(|pipe| {
+            let capture = capture.clone();
+            thread::spawn(move || capture.read_stream(pipe, "stdout"))
+        });
+        let stderr = child.stderr.take().map(|pipe| {
+            let capture = capture.clone();
+            thread::spawn(move || capture.read_stream(pipe, "stderr"))
+        });
+
+        let status = match timeout {
+            Some(timeout) => Self::wait_until(&mut child, Instant::now() + timeout),
+            None => child.wait().map(Some),
+        }.map_err(|e| format!("Erro ao executar {}: {}", label, e))?;
+
+        let join = |reader: Option<thread::JoinHandle<Result<CapturedStream, String>>>| match reader {
+            Some(reader) => reader.join().map_err(|_| format!("Erro lendo a saída de {}", label))?.map(Some),
+            None => Ok(None),
+        };
+        let stdout = join(stdout)?;
+        let stderr = join(stderr)?;
+
+        let (stdout_bytes, stdout_capture) = stdout.map(|s| (s.bytes, s.spill)).unwrap_or_default();
+        let (mut stderr_bytes, stderr_capture) = stderr.map(|s| (s.bytes, s.spill)).unwrap_or_default();
+
+        if status.is_none() {
+            let timeout = timeout.unwrap_or_default().as_millis();
+            println!("⏱️ {} encerrado após {}ms", label, timeout);
+            stderr_bytes.extend(format!("\nTempo esgotado após {}ms", timeout).as_bytes());
+        }
+
+        Ok(SideOutput {
+            stdout_capture,
+            stderr_capture,
+            ..SideOutput::from_bytes(&stdout_bytes, &stderr_bytes, status.and_then(|s| s.code()), cache.encoding())
+        })
+    }
+
+    /// Encerra o grupo de processos liderado por `pid` (spawn com `process_group(0)`
+    /// ou sessão própria), para não sobrarem os filhos de `sh -c`
+    pub fn kill_group(pid: u32) {
+        #[cfg(unix)]
+        {
+            let _ = Command::new("kill")
+                .arg("-KILL")
+                .arg(format!("-{}", pid))
+                .stderr(Stdio::null())
+                .status();
+        }
+        #[cfg(not(unix))]
+        let _ = pid;
+    }
+
+    /// Espera o programa até o prazo; depois disso encerra o grupo de processos e devolve `None`
+    fn wait_until(child: &mut Child, deadline: Instant) -> std::io::Result<Option<ExitStatus>> {
+        loop {
+            if let Some(status) = child.try_wait()? {
+                return Ok(Some(status));
+            }
+
+            if Instant::now() >= deadline {
+                Self::kill_group(child.id());
+                let _ = child.kill();
+                let _ = child.wait();
+                return Ok(None);
+            }
+
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    /// Compara outputs - considera sucesso se forem idênticos ou se ambos são mensagens de uso
+    pub fn outputs_match(legacy_final: &str, new_final: &str) -> bool {
+        legacy_final.trim() == new_final.trim() ||
+            (legacy_final.contains("Uso:") && new_final.contains("Uso:") &&
+             legacy_final.contains("<numero1>") && new_final.contains("<numero1>"))
+    }
+}
+
+pub fn extract_test_inputs(test_code: &str) -> Vec<String> {
+    // Remove comentários e linhas vazias
+    let clean_code = test_code
+        .lines()
+        .filter(|line| !line.trim().starts_with("//") && !line.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    // Padrão 1: Extrai números e operadores diretamente do código
+    let re_basic = Regex::new(r#"(\d+\.?\d*|[+\-*/%^])"#).unwrap();
+
+    let inputs: Vec<String> = re_basic.find_iter(&clean_code)
+        .map(|m| m.as_str().to_string())
+        .collect();
+
+    // Se já encontramos inputs suficientes (pelo menos 3 para num1 op num2), retorna
+    if inputs.len() >= 3 {
+        return inputs;
+    }
+
+    // Padrão 2: Casos incompletos como "2 +" ou "+ 3" - detectar erro esperado
+    if inputs.len() == 2 {
+        // Verifica se temos número + operador ou operador + número
+        let has_number = inputs.iter().any(|i| i.chars().next().unwrap().is_numeric());
+        let has_operator = inputs.iter().any(|i| "+-*/%^".contains(i));
+
+        if has_number && has_operator {
+            // É um caso incompleto esperando erro (como "2 +" ou "+ 3")
+            // Retorna os inputs existentes para que o programa mostre erro de argumentos faltando
+            return inputs;
+        }
+    }
+
+    // Padrão 3: Procura por declarações de variáveis com valores
+    let re_vars = Regex::new(r#"(?:let|const|int|float|double)\s+\w+\s*=\s*(\d+\.?\d*)\s*;?"#).unwrap();
+    let var_values: Vec<String> = re_vars.captures_iter(&clean_code)
+        .map(|cap| cap[1].to_string())
+        .collect();
+
+    if var_values.len() >= 2 {
+        // Se temos pelo menos 2 variáveis numéricas, procuramos o operador
+        let re_op = Regex::new(r#"([+\-*/^])"#).unwrap();
+        let op_match = re_op.find(&clean_code);
+
+        let mut result = var_values;
+        if let Some(op) = op_match {
+            result.insert(1, op.as_str().to_string());
+        }
+        return result;
+    }
+
+    // Padrão 4: Procura por chamadas de função com argumentos
+    let re_func = Regex::new(r#"[a-zA-Z_]\w*\s*\(\s*(\d+\.?\d*)\s*,\s*([+\-*/^])\s*,\s*(\d+\.?\d*)\s*\)"#).unwrap();
+    if let Some(cap) = re_func.captures(&clean_code) {
+        return vec![
+            cap[1].to_string(),
+            cap[2].to_string(),
+            cap[3].to_string()
+        ];
+    }
+
+    // Padrão 5: Testes com variáveis (a + b) - usar valores de exemplo
+    if clean_code.contains("a") && clean_code.contains("b") {
+        if let Some(op_match) = re_basic.find(&clean_code) {
+            let op = op_match.as_str();
+            if op.chars().any(|c| "+-*/%^".contains(c)) {
+                return vec!["5".to_string(), op.to_string(), "3".to_string()];
+            }
+        }
+    }
+
+    // Padrão 6: Teste de uso (vazio ou sem argumentos) - não precisa de inputs
+    if clean_code.trim().is_empty() || clean_code.to_lowercase().contains("usage") ||
+       clean_code.to_lowercase().contains("sem argumentos") || clean_code.to_lowercase().contains("no args") {
+        return vec![];  // Teste de uso intencionalmente vazio
+    }
+
+    // Padrão 7: Fallback para operadores não suportados - usar valores de exemplo
+    if !inputs.is_empty() {
+        return inputs;
+    }
+
+    // Se nada funcionou, retorna vazio para fallback
+    vec![]
+}
@@ -0,0 +1,87 @@
+use crate::storage::{Storage, CURRENT_SCHEMA_VERSION};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::Manager;
+
+/// Configurações globais do app (`settings.json` em app data)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AppSettings {
+    #[serde(rename = "schemaVersion", default)]
+    pub schema_version: u32,
+    /// Quantas execuções manter no histórico de cada projeto
+    #[serde(rename = "executionRetention", default = "default_execution_retention")]
+    pub execution_retention: usize,
+    /// Saídas até este tamanho ficam em memória e no histórico; acima dele vão
+    /// para arquivos da execução e são comparadas por hash
+    #[serde(rename = "captureInlineBytes", default = "default_capture_inline_bytes")]
+    pub capture_inline_bytes: u64,
+    /// Quanto de cada saída é gravado em arquivo; o excedente é descartado
+    #[serde(rename = "maxOutputBytes", default = "default_max_output_bytes")]
+    pub max_output_bytes: u64,
+    /// Tamanho das prévias de saída enviadas à UI
+    #[serde(rename = "previewBytes", default = "default_preview_bytes")]
+    pub preview_bytes: usize,
+}
+
+fn default_execution_retention() -> usize {
+    50
+}
+
+fn default_capture_inline_bytes() -> u64 {
+    1024 * 1024
+}
+
+fn default_max_output_bytes() -> u64 {
+    512 * 1024 * 1024
+}
+
+fn default_preview_bytes() -> usize {
+    64 * 1024
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            execution_retention: default_execution_retention(),
+            capture_inline_bytes: default_capture_inline_bytes(),
+            max_output_bytes: default_max_output_bytes(),
+            preview_bytes: default_preview_bytes(),
+        }
+    }
+}
+
+/// Leitura e gravação das configurações globais
+pub struct SettingsService;
+
+impl SettingsService {
+    pub fn load(app: &tauri::AppHandle) -> Result<AppSettings, String> {
+        Ok(Storage::read_json(&Self::settings_path(app)?)?.unwrap_or_default())
+    }
+
+    pub fn save(settings: &AppSettings, app: &tauri::AppHandle) -> Result<(), String> {
+        if settings.execution_retention == 0 {
+            return Err("A retenção de execuções deve ser de pelo menos 1".to_string());
+        }
+
+        if settings.preview_bytes == 0 || settings.preview_bytes as u64 > settings.capture_inline_bytes {
+            return Err("A prévia deve ter entre 1 byte e o limite de captura em memória".to_string());
+        }
+
+        if settings.capture_inline_bytes > settings.max_output_bytes {
+            return Err("O limite de captura em memória não pode passar do tamanho máximo de saída".to_string());
+        }
+
+        let mut settings = settings.clone();
+        settings.schema_version = CURRENT_SCHEMA_VERSION;
+
+        Storage::write_json_atomic(&Self::settings_path(app)?, &settings)
+    }
+
+    fn settings_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+        let app_data_dir = app.path().app_data_dir()
+            .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+        Ok(app_data_dir.join("settings.json"))
+    }
+}
@@ -0,0 +1,303 @@
+use crate::ai::FullTestFromAI;
+use crate::build_cache::BuildCache;
+use crate::project::ProjectService;
+use crate::runner::{SideOutput, TestRunner};
+use crate::storage::{Storage, CURRENT_SCHEMA_VERSION};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Tamanho máximo de arquivo cujo conteúdo é guardado no snapshot (os demais ficam só com hash)
+const MAX_INLINE_FILE_SIZE: u64 = 64 * 1024;
+
+/// Diretórios nunca observados dentro de `trackedFiles` (caches, VCS e saídas de build)
+const IGNORED_DIRS: [&str; 6] = [".git", "__pycache__", "target", "node_modules", ".venv", ".pytest_cache"];
+
+/// Efeito colateral em arquivo observado durante a execução do legado
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FileEffect {
+    /// Caminho relativo ao diretório de trabalho
+    pub path: String,
+    /// "created", "modified" ou "deleted"
+    pub change: String,
+    pub size: u64,
+    pub hash: Option<String>,
+    pub content: Option<String>,
+}
+
+/// Resultado gravado da execução do legado para um teste
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LegacySnapshot {
+    #[serde(rename = "schemaVersion", default)]
+    pub schema_version: u32,
+    #[serde(rename = "testId")]
+    pub test_id: String,
+    pub version: u32,
+    #[serde(rename = "recordedAt")]
+    pub recorded_at: String,
+    pub command: String,
+    pub output: SideOutput,
+    #[serde(rename = "fileEffects")]
+    pub file_effects: Vec<FileEffect>,
+}
+
+/// Resumo dos snapshots de um teste (para listagem na UI)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SnapshotSummary {
+    #[serde(rename = "testId")]
+    pub test_id: String,
+    #[serde(rename = "latestVersion")]
+    pub latest_version: u32,
+    #[serde(rename = "recordedAt")]
+    pub recorded_at: String,
+}
+
+/// Estado de um arquivo do diretório de trabalho em um dado momento
+#[derive(Debug, Clone, PartialEq)]
+struct FileState {
+    size: u64,
+    hash: String,
+}
+
+/// Serviço para gravar a saída do legado uma vez e reutilizá-la nas execuções seguintes
+pub struct SnapshotService;
+
+impl SnapshotService {
+    /// Executa o legado de um teste e grava uma nova versão do snapshot
+    pub fn record(
+        project_id: &str,
+        test: &FullTestFromAI,
+        cache: &BuildCache,
+        app: &tauri::AppHandle
+    ) -> Result<LegacySnapshot, String> {
+        println!("📸 Gravando snapshot do legado para o teste: {}", test.name);
+
+        TestRunner::compile(&test.legacy_exec, cache, "legado")?;
+
+        // Saídas grandes ficam em arquivo junto das versões do snapshot
+        let test_dir = Self::test_snapshot_dir(project_id, &test.id, app)?;
+        let cache = cache.with_capture_dir(&test_dir);
+
+        let inputs = TestRunner::test_inputs(test);
+        let (output, file_effects) = Self::run_with_effects(test, &test.legacy_exec, &inputs, &cache, "legado")?;
+
+        let _lock = Storage::lock_project(project_id, app)?;
+
+        let version = Self::latest_version(&test_dir) + 1;
+        let snapshot = LegacySnapshot {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            test_id: test.id.clone(),
+            version,
+            recorded_at: chrono::Utc::now().to_rfc3339(),
+            command: TestRunner::build_command(&test.legacy_exec, &inputs),
+            output,
+            file_effects,
+        };
+
+        Storage::write_json_atomic(&test_dir.join(format!("v{}.json", version)), &snapshot)?;
+
+        println!("✅ Snapshot v{} gravado para o teste {}", version, test.id);
+        Ok(snapshot)
+    }
+
+    /// Carrega a versão mais recente do snapshot de um teste
+    pub fn load_latest(project_id: &str, test_id: &str, app: &tauri::AppHandle) -> Result<Option<LegacySnapshot>, String> {
+        let test_dir = Self::test_snapshot_dir(project_id, test_id, app)?;
+        let version = Self::latest_version(&test_dir);
+
+        if version == 0 {
+            return Ok(None);
+        }
+
+        Storage::read_json(&test_dir.join(format!("v{}.json", version)))
+    }
+
+    /// Lista os testes que possuem snapshot gravado
+    pub fn list(project_id: &str, app: &tauri::AppHandle) -> Result<Vec<SnapshotSummary>, String> {
+        let snapshots_dir = ProjectService::project_data_dir(project_id, app)?.join("snapshots");
+
+        if !snapshots_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let entries = fs::read_dir(&snapshots_dir)
+            .map_err(|e| format!("Failed to read snapshots directory: {}", e))?;
+
+        let mut summaries = Vec::new();
+        for entry in entries.filter_map(|e| e.ok()) {
+            let test_dir = entry.path();
+            if !test_dir.is_dir() {
+                continue;
+            }
+
+            let test_id = entry.file_name().to_string_lossy().to_string();
+            if let Some(snapshot) = Self::load_latest(project_id, &test_id, app)? {
+                summaries.push(SnapshotSummary {
+                    test_id: snapshot.test_id,
+                    latest_version: snapshot.version,
+                    recorded_at: snapshot.recorded_at,
+                });
+            }
+        }
+
+        summaries.sort_by(|a, b| a.test_id.cmp(&b.test_id));
+        Ok(summaries)
+    }
+
+    /// Executa um dos lados registrando os arquivos criados, modificados e removidos
+    /// nos caminhos de `trackedFiles` (nenhum, se o teste não declarar)
+    pub fn run_with_effects(
+        test: &FullTestFromAI,
+        exec: &crate::ai::ExecutionInfo,
+        inputs: &[String],
+        cache: &BuildCache,
+        label: &str
+    ) -> Result<(SideOutput, Vec<FileEffect>), String> {
+        Self::observe_effects(test, exec, || TestRunner::run_test(test, exec, inputs, cache, label))
+    }
+
+    /// Observa os arquivos declarados em `trackedFiles` em volta de uma execução
+    /// qualquer do lado (`run`); sem arquivos declarados, só executa
+    pub fn observe_effects(
+        test: &FullTestFromAI,
+        exec: &crate::ai::ExecutionInfo,
+        run: impl FnOnce() -> Result<SideOutput, String>
+    ) -> Result<(SideOutput, Vec<FileEffect>), String> {
+        let tracked = match &test.tracked_files {
+            Some(tracked) if Self::tracks_effects(test) => tracked,
+            _ => return Ok((run()?, Vec::new())),
+        };
+        let working_dir = Path::new(&exec.working_directory);
+
+        let before = Self::capture_tree(working_dir, tracked);
+        let output = run()?;
+        let after = Self::capture_tree(working_dir, tracked);
+
+        Ok((output, Self::diff_trees(&before, &after, working_dir)))
+    }
+
+    /// Se o teste pediu para comparar arquivos afetados (`trackedFiles`)
+    pub fn tracks_effects(test: &FullTestFromAI) -> bool {
+        test.tracked_files.as_ref().is_some_and(|tracked| !tracked.is_empty())
+    }
+
+    /// Compara efeitos colaterais gravados do legado com os observados no novo
+    pub fn compare_effects(expected: &[FileEffect], actual: &[FileEffect]) -> Vec<String> {
+        let mut differences = Vec::new();
+
+        for effect in expected {
+            match actual.iter().find(|a| a.path == effect.path) {
+                None => differences.push(format!("Arquivo '{}' ({}) não foi afetado pelo novo", effect.path, effect.change)),
+                Some(other) if other.change != effect.change => differences.push(format!(
+                    "Arquivo '{}': legado {} / novo {}", effect.path, effect.change, other.change
+                )),
+                Some(other) if other.hash != effect.hash => differences.push(format!(
+                    "Arquivo '{}' tem conteúdo diferente", effect.path
+                )),
+                Some(_) => {}
+            }
+        }
+
+        for effect in actual {
+            if !expected.iter().any(|e| e.path == effect.path) {
+                differences.push(format!("Arquivo '{}' ({}) só foi afetado pelo novo", effect.path, effect.change));
+            }
+        }
+
+        differences
+    }
+
+    /// Diretório de snapshots de um teste
+    fn test_snapshot_dir(project_id: &str, test_id: &str, app: &tauri::AppHandle) -> Result<PathBuf, String> {
+        let safe_id: String = test_id.chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+
+        Ok(ProjectService::project_data_dir(project_id, app)?.join("snapshots").join(safe_id))
+    }
+
+    /// Maior versão gravada em um diretório de snapshots (0 se nenhuma)
+    fn latest_version(test_dir: &Path) -> u32 {
+        fs::read_dir(test_dir)
+            .map(|entries| {
+                entries.filter_map(|e| e.ok())
+                    .filter_map(|e| {
+                        let name = e.file_name().to_string_lossy().to_string();
+                        name.strip_prefix('v')
+                            .and_then(|rest| rest.strip_suffix(".json"))
+                            .and_then(|n| n.parse::<u32>().ok())
+                    })
+                    .max()
+                    .unwrap_or(0)
+            })
+            .unwrap_or(0)
+    }
+
+    /// Lê tamanho e hash dos arquivos observados: os declarados e, nos diretórios
+    /// declarados, tudo fora de `IGNORED_DIRS`
+    fn capture_tree(dir: &Path, tracked: &[String]) -> BTreeMap<String, FileState> {
+        let mut states = BTreeMap::new();
+
+        for entry in tracked.iter()
+            .flat_map(|path| walkdir::WalkDir::new(dir.join(path))
+                .into_iter()
+                .filter_entry(|e| e.depth() == 0 || !IGNORED_DIRS.iter().any(|d| e.file_name() == *d)))
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file()) {
+
+            let relative = entry.path().strip_prefix(dir).unwrap_or(entry.path());
+            if let Ok(content) = fs::read(entry.path()) {
+                states.insert(relative.to_string_lossy().to_string(), FileState {
+                    size: content.len() as u64,
+                    hash: format!("{:x}", Sha256::digest(&content)),
+                });
+            }
+        }
+
+        states
+    }
+
+    /// Calcula os efeitos colaterais entre duas leituras do diretório
+    fn diff_trees(before: &BTreeMap<String, FileState>, after: &BTreeMap<String, FileState>, dir: &Path) -> Vec<FileEffect> {
+        let mut effects = Vec::new();
+
+        for (path, state) in after {
+            let change = match before.get(path) {
+                None => "created",
+                Some(previous) if previous != state => "modified",
+                Some(_) => continue,
+            };
+
+            let content = if state.size <= MAX_INLINE_FILE_SIZE {
+                fs::read(dir.join(path)).ok()
+                    .and_then(|bytes| String::from_utf8(bytes).ok())
+            } else {
+                None
+            };
+
+            effects.push(FileEffect {
+                path: path.clone(),
+                change: change.to_string(),
+                size: state.size,
+                hash: Some(state.hash.clone()),
+                content,
+            });
+        }
+
+        for path in before.keys() {
+            if !after.contains_key(path) {
+                effects.push(FileEffect {
+                    path: path.clone(),
+                    change: "deleted".to_string(),
+                    size: 0,
+                    hash: None,
+                    content: None,
+                });
+            }
+        }
+
+        effects
+    }
+}
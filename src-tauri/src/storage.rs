@@ -0,0 +1,103 @@
+use crate::project::ProjectService;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use tauri::Manager;
+
+/// Versão atual do formato dos documentos JSON gravados pelo app (campo `schemaVersion`)
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+/// Trava exclusiva de um projeto; liberada quando sai de escopo
+pub struct ProjectLock {
+    _file: File,
+}
+
+/// Leitura e escrita segura dos documentos JSON em app data
+pub struct Storage;
+
+impl Storage {
+    /// Lê um documento JSON; retorna `None` se o arquivo não existe
+    pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, String> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let json_data = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+
+        serde_json::from_str(&json_data)
+            .map(Some)
+            .map_err(|e| format!("Failed to deserialize {:?}: {}", path, e))
+    }
+
+    /// Grava um documento JSON de forma atômica: escreve em um arquivo temporário
+    /// no mesmo diretório e renomeia por cima do original
+    pub fn write_json_atomic<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+        let json_data = serde_json::to_string_pretty(value)
+            .map_err(|e| format!("Failed to serialize {:?}: {}", path, e))?;
+
+        Self::write_atomic(path, json_data.as_bytes())
+    }
+
+    /// Grava bytes de forma atômica (temporário + fsync + rename)
+    pub fn write_atomic(path: &Path, data: &[u8]) -> Result<(), String> {
+        let parent = path.parent().unwrap_or_else(|| Path::new("."));
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory {:?}: {}", parent, e))?;
+
+        let file_name = path.file_name()
+            .and_then(|n| n.to_str())
+            .ok_or(format!("Caminho inválido: {:?}", path))?;
+        let temp_path = parent.join(format!(".{}.{}.tmp", file_name, uuid::Uuid::new_v4()));
+
+        let result = (|| {
+            let mut file = File::create(&temp_path)?;
+            file.write_all(data)?;
+            file.sync_all()?;
+            fs::rename(&temp_path, path)
+        })();
+
+        if let Err(e) = result {
+            let _ = fs::remove_file(&temp_path);
+            return Err(format!("Failed to write {:?}: {}", path, e));
+        }
+
+        Ok(())
+    }
+
+    /// Adquire a trava exclusiva de um projeto (`projects/<id>/.lock`), bloqueando
+    /// até que outra escrita em andamento termine
+    pub fn lock_project(project_id: &str, app: &tauri::AppHandle) -> Result<ProjectLock, String> {
+        let project_dir = ProjectService::project_data_dir(project_id, app)?;
+        Self::lock_file(&project_dir.join(".lock"))
+    }
+
+    /// Adquire a trava de `projects.json` (`projects.lock` em app data)
+    pub fn lock_projects(app: &tauri::AppHandle) -> Result<ProjectLock, String> {
+        let app_data_dir = app.path().app_data_dir()
+            .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+        Self::lock_file(&app_data_dir.join("projects.lock"))
+    }
+
+    fn lock_file(path: &Path) -> Result<ProjectLock, String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create directory {:?}: {}", parent, e))?;
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)
+            .map_err(|e| format!("Failed to open lock {:?}: {}", path, e))?;
+
+        file.lock()
+            .map_err(|e| format!("Failed to lock {:?}: {}", path, e))?;
+
+        Ok(ProjectLock { _file: file })
+    }
+}
@@ -0,0 +1,358 @@
+use crate::ai::{ExecutionInfo, FullTestFromAI, TestCase};
+use crate::parameters::ParameterExpander;
+use crate::paths::PortablePaths;
+use crate::project::ProjectService;
+use crate::storage::{Storage, CURRENT_SCHEMA_VERSION};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Origem (`origin`) dos testes gerados pela IA
+pub const AI_ORIGIN: &str = "ai";
+
+/// Último resultado de execução guardado junto da definição do teste
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TestResult {
+    pub status: String,
+    #[serde(rename = "executionTime")]
+    pub execution_time: Option<u64>,
+    #[serde(rename = "legacyOutput")]
+    pub legacy_output: Option<String>,
+    #[serde(rename = "newOutput")]
+    pub new_output: Option<String>,
+}
+
+/// Definição de um teste mais o resultado da última execução
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StoredTest {
+    #[serde(flatten)]
+    pub definition: FullTestFromAI,
+    #[serde(rename = "lastResult", default, skip_serializing_if = "Option::is_none")]
+    pub last_result: Option<TestResult>,
+}
+
+impl StoredTest {
+    /// Visão resumida usada pela UI
+    pub fn to_test_case(&self) -> TestCase {
+        let mut test_case = TestCase {
+            id: self.definition.id.clone(),
+            name: self.definition.name.clone(),
+            description: self.definition.description.clone(),
+            full_code: self.definition.full_code.clone(),
+            ..Default::default()
+        };
+
+        if let Some(result) = &self.last_result {
+            test_case.status = result.status.clone();
+            test_case.execution_time = result.execution_time;
+            test_case.legacy_output = result.legacy_output.clone();
+            test_case.new_output = result.new_output.clone();
+        }
+
+        test_case
+    }
+}
+
+/// Documento único de testes de um projeto (`projects/<id>/tests.json`)
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TestSuite {
+    #[serde(rename = "schemaVersion", default)]
+    pub schema_version: u32,
+    pub tests: Vec<StoredTest>,
+}
+
+/// Armazenamento único dos testes de um projeto: definições e últimos resultados
+pub struct TestStore;
+
+impl TestStore {
+    /// Carrega a suíte do projeto (vazia se ainda não há testes)
+    pub async fn load_suite(project_id: &str, app: &tauri::AppHandle) -> Result<TestSuite, String> {
+        Ok(Storage::read_json(&Self::suite_path(project_id, app)?)?.unwrap_or_default())
+    }
+
+    /// Salva a suíte do projeto. Quem faz leitura-modificação-escrita deve segurar
+    /// a trava do projeto (`Storage::lock_project`) durante toda a operação.
+    /// Paths dentro das raízes do projeto são gravados com `${LEGACY_ROOT}`/`${NEW_ROOT}`.
+    pub async fn save_suite(project_id: &str, suite: &TestSuite, app: &tauri::AppHandle) -> Result<(), String> {
+        let mut suite = suite.clone();
+        suite.schema_version = CURRENT_SCHEMA_VERSION;
+
+        if let Ok(project) = ProjectService::load_project(project_id, app).await {
+            for stored in &mut suite.tests {
+                PortablePaths::relativize(&mut stored.definition, &project);
+            }
+        }
+
+        Storage::write_json_atomic(&Self::suite_path(project_id, app)?, &suite)?;
+
+        println!("✅ {} testes salvos para o projeto {}", suite.tests.len(), project_id);
+        Ok(())
+    }
+
+    /// Lista as definições dos testes do projeto, com os paths resolvidos
+    /// para as raízes atuais do projeto
+    pub async fn list(project_id: &str, app: &tauri::AppHandle) -> Result<Vec<FullTestFromAI>, String> {
+        let suite = Self::load_suite(project_id, app).await?;
+        let project = ProjectService::load_project(project_id, app).await?;
+
+        Ok(suite.tests.into_iter()
+            .map(|t| {
+                let mut definition = t.definition;
+                PortablePaths::resolve(&mut definition, &project);
+                definition
+            })
+            .collect())
+    }
+
+    /// Lista os testes com o último resultado, no formato exibido pela UI
+    pub async fn list_test_cases(project_id: &str, app: &tauri::AppHandle) -> Result<Vec<TestCase>, String> {
+        let suite = Self::load_suite(project_id, app).await?;
+        Ok(suite.tests.iter().map(|t| t.to_test_case()).collect())
+    }
+
+    /// Busca um teste pelo ID
+    pub async fn get(project_id: &str, test_id: &str, app: &tauri::AppHandle) -> Result<FullTestFromAI, String> {
+        Self::list(project_id, app).await?
+            .into_iter()
+            .find(|t| t.id == test_id)
+            .ok_or(format!("Teste com ID '{}' não encontrado", test_id))
+    }
+
+    /// Troca os testes gerados pela IA pelos de uma nova geração; os testes escritos,
+    /// importados ou derivados ficam, e um ID gerado que já exista ganha sufixo
+    pub async fn replace_generated(project_id: &str, tests: Vec<FullTestFromAI>, app: &tauri::AppHandle) -> Result<(), String> {
+        let _lock = Storage::lock_project(project_id, app)?;
+        let mut suite = Self::load_suite(project_id, app).await?;
+
+        suite.tests.retain(|t| t.definition.origin.as_deref() != Some(AI_ORIGIN));
+        let kept = suite.tests.len();
+
+        for mut definition in tests {
+            definition.origin = Some(AI_ORIGIN.to_string());
+            if suite.tests.iter().any(|t| t.definition.id == definition.id) {
+                definition.id = (1..)
+                    .map(|n| format!("{}-ai-{}", definition.id, n))
+                    .find(|id| !suite.tests.iter().any(|t| &t.definition.id == id))
+                    .unwrap_or_default();
+            }
+            suite.tests.push(StoredTest { definition, last_result: None });
+        }
+
+        if kept > 0 {
+            println!("📌 {} testes escritos ou importados mantidos", kept);
+        }
+        Self::save_suite(project_id, &suite, app).await
+    }
+
+    /// Guarda o resultado mais recente de cada teste executado
+    pub async fn record_results(project_id: &str, results: &[TestCase], app: &tauri::AppHandle) -> Result<(), String> {
+        let _lock = Storage::lock_project(project_id, app)?;
+        let mut suite = Self::load_suite(project_id, app).await?;
+
+        for stored in suite.tests.iter_mut() {
+            let id = stored.definition.id.as_str();
+            let instances: Vec<&TestCase> = results.iter()
+                .filter(|r| r.id == id || ParameterExpander::parent_id(&r.id) == Some(id))
+                .collect();
+
+            // Testes parametrizados: falha se alguma instância falhou, com as saídas da primeira que falhou
+            let shown = match instances.iter().find(|r| r.status == "failed").or(instances.first()) {
+                Some(shown) => shown,
+                None => continue,
+            };
+
+            stored.last_result = Some(TestResult {
+                status: shown.status.clone(),
+                execution_time: instances.iter().filter_map(|r| r.execution_time).reduce(|a, b| a + b),
+                legacy_output: shown.legacy_output.clone(),
+                new_output: shown.new_output.clone(),
+            });
+        }
+
+        Self::save_suite(project_id, &suite, app).await
+    }
+
+    /// Cria um teste escrito manualmente (gera ID se vier vazio)
+    pub async fn create(project_id: &str, mut test: FullTestFromAI, app: &tauri::AppHandle) -> Result<FullTestFromAI, String> {
+        let _lock = Storage::lock_project(project_id, app)?;
+        let mut suite = Self::load_suite(project_id, app).await?;
+
+        if test.id.trim().is_empty() {
+            test.id = format!("manual-{}", uuid::Uuid::new_v4());
+        }
+        // Derivados de testes da IA (fuzz, minimização) também não são substituídos
+        test.origin = None;
+
+        Self::validate(&test)?;
+
+        if suite.tests.iter().any(|t| t.definition.id == test.id) {
+            return Err(format!("Já existe um teste com ID '{}'", test.id));
+        }
+
+        suite.tests.push(StoredTest { definition: test.clone(), last_result: None });
+        Self::save_suite(project_id, &suite, app).await?;
+
+        println!("✅ Teste criado: {} ({})", test.name, test.id);
+        Ok(test)
+    }
+
+    /// Substitui a definição de um teste existente (o último resultado deixa de valer);
+    /// um teste da IA editado deixa de ser substituído na próxima geração
+    pub async fn update(project_id: &str, mut test: FullTestFromAI, app: &tauri::AppHandle) -> Result<FullTestFromAI, String> {
+        Self::validate(&test)?;
+        test.origin = None;
+
+        let _lock = Storage::lock_project(project_id, app)?;
+        let mut suite = Self::load_suite(project_id, app).await?;
+        let existing = suite.tests.iter_mut()
+            .find(|t| t.definition.id == test.id)
+            .ok_or(format!("Teste com ID '{}' não encontrado", test.id))?;
+
+        existing.definition = test.clone();
+        existing.last_result = None;
+        Self::save_suite(project_id, &suite, app).await?;
+
+        println!("✅ Teste atualizado: {} ({})", test.name, test.id);
+        Ok(test)
+    }
+
+    /// Remove um teste
+    pub async fn delete(project_id: &str, test_id: &str, app: &tauri::AppHandle) -> Result<(), String> {
+        let _lock = Storage::lock_project(project_id, app)?;
+        let mut suite = Self::load_suite(project_id, app).await?;
+        let count_before = suite.tests.len();
+
+        suite.tests.retain(|t| t.definition.id != test_id);
+
+        if suite.tests.len() == count_before {
+            return Err(format!("Teste com ID '{}' não encontrado", test_id));
+        }
+
+        Self::save_suite(project_id, &suite, app).await?;
+
+        println!("🗑️ Teste removido: {}", test_id);
+        Ok(())
+    }
+
+    /// Remove todos os testes do projeto
+    pub async fn delete_all(project_id: &str, app: &tauri::AppHandle) -> Result<(), String> {
+        let _lock = Storage::lock_project(project_id, app)?;
+        let suite_path = Self::suite_path(project_id, app)?;
+
+        if suite_path.exists() {
+            fs::remove_file(&suite_path)
+                .map_err(|e| format!("Failed to remove tests file: {}", e))?;
+        }
+
+        println!("🗑️ Todos os testes removidos do projeto {}", project_id);
+        Ok(())
+    }
+
+    /// Duplica um teste com novo ID, para servir de base a um caso de borda
+    pub async fn duplicate(project_id: &str, test_id: &str, app: &tauri::AppHandle) -> Result<FullTestFromAI, String> {
+        let _lock = Storage::lock_project(project_id, app)?;
+        let mut suite = Self::load_suite(project_id, app).await?;
+        let original = suite.tests.iter()
+            .map(|t| &t.definition)
+            .find(|t| t.id == test_id)
+            .ok_or(format!("Teste com ID '{}' não encontrado", test_id))?;
+
+        let mut copy = original.clone();
+        let mut suffix = 1;
+        loop {
+            let candidate = format!("{}-copy-{}", original.id, suffix);
+            if !suite.tests.iter().any(|t| t.definition.id == candidate) {
+                copy.id = candidate;
+                break;
+            }
+            suffix += 1;
+        }
+        copy.name = format!("{} (cópia)", original.name);
+        copy.origin = None;
+
+        suite.tests.push(StoredTest { definition: copy.clone(), last_result: None });
+        Self::save_suite(project_id, &suite, app).await?;
+
+        println!("✅ Teste duplicado: {} -> {}", test_id, copy.id);
+
+        if let Ok(project) = ProjectService::load_project(project_id, app).await {
+            PortablePaths::resolve(&mut copy, &project);
+        }
+        Ok(copy)
+    }
+
+    /// Troca o bloco de execução de um dos lados ("legacy" ou "new") em todos os testes,
+    /// ex.: pelo plano do sistema de build detectado no lugar do sugerido pela IA
+    pub async fn replace_exec(project_id: &str, side: &str, exec: ExecutionInfo, app: &tauri::AppHandle) -> Result<usize, String> {
+        let label = match side {
+            "legacy" => "legado",
+            "new" => "novo",
+            _ => return Err(format!("Lado inválido: {} (use legacy ou new)", side)),
+        };
+        Self::validate_exec(&exec, label)?;
+
+        let _lock = Storage::lock_project(project_id, app)?;
+        let mut suite = Self::load_suite(project_id, app).await?;
+
+        for stored in &mut suite.tests {
+            match side {
+                "legacy" => stored.definition.legacy_exec = exec.clone(),
+                _ => stored.definition.new_exec = exec.clone(),
+            }
+            stored.last_result = None;
+        }
+
+        Self::save_suite(project_id, &suite, app).await?;
+
+        println!("✅ Execução do {} substituída em {} testes", label, suite.tests.len());
+        Ok(suite.tests.len())
+    }
+
+    /// Valida os campos obrigatórios de um teste antes de salvá-lo
+    pub fn validate(test: &FullTestFromAI) -> Result<(), String> {
+        if test.id.trim().is_empty() {
+            return Err("O ID do teste não pode ser vazio".to_string());
+        }
+
+        if test.name.trim().is_empty() {
+            return Err("O nome do teste não pode ser vazio".to_string());
+        }
+
+        if test.timeout == Some(0) {
+            return Err("O timeout do teste deve ser maior que zero".to_string());
+        }
+
+        if let Some(parameters) = &test.parameters {
+            parameters.validate()?;
+        }
+
+        Self::validate_exec(&test.legacy_exec, "legado")?;
+        Self::validate_exec(&test.new_exec, "novo")?;
+
+        Ok(())
+    }
+
+    fn validate_exec(exec: &ExecutionInfo, label: &str) -> Result<(), String> {
+        if exec.exec_type.trim().is_empty() {
+            return Err(format!("Tipo de execução do {} não informado", label));
+        }
+
+        if exec.execute_command.trim().is_empty() {
+            return Err(format!("Comando de execução do {} não pode ser vazio", label));
+        }
+
+        if exec.compile_command.as_deref().is_some_and(|c| c.trim().is_empty()) {
+            return Err(format!("Comando de compilação do {} está vazio (use null para nenhum)", label));
+        }
+
+        if !Path::new(&exec.working_directory).is_dir() {
+            return Err(format!("Diretório de trabalho do {} não existe: {}", label, exec.working_directory));
+        }
+
+        Ok(())
+    }
+
+    fn suite_path(project_id: &str, app: &tauri::AppHandle) -> Result<PathBuf, String> {
+        Ok(ProjectService::project_data_dir(project_id, app)?.join("tests.json"))
+    }
+}
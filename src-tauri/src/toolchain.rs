@@ -0,0 +1,410 @@
+use crate::ai::{ExecutionInfo, FullTestFromAI, ProjectInfo};
+use crate::paths::shell_quote;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Palavras que aparecem no início de um comando mas não são ferramentas a verificar
+const SHELL_BUILTINS: &[&str] = &[
+    "cd", "echo", "export", "set", "unset", "true", "false", "exit", "test", "[", ":",
+    "source", ".", "pwd", "read", "printf", "shift", "return", "wait", "ulimit", "umask", "trap", "eval",
+];
+
+/// Palavras reservadas e prefixos: o comando de verdade é a palavra seguinte
+const SHELL_PREFIXES: &[&str] = &[
+    "if", "then", "else", "elif", "fi", "while", "until", "do", "done", "esac", "{", "}", "!",
+    "time", "exec", "env", "command", "nohup",
+];
+
+/// Construções cujas palavras não são comandos (`for x in a b`, `case $x in`)
+const SHELL_LISTS: &[&str] = &["for", "case", "select", "function"];
+
+/// Ferramentas fixadas para um lado do projeto (`legacyToolchain`/`newToolchain`),
+/// usadas no lugar do que o `sh` encontraria no PATH
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ToolchainConfig {
+    /// Caminho explícito por ferramenta (ex.: "python3" -> "/opt/python2.7/bin/python")
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tools: BTreeMap<String, String>,
+    /// Diretórios procurados antes do PATH do sistema
+    #[serde(rename = "extraPath", default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_path: Vec<String>,
+    /// Flags acrescentadas sempre que a ferramenta é chamada (ex.: "gcc" -> ["-std=c89"])
+    #[serde(rename = "compilerFlags", default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub compiler_flags: BTreeMap<String, Vec<String>>,
+}
+
+impl ToolchainConfig {
+    /// Configuração do lado "legacy" ou "new" do projeto (vazia se não houver)
+    pub fn for_side(project: &ProjectInfo, side: &str) -> Self {
+        let config = if side == "legacy" { &project.legacy_toolchain } else { &project.new_toolchain };
+        config.clone().unwrap_or_default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tools.is_empty() && self.extra_path.is_empty() && self.compiler_flags.is_empty()
+    }
+
+    /// Caminho efetivo de uma ferramenta: o fixado, ou o primeiro encontrado em
+    /// `extraPath` e depois no PATH
+    pub fn resolve(&self, tool: &str) -> Option<PathBuf> {
+        if let Some(path) = self.tools.get(tool) {
+            let path = PathBuf::from(path);
+            return ToolchainService::is_executable_file(&path).then_some(path);
+        }
+
+        let extra = self.extra_path.iter().map(PathBuf::from);
+        let system = env::var_os("PATH").map(|p| env::split_paths(&p).collect::<Vec<_>>()).unwrap_or_default();
+
+        extra.chain(system)
+            .flat_map(|dir| {
+                let candidate = dir.join(tool);
+                if cfg!(windows) {
+                    vec![candidate.with_extension("exe"), candidate.with_extension("cmd"), candidate]
+                } else {
+                    vec![candidate]
+                }
+            })
+            .find(|candidate| ToolchainService::is_executable_file(candidate))
+    }
+
+    /// Flags configuradas para a ferramenta
+    pub fn flags(&self, tool: &str) -> &[String] {
+        self.compiler_flags.get(tool).map(|f| f.as_slice()).unwrap_or(&[])
+    }
+
+    /// Valida paths fixados e diretórios extras
+    pub fn validate(&self, label: &str) -> Result<(), String> {
+        for (tool, path) in &self.tools {
+            if !ToolchainService::is_executable_file(Path::new(path)) {
+                return Err(format!("Ferramenta '{}' do {} não é um executável: {}", tool, label, path));
+            }
+        }
+
+        for dir in &self.extra_path {
+            if !Path::new(dir).is_dir() {
+                return Err(format!("Diretório extra do PATH do {} não existe: {}", label, dir));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Cria em `shim_dir` um script por ferramenta fixada ou com flags, que chama o
+    /// executável certo com as flags. Retorna o PATH a usar nos comandos dos testes
+    /// (shims, `extraPath` e o PATH do sistema, nessa ordem).
+    pub fn install_shims(&self, shim_dir: &Path) -> Result<OsString, String> {
+        if shim_dir.exists() {
+            fs::remove_dir_all(shim_dir)
+                .map_err(|e| format!("Failed to reset toolchain directory {:?}: {}", shim_dir, e))?;
+        }
+        fs::create_dir_all(shim_dir)
+            .map_err(|e| format!("Failed to create toolchain directory {:?}: {}", shim_dir, e))?;
+
+        let names: Vec<&String> = self.tools.keys()
+            .chain(self.compiler_flags.keys().filter(|k| !self.tools.contains_key(*k)))
+            .collect();
+
+        for name in names {
+            let target = self.resolve(name)
+                .ok_or(format!("Ferramenta '{}' não encontrada para o toolchain configurado", name))?;
+
+            let mut script = format!("#!/bin/sh\nexec {}", shell_quote(&target.to_string_lossy()));
+            for flag in self.flags(name) {
+                script.push(' ');
+                script.push_str(&shell_quote(flag));
+            }
+            script.push_str(" \"$@\"\n");
+
+            let shim = shim_dir.join(name);
+            fs::write(&shim, script)
+                .map_err(|e| format!("Failed to write toolchain shim {:?}: {}", shim, e))?;
+
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(&shim, fs::Permissions::from_mode(0o755))
+                    .map_err(|e| format!("Failed to chmod toolchain shim {:?}: {}", shim, e))?;
+            }
+        }
+
+        let mut dirs = vec![shim_dir.to_path_buf()];
+        dirs.extend(self.extra_path.iter().map(PathBuf::from));
+        if let Some(system) = env::var_os("PATH") {
+            dirs.extend(env::split_paths(&system));
+        }
+
+        env::join_paths(dirs).map_err(|e| format!("PATH inválido: {}", e))
+    }
+}
+
+/// Situação de um compilador/interpretador na máquina
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ToolStatus {
+    pub name: String,
+    /// "legacy" ou "new"
+    pub side: String,
+    /// Tipos de execução (`ExecutionInfo.exec_type`) ou comandos que usam a ferramenta
+    #[serde(rename = "requiredBy")]
+    pub required_by: Vec<String>,
+    pub present: bool,
+    pub path: Option<String>,
+    pub version: Option<String>,
+}
+
+/// Relatório das ferramentas exigidas por um conjunto de testes
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ToolchainReport {
+    pub tools: Vec<ToolStatus>,
+    pub warnings: Vec<String>,
+}
+
+impl ToolchainReport {
+    /// Versões das ferramentas encontradas, por lado (gravadas em cada execução)
+    pub fn versions(&self) -> BTreeMap<String, String> {
+        self.tools.iter()
+            .filter(|t| t.present)
+            .map(|t| (format!("{}/{}", t.side, t.name), t.version.clone().unwrap_or_default()))
+            .collect()
+    }
+
+    /// Ferramentas exigidas que não foram encontradas (`lado/nome`)
+    pub fn missing(&self) -> Vec<String> {
+        self.tools.iter()
+            .filter(|t| !t.present)
+            .map(|t| format!("{}/{}", t.side, t.name))
+            .collect()
+    }
+
+    /// Ferramentas ausentes exigidas pelo bloco de execução de um lado
+    pub fn missing_for(&self, side: &str, exec: &ExecutionInfo) -> Vec<String> {
+        ToolchainService::required_tools(exec).into_iter()
+            .filter(|tool| self.tools.iter().any(|t| !t.present && t.side == side && &t.name == tool))
+            .collect()
+    }
+}
+
+/// Descoberta dos compiladores e interpretadores usados pelos testes
+pub struct ToolchainService;
+
+impl ToolchainService {
+    /// Verifica as ferramentas exigidas pelos testes nos lados informados ("legacy"/"new"),
+    /// levando em conta o toolchain configurado de cada lado
+    pub fn check(project: &ProjectInfo, tests: &[FullTestFromAI], sides: &[&str]) -> ToolchainReport {
+        let mut report = ToolchainReport::default();
+
+        for side in sides {
+            let config = ToolchainConfig::for_side(project, side);
+            let label = if *side == "legacy" { "legado" } else { "novo" };
+            let mut required: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+            for test in tests {
+                let exec = if *side == "legacy" { &test.legacy_exec } else { &test.new_exec };
+                for tool in Self::required_tools(exec) {
+                    let users = required.entry(tool).or_default();
+                    if !users.contains(&exec.exec_type) {
+                        users.push(exec.exec_type.clone());
+                    }
+                }
+            }
+
+            for (name, required_by) in required {
+                let path = config.resolve(&name);
+                let version = path.as_deref().and_then(Self::version);
+                // Fora do toolchain fixado, só falta o que o próprio `sh` não acha
+                let known = path.is_some() || (!config.tools.contains_key(&name) && Self::shell_knows(&name).is_some());
+
+                if !known {
+                    report.warnings.push(format!(
+                        "'{}' não encontrado para o {} (usado por: {})",
+                        name,
+                        label,
+                        required_by.join(", ")
+                    ));
+                }
+
+                report.tools.push(ToolStatus {
+                    name,
+                    side: side.to_string(),
+                    required_by,
+                    present: known,
+                    path: path.map(|p| p.to_string_lossy().to_string()),
+                    version,
+                });
+            }
+        }
+
+        for warning in &report.warnings {
+            println!("⚠️ {}", warning);
+        }
+
+        report
+    }
+
+    /// Ferramentas que um bloco de execução precisa: as implícitas no tipo de
+    /// execução mais as chamadas diretamente nos comandos
+    pub fn required_tools(exec: &ExecutionInfo) -> Vec<String> {
+        let commands = format!("{} {}", exec.compile_command.as_deref().unwrap_or(""), exec.execute_command);
+        let source_ext = Path::new(&exec.source_file).extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("");
+
+        let tools: Vec<&str> = match exec.exec_type.to_lowercase().as_str() {
+            "c_compiled" | "c" if matches!(source_ext, "cpp" | "cc" | "cxx") => vec!["g++"],
+            "c_compiled" | "c" => vec!["gcc"],
+            "cpp" | "c++" => vec!["g++"],
+            "python" => vec!["python3"],
+            "node" | "javascript" | "typescript" => vec!["node"],
+            "java" => vec!["javac", "java"],
+            "rust" if commands.contains("cargo") => vec!["cargo"],
+            "rust" => vec!["rustc"],
+            "go" => vec!["go"],
+            "cobol" => vec!["cobc"],
+            "fortran" => vec!["gfortran"],
+            "pascal" => vec!["fpc"],
+            "perl" => vec!["perl"],
+            "cargo" => vec!["cargo"],
+            "make" => vec!["make"],
+            "cmake" => vec!["cmake"],
+            "maven" => vec!["mvn", "java"],
+            "gradle" if commands.contains("./gradlew") => vec!["java"],
+            "gradle" => vec!["gradle", "java"],
+            "npm" => vec!["npm", "node"],
+            _ => Vec::new(),
+        };
+
+        let mut unique: Vec<String> = tools.into_iter().map(|t| t.to_string()).collect();
+        for command in [exec.compile_command.as_deref().unwrap_or(""), exec.execute_command.as_str()] {
+            for tool in Self::command_names(command) {
+                if !unique.contains(&tool) {
+                    unique.push(tool);
+                }
+            }
+        }
+
+        unique
+    }
+
+    /// Programa chamado por cada comando simples de uma linha de shell, sem
+    /// atribuições, redirecionamentos (`2>&1`, `> out`), parênteses de subshell,
+    /// palavras reservadas, builtins e caminhos/variáveis
+    fn command_names(line: &str) -> Vec<String> {
+        let mut commands: Vec<Vec<String>> = vec![Vec::new()];
+        let mut word = String::new();
+        let mut chars = line.chars().peekable();
+        let mut quote: Option<char> = None;
+        let mut skip_target = false;
+
+        // Fecha a palavra atual (ou a descarta se for alvo de redirecionamento)
+        let finish = |word: &mut String, commands: &mut Vec<Vec<String>>, skip_target: &mut bool| {
+            if !word.is_empty() {
+                if !std::mem::take(skip_target) {
+                    commands.last_mut().unwrap().push(word.clone());
+                }
+                word.clear();
+            }
+        };
+
+        while let Some(c) = chars.next() {
+            match (quote, c) {
+                (Some(q), c) if c == q => quote = None,
+                (Some(_), '\\') => { word.push(c); word.extend(chars.next()); },
+                (Some(_), c) => word.push(c),
+                (None, '\'' | '"') => { quote = Some(c); word.push(c); },
+                (None, '\\') => { word.push(c); word.extend(chars.next()); },
+                (None, c) if c.is_whitespace() && c != '\n' => finish(&mut word, &mut commands, &mut skip_target),
+                (None, '>' | '<') => {
+                    // `2>`: o número do descritor faz parte do redirecionamento
+                    if word.chars().all(|d| d.is_ascii_digit()) {
+                        word.clear();
+                    } else {
+                        finish(&mut word, &mut commands, &mut skip_target);
+                    }
+                    while chars.next_if(|n| matches!(n, '>' | '<' | '&')).is_some() {}
+                    // `>&1`, `>&-`: o alvo é um descritor, colado ao operador
+                    skip_target = true;
+                },
+                (None, ';' | '&' | '|' | '(' | ')' | '\n') => {
+                    finish(&mut word, &mut commands, &mut skip_target);
+                    commands.push(Vec::new());
+                },
+                (None, c) => word.push(c),
+            }
+        }
+        finish(&mut word, &mut commands, &mut skip_target);
+
+        commands.into_iter()
+            .filter_map(|words| {
+                let mut words = words.into_iter()
+                    .skip_while(|w| SHELL_PREFIXES.contains(&w.as_str()) || Self::is_assignment(w));
+                let first = words.next()?;
+                (!SHELL_LISTS.contains(&first.as_str())).then_some(first)
+            })
+            .filter(|tool| {
+                !tool.contains('/')
+                    && !tool.contains(['$', '`', '\'', '"', '='])
+                    && !SHELL_BUILTINS.contains(&tool.as_str())
+                    && !SHELL_LISTS.contains(&tool.as_str())
+            })
+            .collect()
+    }
+
+    /// `NOME=valor` no início de um comando
+    fn is_assignment(word: &str) -> bool {
+        word.split_once('=').is_some_and(|(name, _)| {
+            !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        })
+    }
+
+    /// O `sh` conhece o nome (builtin, função ou programa fora do PATH do app)?
+    fn shell_knows(name: &str) -> Option<String> {
+        let output = Command::new("sh")
+            .arg("-c")
+            .arg("command -v \"$1\"")
+            .arg("sh")
+            .arg(name)
+            .output()
+            .ok()?;
+
+        output.status.success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    pub fn is_executable_file(path: &Path) -> bool {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            path.metadata()
+                .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+                .unwrap_or(false)
+        }
+        #[cfg(not(unix))]
+        {
+            path.is_file()
+        }
+    }
+
+    /// Primeira linha da saída de versão da ferramenta
+    fn version(path: &Path) -> Option<String> {
+        let name = path.file_stem().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let arg = match name.as_str() {
+            "go" => "version",
+            "java" | "javac" => "-version",
+            _ => "--version",
+        };
+
+        let output = Command::new(path).arg(arg).output().ok()?;
+
+        // java/javac escrevem a versão no stderr
+        let text = if output.stdout.is_empty() { output.stderr } else { output.stdout };
+        String::from_utf8_lossy(&text)
+            .lines()
+            .map(|line| line.trim())
+            .find(|line| !line.is_empty())
+            .map(|line| line.to_string())
+    }
+}
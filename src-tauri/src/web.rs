@@ -0,0 +1,381 @@
+use crate::ai::{FullTestFromAI, ProjectInfo, TestCase};
+use crate::api::ApiRunner;
+use crate::build_cache::BuildCache;
+use crate::history::TestRunRecord;
+use regex::Regex;
+use scraper::{ElementRef, Html, Node};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+/// Elementos cujo conteúdo não é texto visível
+const INVISIBLE_ELEMENTS: [&str; 5] = ["script", "style", "noscript", "template", "head"];
+
+/// Atributos que mudam a cada requisição ou build
+const VOLATILE_ATTRIBUTES: [&str; 3] = ["nonce", "integrity", "data-csrf"];
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+/// Página visitada por um teste de projeto Web (`pages` no teste)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PageStep {
+    #[serde(default = "default_method")]
+    pub method: String,
+    /// Caminho com query string (ex.: "/login?next=/home")
+    pub path: String,
+    /// Campos enviados como formulário; campos ocultos da página anterior
+    /// (ex.: token CSRF) são incluídos automaticamente
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub form: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+}
+
+/// Página obtida de um dos lados, já normalizada
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PageCapture {
+    pub path: String,
+    /// Caminho final, depois dos redirecionamentos
+    #[serde(rename = "finalPath")]
+    pub final_path: String,
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Texto visível, uma linha por bloco de texto
+    pub text: String,
+    /// Estrutura do DOM (uma linha por elemento, indentada pela profundidade)
+    #[serde(skip)]
+    pub dom: Vec<String>,
+    #[serde(skip)]
+    hidden_fields: BTreeMap<String, String>,
+}
+
+impl PageCapture {
+    pub fn display_output(&self) -> String {
+        format!("HTTP {} {}\n\n{}", self.status, self.final_path, self.text)
+    }
+}
+
+/// Visita as mesmas páginas nos dois apps e compara DOM normalizado e texto visível
+pub struct WebRunner;
+
+impl WebRunner {
+    /// Sobe os dois apps (configurados em `apiConfig`) e executa os passos de
+    /// cada teste com uma sessão (cookies) nova por teste
+    pub async fn run_tests(
+        project: &ProjectInfo,
+        tests: &[FullTestFromAI],
+        legacy_cache: &BuildCache,
+        new_cache: &BuildCache
+    ) -> Result<Vec<TestRunRecord>, String> {
+        let config = project.api_config.as_ref()
+            .ok_or("Projeto do tipo Web sem servidores configurados (apiConfig)")?;
+        let probe_client = reqwest::Client::new();
+
+        let legacy_server = ApiRunner::start(&config.legacy, &project.legacy_path, legacy_cache, &probe_client, "legado").await?;
+        let new_server = ApiRunner::start(&config.new, &project.new_path, new_cache, &probe_client, "novo").await?;
+
+        let mut executed_tests = Vec::new();
+
+        for (i, test) in tests.iter().enumerate() {
+            println!("⚡ [{}/{}] Páginas: {}", i + 1, tests.len(), test.name);
+            let start_time = Instant::now();
+
+            let steps = match &test.pages {
+                Some(steps) if !steps.is_empty() => steps,
+                _ => {
+                    executed_tests.push(TestRunRecord::not_run(test, "Teste sem páginas a visitar (pages)"));
+                    continue;
+                }
+            };
+
+            let timeout = Duration::from_millis(test.timeout.unwrap_or(30000));
+            let legacy_result = Self::visit(legacy_server.base_url(), steps, timeout, &config.ignore_attributes).await;
+            let new_result = Self::visit(new_server.base_url(), steps, timeout, &config.ignore_attributes).await;
+
+            let record = match (legacy_result, new_result) {
+                (Ok(legacy_pages), Ok(new_pages)) => {
+                    let differences: Vec<String> = legacy_pages.iter().zip(new_pages.iter())
+                        .flat_map(|(legacy, new)| Self::compare(legacy, new))
+                        .collect();
+                    for difference in &differences {
+                        println!("   ↳ {}", difference);
+                    }
+
+                    let success = differences.is_empty();
+                    println!("{} Teste {} - Status: {}", if success { "✅" } else { "❌" }, test.name, if success { "PASS" } else { "FAIL" });
+
+                    TestRunRecord {
+                        result: TestCase {
+                            id: test.id.clone(),
+                            name: test.name.clone(),
+                            description: test.description.clone(),
+                            full_code: test.full_code.clone(),
+                            status: if success { "passed" } else { "failed" }.to_string(),
+                            execution_time: Some(start_time.elapsed().as_millis() as u64),
+                            legacy_output: legacy_pages.last().map(|p| p.display_output()),
+                            new_output: new_pages.last().map(|p| p.display_output()),
+                        },
+                        legacy_pages,
+                        new_pages,
+                        differences,
+                        ..Default::default()
+                    }
+                },
+                (Err(legacy_error), Err(new_error)) => TestRunRecord::not_run(
+                    test,
+                    &format!("Erro no legado: {}\nErro no novo: {}", legacy_error, new_error)
+                ),
+                // Só um lado falhou na conexão: é uma divergência de comportamento
+                (Ok(legacy_pages), Err(e)) => Self::transport_failure(test, start_time, legacy_pages, Vec::new(), format!("app novo não respondeu: {}", e)),
+                (Err(e), Ok(new_pages)) => Self::transport_failure(test, start_time, Vec::new(), new_pages, format!("app legado não respondeu: {}", e)),
+            };
+
+            executed_tests.push(record);
+        }
+
+        Ok(executed_tests)
+    }
+
+    /// Registro de um teste em que só um dos apps respondeu; o erro de transporte
+    /// é a diferença e aparece no lugar da página do lado que falhou
+    fn transport_failure(
+        test: &FullTestFromAI,
+        start_time: Instant,
+        legacy_pages: Vec<PageCapture>,
+        new_pages: Vec<PageCapture>,
+        difference: String
+    ) -> TestRunRecord {
+        println!("   ↳ {}", difference);
+        println!("❌ Teste {} - Status: FAIL", test.name);
+
+        TestRunRecord {
+            result: TestCase {
+                id: test.id.clone(),
+                name: test.name.clone(),
+                description: test.description.clone(),
+                full_code: test.full_code.clone(),
+                status: "failed".to_string(),
+                execution_time: Some(start_time.elapsed().as_millis() as u64),
+                legacy_output: Some(legacy_pages.last().map(|p| p.display_output()).unwrap_or_else(|| difference.clone())),
+                new_output: Some(new_pages.last().map(|p| p.display_output()).unwrap_or_else(|| difference.clone())),
+            },
+            legacy_pages,
+            new_pages,
+            differences: vec![difference],
+            ..Default::default()
+        }
+    }
+
+    /// Executa os passos em sequência contra um app, mantendo cookies entre eles
+    async fn visit(
+        base_url: &str,
+        steps: &[PageStep],
+        timeout: Duration,
+        ignore_attributes: &[String]
+    ) -> Result<Vec<PageCapture>, String> {
+        let client = reqwest::Client::builder()
+            .cookie_store(true)
+            .timeout(timeout)
+            .build()
+            .map_err(|e| format!("Erro ao criar cliente HTTP: {}", e))?;
+
+        let mut pages: Vec<PageCapture> = Vec::new();
+
+        for step in steps {
+            let method = reqwest::Method::from_bytes(step.method.to_uppercase().as_bytes())
+                .map_err(|_| format!("Método HTTP inválido: {}", step.method))?;
+            let url = format!("{}{}", base_url, step.path);
+
+            let mut builder = client.request(method, &url);
+            for (name, value) in &step.headers {
+                builder = builder.header(name, value);
+            }
+
+            if !step.form.is_empty() {
+                let mut form = pages.last().map(|p| p.hidden_fields.clone()).unwrap_or_default();
+                form.extend(step.form.clone());
+                builder = builder.form(&form);
+            }
+
+            let response = builder.send().await
+                .map_err(|e| format!("Erro na requisição {} {}: {}", step.method, url, e))?;
+            let status = response.status().as_u16();
+            let final_path = match response.url().query() {
+                Some(query) => format!("{}?{}", response.url().path(), query),
+                None => response.url().path().to_string(),
+            };
+            let body = response.text().await
+                .map_err(|e| format!("Erro lendo página {}: {}", url, e))?;
+
+            pages.push(Self::capture(&step.path, &final_path, status, &body, ignore_attributes));
+        }
+
+        Ok(pages)
+    }
+
+    /// Diferenças entre as páginas dos dois lados
+    pub fn compare(legacy: &PageCapture, new: &PageCapture) -> Vec<String> {
+        let mut differences = Vec::new();
+        let page = &legacy.path;
+
+        if legacy.status != new.status {
+            differences.push(format!("{}: status {} != {}", page, legacy.status, new.status));
+        }
+
+        if legacy.final_path != new.final_path {
+            differences.push(format!("{}: redirecionou para {} != {}", page, legacy.final_path, new.final_path));
+        }
+
+        if legacy.title != new.title {
+            differences.push(format!(
+                "{}: título {:?} != {:?}",
+                page,
+                legacy.title.as_deref().unwrap_or(""),
+                new.title.as_deref().unwrap_or("")
+            ));
+        }
+
+        let legacy_text: Vec<&str> = legacy.text.lines().collect();
+        let new_text: Vec<&str> = new.text.lines().collect();
+        if let Some(difference) = Self::first_difference(&legacy_text, &new_text) {
+            differences.push(format!("{}: texto {}", page, difference));
+        }
+
+        let legacy_dom: Vec<&str> = legacy.dom.iter().map(|l| l.as_str()).collect();
+        let new_dom: Vec<&str> = new.dom.iter().map(|l| l.as_str()).collect();
+        if let Some(difference) = Self::first_difference(&legacy_dom, &new_dom) {
+            differences.push(format!("{}: DOM {}", page, difference));
+        }
+
+        differences
+    }
+
+    /// Normaliza a página: estrutura do DOM sem atributos voláteis e texto visível
+    pub fn capture(path: &str, final_path: &str, status: u16, body: &str, ignore_attributes: &[String]) -> PageCapture {
+        let document = Html::parse_document(body);
+        let mut dom = Vec::new();
+        let mut text = Vec::new();
+        let mut hidden_fields = BTreeMap::new();
+
+        Self::walk(document.root_element(), 0, false, ignore_attributes, &mut dom, &mut text, &mut hidden_fields);
+
+        let title = scraper::Selector::parse("title").ok()
+            .and_then(|selector| document.select(&selector).next())
+            .map(|title| Self::collapse_whitespace(&title.text().collect::<String>()))
+            .filter(|title| !title.is_empty());
+
+        PageCapture {
+            path: path.to_string(),
+            final_path: final_path.to_string(),
+            status,
+            title,
+            text: text.join("\n"),
+            dom,
+            hidden_fields,
+        }
+    }
+
+    fn walk(
+        element: ElementRef,
+        depth: usize,
+        invisible: bool,
+        ignore_attributes: &[String],
+        dom: &mut Vec<String>,
+        text: &mut Vec<String>,
+        hidden_fields: &mut BTreeMap<String, String>
+    ) {
+        let name = element.value().name();
+        let invisible = invisible || INVISIBLE_ELEMENTS.contains(&name);
+
+        if name == "input" && element.value().attr("type").is_some_and(|t| t.eq_ignore_ascii_case("hidden")) {
+            if let Some(field) = element.value().attr("name") {
+                hidden_fields.insert(field.to_string(), element.value().attr("value").unwrap_or("").to_string());
+            }
+        }
+
+        dom.push(format!("{}{}", "  ".repeat(depth), Self::describe_element(element, ignore_attributes)));
+
+        for child in element.children() {
+            match child.value() {
+                Node::Element(_) => {
+                    if let Some(child) = ElementRef::wrap(child) {
+                        Self::walk(child, depth + 1, invisible, ignore_attributes, dom, text, hidden_fields);
+                    }
+                },
+                Node::Text(content) if !invisible => {
+                    let content = Self::collapse_whitespace(content);
+                    if !content.is_empty() {
+                        text.push(content);
+                    }
+                },
+                _ => {}
+            }
+        }
+    }
+
+    /// `<tag attr="valor">` com atributos ordenados e valores voláteis removidos
+    fn describe_element(element: ElementRef, ignore_attributes: &[String]) -> String {
+        let value = element.value();
+        let csrf_field = ["name", "id"].iter()
+            .filter_map(|attr| value.attr(attr))
+            .any(Self::is_token_name);
+
+        let mut attributes: Vec<String> = value.attrs()
+            .filter(|(name, _)| !VOLATILE_ATTRIBUTES.contains(name) && !Self::is_token_name(name))
+            .filter(|(name, _)| !ignore_attributes.iter().any(|ignored| ignored.eq_ignore_ascii_case(name)))
+            .map(|(name, attr_value)| {
+                let attr_value = match name {
+                    "value" | "content" if csrf_field => "*".to_string(),
+                    "class" => {
+                        let mut classes: Vec<&str> = attr_value.split_whitespace().collect();
+                        classes.sort();
+                        classes.join(" ")
+                    },
+                    "src" | "href" => Self::strip_asset_hash(attr_value),
+                    _ => attr_value.to_string(),
+                };
+                format!("{}=\"{}\"", name, attr_value)
+            })
+            .collect();
+        attributes.sort();
+
+        if attributes.is_empty() {
+            format!("<{}>", value.name())
+        } else {
+            format!("<{} {}>", value.name(), attributes.join(" "))
+        }
+    }
+
+    /// Campos e atributos de token anti-CSRF (`csrf_token`, `authenticity_token`, `_token`...)
+    fn is_token_name(name: &str) -> bool {
+        let name = name.to_lowercase();
+        name.contains("csrf") || name.contains("xsrf") || name.ends_with("_token") || name == "token"
+    }
+
+    /// `app.3f9a8c1b.js` → `app.js`, `style.css?v=123` → `style.css`
+    fn strip_asset_hash(url: &str) -> String {
+        let hash = Regex::new(r"[.-][0-9a-fA-F]{8,}(\.[A-Za-z0-9]+)").unwrap();
+        let version = Regex::new(r"\?(v|ver|version|hash|h)=[^&#]*$").unwrap();
+
+        let url = hash.replace_all(url, "$1");
+        version.replace(&url, "").to_string()
+    }
+
+    fn collapse_whitespace(text: &str) -> String {
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    /// Primeira linha diferente entre as duas listas (com a posição)
+    fn first_difference(legacy: &[&str], new: &[&str]) -> Option<String> {
+        let position = legacy.iter().zip(new.iter()).position(|(a, b)| a != b);
+
+        match position {
+            Some(i) => Some(format!("difere na linha {}: {:?} != {:?}", i + 1, legacy[i], new[i])),
+            None if legacy.len() != new.len() => Some(format!("{} linhas != {} linhas", legacy.len(), new.len())),
+            None => None,
+        }
+    }
+}
@@ -124,6 +124,9 @@ const ExecutionHistory: React.FC<ExecutionHistoryProps> = ({
                       {execution.failedTests > 0 && (
                         <span className="text-red-400">{execution.failedTests} failed</span>
                       )}
+                      {(execution.notRunTests ?? 0) > 0 && (
+                        <span className="text-yellow-400">{execution.notRunTests} not run</span>
+                      )}
                     </div>
                   </div>
 
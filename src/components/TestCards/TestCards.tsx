@@ -21,6 +21,8 @@ const TestCards: React.FC<TestCardsProps> = ({
           return "text-green-400";
         case "failed":
           return "text-red-400";
+        case "not_run":
+          return "text-yellow-400";
         default:
           return "text-gray-400";
       }
@@ -36,6 +38,8 @@ const TestCards: React.FC<TestCardsProps> = ({
         return "border-green-500";
       case "failed":
         return "border-red-500";
+      case "not_run":
+        return "border-yellow-500";
       default:
         return "border-gray-600";
     }
@@ -20,6 +20,8 @@ const TestDetails: React.FC<TestDetailsProps> = ({ test, projectId }) => {
         return "text-green-400";
       case "failed":
         return "text-red-400";
+      case "not_run":
+        return "text-yellow-400";
       default:
         return "text-gray-400";
     }
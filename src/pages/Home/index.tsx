@@ -5,8 +5,6 @@ import RecentProjects from '../../components/RecentProjects/RecentProjects';
 import { NewProjectData, Project } from '../../types';
 import projectService from '../../services/ProjectService';
 import { confirm } from '@tauri-apps/plugin-dialog';
-import TestService from '../../services/TestService';
-import ExecutionService from '../../services/ExecutionService';
 
 const Home = () => {
   const navigate = useNavigate();
@@ -57,9 +55,8 @@ const Home = () => {
 
     if (confirmed) {
       try {
+        // O backend apaga projects/<id>/ inteiro (testes, execuções e snapshots)
         await projectService.deleteProject(projectId);
-        await TestService.deleteAllTestsFromProject(projectId);
-        await ExecutionService.deleteAllExecutionsFromProject(projectId);
         setProjects(prev => prev.filter(p => p.id !== projectId));
       } catch (error) {
       }
@@ -3,10 +3,9 @@ import { useParams, useNavigate } from "react-router-dom";
 import Sidebar from "../../components/Sidebar/Sidebar";
 import TestHeader from "../../components/Header/Header";
 import TestCards from "../../components/TestCards/TestCards";
-import type { Project, TestCase } from "../../types";
+import type { Project, TestCase, TestExecutionResponse, ToolchainReport } from "../../types";
 import ProjectService from "../../services/ProjectService";
 import TestService from "../../services/TestService";
-import ExecutionService from "../../services/ExecutionService";
 import { invoke } from "@tauri-apps/api/core";
 
 
@@ -77,31 +76,28 @@ const Tests = () => {
   const handleRunTests = async () => {
     if (!projectId || !currentProject) return;
 
+    // Avisa antes de rodar se faltar algum compilador/interpretador usado pelos testes
+    try {
+      const toolchain = await invoke<ToolchainReport>('check_toolchains', { projectId });
+      if (toolchain.warnings.length > 0 &&
+          !window.confirm(`Ferramentas ausentes:\n${toolchain.warnings.join('\n')}\n\nExecutar mesmo assim?`)) {
+        return;
+      }
+    } catch (error) {
+      console.error('Erro ao verificar ferramentas:', error);
+    }
+
     setIsRunning(true);
 
     try {
       setTests(tests.map(test => ({ ...test, status: "running" as const, executionTime: 0 })));
 
-      // Backend carrega FullTestFromAI automaticamente
-      const executionResponse = await invoke<TestCase[]>('execute_tests', {
+      // Backend carrega FullTestFromAI, guarda os resultados e registra a execução no histórico
+      const executionResponse = await invoke<TestExecutionResponse>('execute_tests', {
         projectId: projectId
       });
 
-      await TestService.saveTestResults(projectId, {
-        projectId,
-        executionId: crypto.randomUUID(),
-        testResults: executionResponse,
-        executedAt: new Date().toISOString(),
-        totalExecutionTime: executionResponse.reduce((acc, test) => acc + (test.executionTime || 0), 0)
-      });
-
-      await ExecutionService.saveExecution(
-        projectId,
-        currentProject.name,
-        executionResponse
-      );
-
-      setTests(executionResponse);
+      setTests(executionResponse.testResults);
     } catch (error) {
       console.error('Erro ao executar testes:', error);
       setTests(tests.map(test => ({ ...test, status: "failed" as const })));
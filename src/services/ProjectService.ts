@@ -1,112 +1,113 @@
-import { Project, NewProjectData } from '../types';
-import { readTextFile, writeTextFile, exists, create } from '@tauri-apps/plugin-fs';
-import { appDataDir, join } from '@tauri-apps/api/path';
+import { Project, NewProjectData, ProjectBundleManifest, ProjectBundlePreview, BuildPlan, BuildArtifact, ProjectBuildSystems, FullTestFromAI, ToolchainConfig, EntryPoint, EntryPointCandidate, ProjectSummary, ApiConfig, Comparator } from '../types';
+import { invoke } from '@tauri-apps/api/core';
 
-const PROJECTS_FILE = 'projects.json';
+// Os projetos são mantidos pelo backend (`projects.json` em app data)
+const fromBackend = (project: any): Project => ({
+  ...project,
+  lastOpened: project.lastOpened ? new Date(project.lastOpened) : undefined
+});
 
 class ProjectService {
-  private async getProjectsFilePath(): Promise<string> {
-    const appDir = await appDataDir();
-    return await join(appDir, PROJECTS_FILE);
-  }
-
-  private async ensureAppDataDir(): Promise<void> {
+  async loadProjects(): Promise<Project[]> {
     try {
-      const appDir = await appDataDir();
-      const dirExists = await exists(appDir);
-      if (!dirExists) {
-        await create(appDir);
-      }
+      const projects = await invoke<any[]>('list_projects');
+      return projects.map(fromBackend);
     } catch (error) {
-      console.error('Error ensuring app data directory:', error);
+      console.error('Error loading projects:', error);
+      return [];
     }
   }
 
-  async loadProjects(): Promise<Project[]> {
-    try {
-      await this.ensureAppDataDir();
-      const filePath = await this.getProjectsFilePath();
+  async saveProject(projectData: NewProjectData): Promise<Project> {
+    const project = await invoke<any>('create_project', {
+      name: projectData.name,
+      legacyPath: projectData.legacyPath,
+      newPath: projectData.newPath,
+      projectType: projectData.type
+    });
+
+    return fromBackend(project);
+  }
 
-      const fileExists = await exists(filePath);
-      if (!fileExists) {
-        return [];
-      }
+  async deleteProject(id: string): Promise<void> {
+    await invoke('delete_project', { projectId: id });
+  }
 
-      try {
-        const content = await readTextFile(filePath);
-        const projects = JSON.parse(content);
-
-        if (!Array.isArray(projects)) {
-          console.warn('Projects file is not in expected format, returning empty array');
-          return [];
-        }
-
-        return projects.map((project: any) => ({
-          ...project,
-          lastOpened: project.lastOpened ? new Date(project.lastOpened) : undefined
-        }));
-      } catch (parseError) {
-        console.warn('Error parsing projects file, returning empty array:', parseError);
-        return [];
+  async updateProject(updatedProject: Project): Promise<void> {
+    await invoke('update_project', {
+      project: {
+        ...updatedProject,
+        lastOpened: updatedProject.lastOpened?.toISOString()
       }
-    } catch (error) {
-      return [];
-    }
+    });
   }
 
-  async saveProject(projectData: NewProjectData): Promise<Project> {
-    try {
-      const newProject: Project = {
-        id: crypto.randomUUID(),
-        name: projectData.name,
-        type: projectData.type,
-        legacyPath: projectData.legacyPath,
-        newPath: projectData.newPath,
-        lastOpened: new Date()
-      };
+  async renameProject(id: string, name: string): Promise<Project> {
+    const project = await invoke<any>('rename_project', { projectId: id, name });
+    return fromBackend(project);
+  }
 
-      const existingProjects = await this.loadProjects();
-      const updatedProjects = [...existingProjects, newProject];
+  async exportProject(id: string, destination: string, includeSnapshots = true): Promise<ProjectBundleManifest> {
+    return await invoke<ProjectBundleManifest>('export_project', { projectId: id, destination, includeSnapshots });
+  }
 
-      await this.saveProjectsToFile(updatedProjects);
+  async previewBundle(bundlePath: string): Promise<ProjectBundlePreview> {
+    return await invoke<ProjectBundlePreview>('preview_project_bundle', { bundlePath });
+  }
 
-      return newProject;
-    } catch (error) {
-      throw error;
-    }
+  // Sem legacyPath/newPath, mantém os diretórios da máquina de origem;
+  // applySettings troca as configurações globais pelas do pacote (ver settingsChanges)
+  async importProject(
+    bundlePath: string,
+    options: { name?: string; legacyPath?: string; newPath?: string; applySettings?: boolean } = {}
+  ): Promise<Project> {
+    const project = await invoke<any>('import_project', { bundlePath, ...options });
+    return fromBackend(project);
   }
 
-  async saveProjectsToFile(projects: Project[]): Promise<void> {
-    try {
-      await this.ensureAppDataDir();
-      const filePath = await this.getProjectsFilePath();
-      const content = JSON.stringify(projects, null, 2);
-      await writeTextFile(filePath, content);
-    } catch (error) {
-      throw error;
-    }
+  async detectBuildSystems(id: string): Promise<ProjectBuildSystems> {
+    return await invoke<ProjectBuildSystems>('detect_build_systems', { projectId: id });
   }
 
-  async deleteProject(id: string): Promise<void> {
-    try {
-      const existingProjects = await this.loadProjects();
-      const updatedProjects = existingProjects.filter(project => project.id !== id);
-      await this.saveProjectsToFile(updatedProjects);
-    } catch (error) {
-      throw error;
-    }
+  async applyBuildPlan(id: string, side: 'legacy' | 'new', plan: BuildPlan): Promise<FullTestFromAI[]> {
+    return await invoke<FullTestFromAI[]>('apply_build_plan', { projectId: id, side, plan });
   }
 
-  async updateProject(updatedProject: Project): Promise<void> {
-    try {
-      const existingProjects = await this.loadProjects();
-      const updatedProjects = existingProjects.map(project =>
-        project.id === updatedProject.id ? updatedProject : project
-      );
-      await this.saveProjectsToFile(updatedProjects);
-    } catch (error) {
-      throw error;
-    }
+  async listBuildArtifacts(id: string): Promise<BuildArtifact[]> {
+    return await invoke<BuildArtifact[]>('list_build_artifacts', { projectId: id });
+  }
+
+  // Retorna os bytes liberados
+  async cleanBuildArtifacts(id: string): Promise<number> {
+    return await invoke<number>('clean_build_artifacts', { projectId: id });
+  }
+
+  async setToolchain(id: string, side: 'legacy' | 'new', toolchain: ToolchainConfig | null): Promise<Project> {
+    return await invoke<Project>('set_project_toolchain', { projectId: id, side, toolchain });
+  }
+
+  async setApiConfig(id: string, config: ApiConfig | null): Promise<Project> {
+    return await invoke<Project>('set_api_config', { projectId: id, config });
+  }
+
+  async setEncoding(id: string, side: 'legacy' | 'new', encoding: string | null): Promise<Project> {
+    return await invoke<Project>('set_project_encoding', { projectId: id, side, encoding });
+  }
+
+  async setComparator(id: string, comparator: Comparator | null): Promise<Project> {
+    return await invoke<Project>('set_project_comparator', { projectId: id, comparator });
+  }
+
+  async getSummary(id: string): Promise<ProjectSummary> {
+    return await invoke<ProjectSummary>('project_summary', { projectId: id });
+  }
+
+  async listEntryPointCandidates(id: string, side: 'legacy' | 'new'): Promise<EntryPointCandidate[]> {
+    return await invoke<EntryPointCandidate[]>('list_entry_point_candidates', { projectId: id, side });
+  }
+
+  async setEntryPoints(id: string, side: 'legacy' | 'new', entryPoints: EntryPoint[]): Promise<Project> {
+    return await invoke<Project>('set_entry_points', { projectId: id, side, entryPoints });
   }
 
   async getProjectById(id: string): Promise<Project | null> {
@@ -119,16 +120,8 @@ class ProjectService {
   }
 
   async updateLastOpened(id: string): Promise<void> {
-    try {
-      const existingProjects = await this.loadProjects();
-      const updatedProjects = existingProjects.map(project =>
-        project.id === id ? { ...project, lastOpened: new Date() } : project
-      );
-      await this.saveProjectsToFile(updatedProjects);
-    } catch (error) {
-      throw error;
-    }
+    await invoke('touch_project', { projectId: id });
   }
 }
 
-export default new ProjectService();
\ No newline at end of file
+export default new ProjectService();
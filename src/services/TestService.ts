@@ -1,76 +1,15 @@
-import { TestCase, TestExecutionResponse } from '../types';
-import { readTextFile, writeTextFile, exists, create, remove } from '@tauri-apps/plugin-fs';
-import { appDataDir, join } from '@tauri-apps/api/path';
+import { FuzzOptions, FuzzReport, MinimizeOptions, MinimizeReport, TestCase } from '../types';
+import { invoke } from '@tauri-apps/api/core';
 
+// Os testes (definições + último resultado) ficam em projects/<id>/tests.json, gerenciado pelo backend
 class TestService {
-  private async getTestsFilePath(projectId: string): Promise<string> {
-    const appDir = await appDataDir();
-    return await join(appDir, `tests-${projectId}.json`);
-  }
-
-  private async ensureAppDataDir(): Promise<void> {
-    try {
-      const appDir = await appDataDir();
-      const dirExists = await exists(appDir);
-      if (!dirExists) {
-        await create(appDir);
-      }
-    } catch (error) {
-      throw error;
-    }
-  }
-
-  async saveGeneratedTests(projectId: string, tests: TestCase[]): Promise<void> {
-    try {
-      await this.ensureAppDataDir();
-
-      const testsFilePath = await this.getTestsFilePath(projectId);
-      let existingTests: Record<string, TestCase> = {};
-
-      const fileExists = await exists(testsFilePath);
-      if (fileExists) {
-        const content = await readTextFile(testsFilePath);
-        if (content && content.trim().length > 0) {
-          existingTests = JSON.parse(content);
-        }
-      }
-
-      tests.forEach(test => {
-        existingTests[test.id] = {
-          ...test,
-          status: 'pending'
-        };
-      });
-
-      await writeTextFile(testsFilePath, JSON.stringify(existingTests, null, 2));
-
-    } catch (error) {
-      throw error;
-    }
-  }
-
   async getTestsByProjectId(projectId: string): Promise<TestCase[]> {
     return await this.getTestsByProject(projectId);
   }
 
   async getTestsByProject(projectId: string): Promise<TestCase[]> {
     try {
-      await this.ensureAppDataDir();
-      const testsFilePath = await this.getTestsFilePath(projectId);
-      const fileExists = await exists(testsFilePath);
-
-      if (!fileExists) {
-        return [];
-      }
-
-      const content = await readTextFile(testsFilePath);
-
-      if (!content || content.trim().length === 0) {
-        return [];
-      }
-
-      const tests: Record<string, TestCase> = JSON.parse(content);
-      return Object.values(tests);
+      return await invoke<TestCase[]>('list_test_cases', { projectId });
     } catch (error) {
       return [];
     }
@@ -86,100 +25,23 @@ class TestService {
     }
   }
 
-  
-  async updateTestResult(projectId: string, testId: string, result: {
-    status: TestCase['status'];
-    executionTime?: number;
-    legacyOutput?: string;
-    newOutput?: string;
-  }): Promise<void> {
-    try {
-      await this.ensureAppDataDir();
-      const testsFilePath = await this.getTestsFilePath(projectId);
-      let tests: Record<string, TestCase> = {};
-
-      const fileExists = await exists(testsFilePath);
-      if (fileExists) {
-        const content = await readTextFile(testsFilePath);
-        tests = JSON.parse(content);
-      }
-
-      if (tests[testId]) {
-        tests[testId] = {
-          ...tests[testId],
-          ...result
-        };
-
-        await writeTextFile(testsFilePath, JSON.stringify(tests, null, 2));
-      } else {
-        console.warn(`⚠️ Test ${testId} not found for updating`);
-      }
-    } catch (error) {
-      throw error;
-    }
-  }
-
-  async saveTestResult(projectId: string, test: TestCase): Promise<void> {
-    try {
-      await this.updateTestResult(projectId, test.id, {
-        status: test.status,
-        executionTime: test.executionTime,
-        legacyOutput: test.legacyOutput,
-        newOutput: test.newOutput
-      });
-    } catch (error) {
-      throw error;
-    }
-  }
-
-  async saveTestResults(projectId: string, executionResponse: TestExecutionResponse): Promise<void> {
-    try {
-      for (const test of executionResponse.testResults) {
-        await this.updateTestResult(projectId, test.id, {
-          status: test.status,
-          executionTime: test.executionTime,
-          legacyOutput: test.legacyOutput,
-          newOutput: test.newOutput
-        });
-      }
-    } catch (error) {
-      throw error;
-    }
-  }
-
-  
   async deleteTest(projectId: string, testId: string): Promise<void> {
-    try {
-      await this.ensureAppDataDir();
-
-      const testsFilePath = await this.getTestsFilePath(projectId);
-      let tests: Record<string, TestCase> = {};
-
-      const fileExists = await exists(testsFilePath);
-      if (fileExists) {
-        const content = await readTextFile(testsFilePath);
-        tests = JSON.parse(content);
-        delete tests[testId];
-        await writeTextFile(testsFilePath, JSON.stringify(tests, null, 2));
-      }
-    } catch (error) {
-      throw error;
-    }
+    await invoke('delete_full_test', { projectId, testId });
   }
 
   async deleteAllTestsFromProject(projectId: string): Promise<void> {
-    try {
-      await this.ensureAppDataDir();
+    await invoke('delete_all_tests', { projectId });
+  }
 
-      const testsFilePath = await this.getTestsFilePath(projectId);
+  // Varia args/stdin do teste e salva como novos testes as entradas em que legado e novo divergem
+  async fuzzTest(projectId: string, testId: string, options?: FuzzOptions): Promise<FuzzReport> {
+    return await invoke<FuzzReport>('fuzz_test', { projectId, testId, options: options ?? null });
+  }
 
-      if (await exists(testsFilePath)) {
-        await remove(testsFilePath);
-      }
-    } catch (error) {
-      throw error;
-    }
+  // Reduz args, stdin e fixtures de um teste que diverge e salva a reprodução mínima como novo teste
+  async minimizeTest(projectId: string, testId: string, options?: MinimizeOptions): Promise<MinimizeReport> {
+    return await invoke<MinimizeReport>('minimize_test', { projectId, testId, options: options ?? null });
   }
 }
 
-export default new TestService();
\ No newline at end of file
+export default new TestService();
@@ -3,7 +3,7 @@ export interface TestCase {
   name: string
   description: string
   fullCode?: string  // Código do teste gerado pela IA
-  status: "pending" | "running" | "passed" | "failed"
+  status: "pending" | "running" | "passed" | "failed" | "not_run"  // not_run: erro antes de rodar (build, snapshot ausente)
   legacyOutput?: string
   newOutput?: string
   executionTime?: number
@@ -17,6 +17,16 @@ export interface FullTestFromAI {
   fullCode: string
   expectedExitCode: number
   timeout?: number
+  args?: string[]                 // Argumentos explícitos (senão extraídos de fullCode)
+  stdin?: string                  // Entrada enviada ao stdin dos dois programas
+  interactive?: ExpectStep[]      // Roteiro para programas interativos (pseudo-terminal)
+  parameters?: TestParameters     // Roda uma instância por linha, trocando {{nome}} pelos valores
+  comparator?: Comparator         // Como as saídas são comparadas (padrão: texto)
+  entryPoint?: string             // Ponto de entrada fixado exercitado pelo teste
+  request?: HttpRequestSpec       // Requisição enviada aos dois servidores (projetos API)
+  pages?: PageStep[]              // Páginas visitadas nos dois apps (projetos Web)
+  trackedFiles?: string[]         // Arquivos/diretórios cujos efeitos entram no veredito
+  origin?: 'ai'                   // Gerado pela IA (substituído na próxima geração)
 
   // 🆕 Execução específica para cada projeto
   legacyExec: ExecutionInfo
@@ -32,6 +42,117 @@ export interface ExecutionInfo {
   workingDirectory: string        // Caminho completo para o diretório do projeto
 }
 
+// Fonte das linhas de um teste parametrizado (apenas uma). Instâncias recebem
+// IDs "<id>[1]", "<id>[2]"... e aparecem separadas nos resultados
+export interface TestParameters {
+  rows?: Record<string, string>[]
+  dataFile?: string               // CSV com cabeçalho, TSV ou JSON (array de objetos)
+  filesIn?: string                // Uma instância por arquivo: {{file}} e {{fileName}}
+}
+
+// Comparadores de saída estruturada
+export type Comparator =
+  | { type: "text" }
+  | { type: "json"; ignoreArrayOrder?: boolean; tolerance?: number }
+  | { type: "csv"; delimiter?: string; header?: boolean; ignoreColumnOrder?: boolean; ignoreRowOrder?: boolean; tolerance?: number }
+  | { type: "xml"; preserveWhitespace?: boolean }
+  // Recebe $1/$2 (diretórios legacy/new com stdout, stderr, exit_code, effects.json e files/)
+  // e responde pelo exit code (0 iguais, 1 diferentes) ou JSON {equal, diff?, differences?}
+  | { type: "external"; command: string; timeoutMs?: number }
+
+// Diferença em um caminho da saída ("$.itens[2].preco", "[3].total", "/pedido/item[2]/@id")
+export interface Difference {
+  path: string
+  legacy?: string | null          // Ausente quando só existe no novo
+  new?: string | null             // Ausente quando só existe no legado
+}
+
+export interface ComparisonResult {
+  comparator: string
+  outputsMatch: boolean
+  exitCodesMatch: boolean
+  differences: Difference[]
+  diff?: string                   // Texto devolvido pelo comparador externo
+  hexDiff?: string                // Dump hexadecimal em volta da 1ª diferença (saída binária)
+}
+
+// Saída de um lado; os bytes originais (base64) vêm quando diferem do texto UTF-8
+export interface SideOutput {
+  stdout: string
+  stderr: string
+  exitCode?: number | null
+  encoding?: string               // "UTF-8", "windows-1252", "IBM850"...
+  binary?: boolean
+  stdoutBytes?: string
+  stderrBytes?: string
+  stdoutCapture?: CaptureInfo     // Saída grande: stdout tem só a prévia
+  stderrCapture?: CaptureInfo
+  scriptFailure?: string          // Falha do roteiro interativo; reprova o teste
+}
+
+export interface CaptureInfo {
+  size: number
+  sha256: string
+  file: string
+  truncated?: boolean             // Passou de maxOutputBytes; o arquivo tem só o início
+}
+
+// Passo de roteiro interativo: a transcrição completa vira a saída comparada
+export type ExpectStep =
+  | { action: "expect"; pattern: string; timeoutMs?: number }   // Regex esperada na saída
+  | { action: "send"; text: string }
+  | { action: "sendLine"; text: string }                        // Texto + Enter
+  | { action: "sendControl"; key: string }                      // "c", "d", "enter", "up"...
+
+// Projetos do tipo API
+export interface HttpRequestSpec {
+  method: string
+  path: string                    // Com query string, sem host/porta
+  headers?: Record<string, string>
+  body?: string | null
+}
+
+export interface HttpCapture {
+  status: number
+  headers: Record<string, string>
+  body: string
+  elapsedMs: number
+}
+
+// Projetos do tipo Web
+export interface PageStep {
+  method?: string                 // Padrão: GET
+  path: string
+  form?: Record<string, string>   // Campos ocultos da página anterior vão junto
+  headers?: Record<string, string>
+}
+
+export interface PageCapture {
+  path: string
+  finalPath: string
+  status: number
+  title?: string
+  text: string
+}
+
+export interface ServerConfig {
+  command: string                 // Recebe a porta em $PORT
+  workingDirectory?: string       // Relativo à raiz do lado
+  port: number
+  readiness?: {
+    path?: string
+    timeoutMs?: number
+    expectedStatus?: number
+  }
+}
+
+export interface ApiConfig {
+  legacy: ServerConfig
+  new: ServerConfig
+  ignoreHeaders?: string[]
+  ignoreAttributes?: string[]     // Atributos HTML ignorados (projetos Web)
+}
+
 // Execução de Testes (Runtime)
 export interface TestExecutionResponse {
   projectId: string
@@ -39,6 +160,21 @@ export interface TestExecutionResponse {
   testResults: TestCase[]
   executedAt: string
   totalExecutionTime: number
+  missingTools?: string[]
+}
+
+export interface ToolStatus {
+  name: string
+  side: 'legacy' | 'new'
+  requiredBy: string[]
+  present: boolean
+  path?: string | null
+  version?: string | null
+}
+
+export interface ToolchainReport {
+  tools: ToolStatus[]
+  warnings: string[]
 }
 
 
@@ -49,6 +185,44 @@ export interface Project{
     legacyPath?: string
     newPath?: string
     lastOpened?: Date
+    legacyToolchain?: ToolchainConfig | null
+    newToolchain?: ToolchainConfig | null
+    legacyEntryPoints?: EntryPoint[]
+    newEntryPoints?: EntryPoint[]
+    apiConfig?: ApiConfig | null
+    comparator?: Comparator | null  // Padrão dos testes sem comparador próprio
+    legacyEncoding?: string | null  // "auto" (padrão), "ISO-8859-1", "CP850"...
+    newEncoding?: string | null
+}
+
+export interface LanguageStats {
+  language: string
+  files: number
+  lines: number
+  codeLines: number
+}
+
+export interface ProjectSummary {
+  legacy: LanguageStats[]
+  new: LanguageStats[]
+}
+
+export interface EntryPoint {
+  name: string
+  path: string                    // Relativo à raiz do lado
+}
+
+export interface EntryPointCandidate {
+  path: string
+  relativePath: string
+  score: number
+  reasons: string[]
+}
+
+export interface ToolchainConfig {
+  tools?: Record<string, string>
+  extraPath?: string[]
+  compilerFlags?: Record<string, string[]>
 }
 
 export interface NewProjectData {
@@ -61,6 +235,57 @@ export interface NewProjectData {
 
 export type ProjectType = 'API' | 'Web' | 'Terminal';
 
+export interface BuildPlan {
+  kind: string
+  manifest: string
+  name: string
+  buildCommand?: string | null
+  runCommand: string
+}
+
+export interface BuildArtifact {
+  side: 'legacy' | 'new'
+  source: string
+  artifact: string
+  sourceHash?: string | null
+  builtAt: string
+}
+
+export interface ProjectBuildSystems {
+  legacy: BuildPlan[]
+  new: BuildPlan[]
+  warnings: string[]
+}
+
+export interface ProjectBundleManifest {
+  schemaVersion: number
+  format: string
+  exportedAt: string
+  project: {
+    id: string
+    name: string
+    type: ProjectType
+    legacyPath: string
+    newPath: string
+  }
+  settings: AppSettings
+  testCount: number
+  includesSnapshots: boolean
+}
+
+// Manifesto com o que mudaria nas configurações globais se as do pacote forem aplicadas
+export interface ProjectBundlePreview extends ProjectBundleManifest {
+  settingsChanges: string[]       // Ex.: "executionRetention: 50 → 100"
+}
+
+// Configurações globais (`settings.json`)
+export interface AppSettings {
+  executionRetention: number
+  captureInlineBytes?: number     // Acima disso a saída vai para arquivo e é comparada por hash
+  maxOutputBytes?: number         // Quanto de cada saída é gravado em arquivo
+  previewBytes?: number           // Tamanho das prévias em legacyOutput/newOutput
+}
+
 export interface Execution {
   id: string
   projectId: string
@@ -69,15 +294,81 @@ export interface Execution {
   totalTests: number
   passedTests: number
   failedTests: number
+  notRunTests?: number            // Testes que não chegaram a rodar
   totalExecutionTime: number
   status: 'passed' | 'failed'
+  finishedAt?: string
+  mode?: 'live' | 'snapshot'
+}
+
+// Resultado de um teste no histórico, com os detalhes de cada lado
+export interface TestRunResult extends TestCase {
+  legacyRun?: SideOutput
+  newRun?: SideOutput
+  legacyHttp?: HttpCapture
+  newHttp?: HttpCapture
+  legacyPages?: PageCapture[]
+  newPages?: PageCapture[]
+  differences?: string[]
+  comparison?: ComparisonResult
 }
 
 export interface ExecutionResult extends Execution {
-  testResults: TestCase[]
+  testResults: TestRunResult[]
+  toolVersions?: Record<string, string>
+  missingTools?: string[]
 }
 
 export interface ExecutionHistory {
   projectId: string
   executions: Execution[]
-}
\ No newline at end of file
+}
+// Opções do fuzzing diferencial de um teste
+export interface FuzzOptions {
+  iterations?: number             // Entradas geradas (padrão 100)
+  seed?: number                   // Repete uma sessão anterior
+  maxDivergences?: number         // Para depois de salvar N divergências (padrão 10)
+  timeoutMs?: number              // Limite de cada execução (padrão: timeout do teste ou 10s)
+}
+
+export interface FuzzDivergence {
+  args: string[]
+  stdin?: string
+  differences: string[]
+  legacyOutput: string
+  newOutput: string
+  savedAs?: string                // ID do teste criado com a entrada
+}
+
+export interface FuzzReport {
+  testId: string
+  seed: number
+  iterations: number
+  divergences: FuzzDivergence[]
+}
+
+// Opções da minimização (delta debugging) de um teste que diverge
+export interface MinimizeOptions {
+  maxRuns?: number                // Limite de execuções (padrão 500)
+  timeoutMs?: number              // Limite de cada execução (padrão: timeout do teste ou 10s)
+}
+
+export interface MinimizedFixture {
+  original: string
+  minimized: string               // Argumento da cópia reduzida: ${FIXTURES_DIR}/<uuid>/<nome> (fixtures do projeto)
+  linesBefore: number
+  linesAfter: number
+}
+
+export interface MinimizeReport {
+  testId: string
+  savedAs: string                 // ID do teste criado com a reprodução mínima
+  args: string[]
+  stdin?: string
+  argsBefore: number
+  stdinLinesBefore: number
+  stdinLinesAfter: number
+  fixtures?: MinimizedFixture[]
+  differences: string[]
+  runs: number
+}